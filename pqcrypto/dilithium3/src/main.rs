@@ -6,10 +6,90 @@
 // 1. Generate a public-secret key pair
 // 2. Sign a message using the private key
 // 3. Verify the signature using the public key
+// 4. Convert keys to and from the liboqs toolkit's byte representation
 
 // Import necessary cryptographic libraries from pqcrypto-dilithium and pqcrypto-traits
 use pqcrypto_dilithium::dilithium3;
-use pqcrypto_traits::sign::{PublicKey, SignedMessage};
+use pqcrypto_traits::sign::{PublicKey, SecretKey, SignedMessage};
+use std::fmt;
+
+// === Interop with the liboqs toolkit ===
+// liboqs "Dilithium3" and pqcrypto-dilithium's `dilithium3` both implement the round 3.1
+// Dilithium3 specification, and both store keys in the packed encoding that the
+// specification defines (public key: rho || t1, secret key: rho || key || tr || s1 || s2 || t0).
+// The byte layouts are therefore identical and conversion is a length-checked copy.
+// ML-DSA-65 keys from liboqs are NOT compatible: FIPS 204 changed the secret key layout
+// and signing, so they are rejected by the length check or fail verification.
+
+#[derive(Debug)]
+enum ConvertError {
+    /// The input does not have the size of a Dilithium3 key
+    LengthMismatch { expected: usize, actual: usize },
+    /// The input is shorter than the smallest possible signed message
+    TooShort { minimum: usize, actual: usize },
+    /// pqcrypto rejected the bytes
+    Rejected(pqcrypto_traits::Error),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertError::LengthMismatch { expected, actual } => {
+                write!(f, "expected a {}-byte Dilithium3 key, got {} bytes", expected, actual)
+            }
+            ConvertError::TooShort { minimum, actual } => {
+                write!(f, "a Dilithium3 signed message is at least {} bytes, got {}", minimum, actual)
+            }
+            ConvertError::Rejected(e) => write!(f, "bytes rejected: {}", e),
+        }
+    }
+}
+
+fn check_len(bytes: &[u8], expected: usize) -> Result<(), ConvertError> {
+    if bytes.len() != expected {
+        return Err(ConvertError::LengthMismatch { expected, actual: bytes.len() });
+    }
+    Ok(())
+}
+
+/// Converts a public key exported by the liboqs toolkit (`oqs::sig::PublicKey` bytes)
+fn oqs_pk_to_pqcrypto(bytes: &[u8]) -> Result<dilithium3::PublicKey, ConvertError> {
+    check_len(bytes, dilithium3::public_key_bytes())?;
+    dilithium3::PublicKey::from_bytes(bytes).map_err(ConvertError::Rejected)
+}
+
+/// Converts a secret key exported by the liboqs toolkit (`oqs::sig::SecretKey` bytes)
+fn oqs_sk_to_pqcrypto(bytes: &[u8]) -> Result<dilithium3::SecretKey, ConvertError> {
+    check_len(bytes, dilithium3::secret_key_bytes())?;
+    dilithium3::SecretKey::from_bytes(bytes).map_err(ConvertError::Rejected)
+}
+
+/// Returns public key bytes that liboqs accepts via `Sig::public_key_from_bytes`
+fn pqcrypto_pk_to_oqs(pk: &dilithium3::PublicKey) -> Vec<u8> {
+    pk.as_bytes().to_vec()
+}
+
+/// Returns secret key bytes that liboqs accepts via `Sig::secret_key_from_bytes`
+fn pqcrypto_sk_to_oqs(sk: &dilithium3::SecretKey) -> Vec<u8> {
+    sk.as_bytes().to_vec()
+}
+
+/// Validated construction from untrusted bytes, so length checks live in one place
+trait SignedMessageExt: Sized {
+    fn from_validated_bytes(bytes: &[u8]) -> Result<Self, ConvertError>;
+}
+
+impl SignedMessageExt for dilithium3::SignedMessage {
+    /// A signed message is the signature followed by the message, which may be empty, so
+    /// anything shorter than a signature cannot be one
+    fn from_validated_bytes(bytes: &[u8]) -> Result<Self, ConvertError> {
+        let minimum = dilithium3::signature_bytes();
+        if bytes.len() < minimum {
+            return Err(ConvertError::TooShort { minimum, actual: bytes.len() });
+        }
+        <dilithium3::SignedMessage as SignedMessage>::from_bytes(bytes).map_err(ConvertError::Rejected)
+    }
+}
 
 fn main() {
     // === Step 1: Generate a Key Pair ===
@@ -30,9 +110,20 @@ fn main() {
     println!("Signed Message Length: {} bytes", signed_message.as_bytes().len());
 
     // === Step 4: Verify the Signed Message ===
-    // Verification checks if the signature is valid using the corresponding public key.
+    // The receiver gets raw bytes, which are validated while rebuilding the SignedMessage.
+    // Verification then checks if the signature is valid using the corresponding public key.
     // If verification succeeds, it returns the original message.
-    match dilithium3::open(&signed_message, &pk) {
+    let received = match dilithium3::SignedMessage::from_validated_bytes(signed_message.as_bytes()) {
+        Ok(received) => received,
+        Err(e) => {
+            println!("Received signed message rejected: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = dilithium3::SignedMessage::from_validated_bytes(&signed_message.as_bytes()[..16]) {
+        println!("Truncated signed message rejected: {}", e);
+    }
+    match dilithium3::open(&received, &pk) {
         Ok(valid_message) => {
             println!("Verified successfully: {:?}", String::from_utf8_lossy(&valid_message));
         },
@@ -40,4 +131,24 @@ fn main() {
             println!("Signature verification failed!");
         }
     }
+
+    // === Step 5: Round-Trip Keys Through the liboqs Representation ===
+    // The exported bytes are what the liboqs toolkit stores; converting them back must yield
+    // keys that still sign and verify.
+    let oqs_pk = pqcrypto_pk_to_oqs(&pk);
+    let oqs_sk = pqcrypto_sk_to_oqs(&sk);
+    match (oqs_pk_to_pqcrypto(&oqs_pk), oqs_sk_to_pqcrypto(&oqs_sk)) {
+        (Ok(converted_pk), Ok(converted_sk)) => {
+            let resigned = dilithium3::sign(message, &converted_sk);
+            match dilithium3::open(&resigned, &converted_pk) {
+                Ok(_) => println!("Keys converted to and from liboqs bytes still sign and verify."),
+                Err(_) => println!("Converted keys failed to verify!"),
+            }
+        }
+        (Err(e), _) | (_, Err(e)) => println!("Key conversion failed: {}", e),
+    }
+    match oqs_pk_to_pqcrypto(&oqs_pk[..oqs_pk.len() - 1]) {
+        Err(e) => println!("Truncated key rejected: {}", e),
+        Ok(_) => println!("Truncated key was accepted!"),
+    }
 }
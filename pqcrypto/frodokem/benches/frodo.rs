@@ -0,0 +1,48 @@
+//! # FrodoKEM variant comparison
+//!
+//! Times key generation, encapsulation and decapsulation for every FrodoKEM variant, so the
+//! AES and SHAKE flavours of each parameter set can be compared on the same machine. AES is
+//! usually faster on CPUs with AES instructions and SHAKE faster without them.
+//!
+//! Run with `cargo bench --bench frodo`, or `cargo bench --bench frodo -- 976` for one
+//! parameter set. Each variant is its own benchmark group, named after the algorithm.
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use kem_scheme::{
+    FrodoKem640Aes, FrodoKem640Shake, FrodoKem976Aes, FrodoKem976Shake, FrodoKem1344Aes, FrodoKem1344Shake,
+    KemScheme,
+};
+use pqcrypto_traits::kem::SharedSecret;
+
+/// Benchmarks one variant, reusing a key pair and ciphertext generated before timing starts
+fn bench_variant<K: KemScheme>(c: &mut Criterion) {
+    let (public_key, secret_key) = K::keypair();
+    let (shared_secret, ciphertext) = K::encapsulate(&public_key);
+    assert_eq!(
+        K::decapsulate(&ciphertext, &secret_key).as_bytes(),
+        shared_secret.as_bytes(),
+        "{} key pair does not round-trip",
+        K::NAME
+    );
+
+    let mut group = c.benchmark_group(K::NAME);
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("keygen", |b| b.iter(K::keypair));
+    group.bench_function("encapsulate", |b| b.iter(|| K::encapsulate(black_box(&public_key))));
+    group.bench_function("decapsulate", |b| {
+        b.iter(|| K::decapsulate(black_box(&ciphertext), black_box(&secret_key)))
+    });
+    group.finish();
+}
+
+fn bench_frodo(c: &mut Criterion) {
+    bench_variant::<FrodoKem640Aes>(c);
+    bench_variant::<FrodoKem640Shake>(c);
+    bench_variant::<FrodoKem976Aes>(c);
+    bench_variant::<FrodoKem976Shake>(c);
+    bench_variant::<FrodoKem1344Aes>(c);
+    bench_variant::<FrodoKem1344Shake>(c);
+}
+
+criterion_group!(benches, bench_frodo);
+criterion_main!(benches);
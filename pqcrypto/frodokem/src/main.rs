@@ -1,7 +1,7 @@
 //! ================================================================
 //! 🧪 Post-Quantum Cryptography: FrodoKEM-976-AES 
 //!
-//! 🔐 Algorithm: FrodoKEM-976-AES
+//! 🔐 Algorithm: FrodoKEM-976-AES by default, any FrodoKEM variant with `--variant`
 //! 📚 Standardization: NIST PQC Round 3 Alternate Finalist
 //! 🧮 Type: Lattice-based Key Encapsulation Mechanism (KEM)
 //!
@@ -14,6 +14,26 @@
 //!     3. Ciphertext generation
 //!     4. Shared secret decapsulation
 //!     5. Byte comparison to verify secure key agreement
+//!     6. HMAC-based key confirmation between the two parties
+//!     7. Parsing a ciphertext from a partial network buffer
+//!     8. Deriving symmetric keys from the shared secret with HKDF
+//!     9. Running the exchange through the shared `KemScheme` interface
+//!    10. Authenticating the ciphertext with a Dilithium3 signature
+//!    11. Encrypting a file to the receiver's public key with AES-256-GCM
+//!
+//! 🔀 Pass `--variant <name>` (e.g. `--variant frodokem976shake`) to run the same steps with
+//!    another FrodoKEM parameter set; AES and SHAKE differ only in how the public matrix is
+//!    generated. `cargo bench --bench frodo` times every variant side by side.
+//!
+//! 📊 Pass `--mem-report` to print the peak heap usage of each operation.
+//!
+//! 📦 Instead of the example, the binary can encrypt files with keys kept in bundle files,
+//!    each one tagged container instead of separate key and ciphertext files:
+//!     - `keygen --bundle keys.qkb` writes the public and secret key
+//!     - `seal <input> --from-bundle keys.qkb --bundle sealed.qkb` writes the KEM ciphertext,
+//!       nonce and encrypted file
+//!     - `open sealed.qkb --from-bundle keys.qkb --out <output>` decrypts it again
+//!    A bundle missing a section the command needs is rejected. `--variant` applies here too.
 //!
 //! ⚠️ Important:
 //!     - Use compatible crate versions (see Cargo.toml)
@@ -25,23 +45,587 @@
 //!
 //! 📦 Crates required:
 //!     - pqcrypto-frodo
+//!     - pqcrypto-dilithium (authenticated KEM)
 //!     - pqcrypto-traits
 //!     - hex
+//!     - aes-gcm, rand (file encryption)
+//!     - kem_kdf (../kem-kdf)
+//!     - kem_scheme (../kem-scheme, feature "frodo")
+//!     - heap_stats (../heap-stats, `--mem-report`)
 //! ================================================================
 
-use pqcrypto_frodo::frodokem976aes;
 use pqcrypto_traits::kem::{Ciphertext, PublicKey, SecretKey, SharedSecret};
+use pqcrypto_traits::sign::DetachedSignature;
+use pqcrypto_dilithium::dilithium3;
 use hex;
+use kem_kdf::{Role, confirm_key, ct_eq, verify_key_confirmation};
+use kem_scheme::{KemScheme, KemSizes};
+use heap_stats::{CountingAllocator, measure_peak_heap};
+use aes_gcm::{Aes256Gcm, Nonce, aead::{Aead, KeyInit, Payload}};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use std::path::Path;
+
+// ================================================================
+// Heap accounting for `--mem-report`
+// heap_stats' counting allocator records current and peak heap
+// usage so each operation's peak can be reported.
+// ================================================================
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Error returned when reading a ciphertext from a network buffer
+#[derive(Debug)]
+enum FrameError {
+    /// The buffer is too short; `needed` more bytes must arrive before it can be parsed
+    Incomplete { needed: usize },
+    /// The buffer holds enough bytes but they do not form a valid ciphertext
+    Corrupt,
+}
+
+impl std::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FrameError::Incomplete { needed } => write!(f, "incomplete ciphertext frame: {} more bytes needed", needed),
+            FrameError::Corrupt => write!(f, "corrupt ciphertext frame"),
+        }
+    }
+}
+
+/// Parses a `K` ciphertext off the front of a possibly partial buffer, returning it and the
+/// unread tail of `bytes`
+fn ciphertext_from_frame<K: KemScheme>(bytes: &[u8]) -> Result<(K::Ciphertext, &[u8]), FrameError> {
+    let len = K::sizes().ciphertext;
+    if bytes.len() < len {
+        return Err(FrameError::Incomplete { needed: len - bytes.len() });
+    }
+    let (frame, tail) = bytes.split_at(len);
+    let ct = K::Ciphertext::from_bytes(frame).map_err(|_| FrameError::Corrupt)?;
+    Ok((ct, tail))
+}
+
+/// Domain separation prefixed to the signed message, so these signatures cannot be replayed
+/// as signatures over anything else
+const SIGNED_CIPHERTEXT_CONTEXT: &[u8] = b"Quantova authenticated KEM ciphertext v2";
+
+/// How long after signing a ciphertext is still accepted, in seconds
+const SIGNED_CIPHERTEXT_MAX_AGE_SECS: u64 = 300;
+
+/// Clock skew tolerated for signatures that appear to come from the future, in seconds
+const SIGNED_CIPHERTEXT_MAX_SKEW_SECS: u64 = 30;
+
+/// Error returned when a signed ciphertext fails verification
+#[derive(Debug)]
+enum SignedCiphertextError {
+    /// The signature does not cover this ciphertext, recipient and timestamp under the
+    /// expected signer's key
+    BadSignature,
+    /// The signature is older than `SIGNED_CIPHERTEXT_MAX_AGE_SECS`, or dated in the future
+    Stale { signed_at: u64, now: u64 },
+}
+
+impl std::fmt::Display for SignedCiphertextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignedCiphertextError::BadSignature => write!(
+                f,
+                "ciphertext signature is invalid: not sent by the expected signer, not addressed to this recipient or modified in transit"
+            ),
+            SignedCiphertextError::Stale { signed_at, now } => {
+                write!(f, "ciphertext was signed at {} but it is now {}: outside the accepted window", signed_at, now)
+            }
+        }
+    }
+}
+
+/// A KEM ciphertext with the sender's Dilithium3 signature over it
+struct SignedCiphertext<K: KemScheme> {
+    ciphertext: K::Ciphertext,
+    /// Seconds since the Unix epoch when the sender signed
+    signed_at: u64,
+    signature: dilithium3::DetachedSignature,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// The signed message: context || KEM name || recipient public key || timestamp || ciphertext,
+/// with every variable-length field prefixed by its 4-byte big-endian length. Binding the
+/// recipient stops a signed ciphertext from being redirected to another key, and the
+/// timestamp bounds how long it can be replayed.
+fn signed_ciphertext_message<K: KemScheme>(recipient_pk: &K::PublicKey, signed_at: u64, ciphertext: &K::Ciphertext) -> Vec<u8> {
+    let mut message = SIGNED_CIPHERTEXT_CONTEXT.to_vec();
+    for field in [K::NAME.as_bytes(), recipient_pk.as_bytes()] {
+        message.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        message.extend_from_slice(field);
+    }
+    message.extend_from_slice(&signed_at.to_be_bytes());
+    message.extend_from_slice(&(ciphertext.as_bytes().len() as u32).to_be_bytes());
+    message.extend_from_slice(ciphertext.as_bytes());
+    message
+}
+
+/// Encapsulates to `recipient_pk` and signs the ciphertext, the recipient's key and the
+/// current time with the sender's Dilithium3 key, returning the sender's shared secret and
+/// the signed ciphertext to send
+fn encapsulate_signed<K: KemScheme>(
+    recipient_pk: &K::PublicKey,
+    signer_sk: &dilithium3::SecretKey,
+) -> (K::SharedSecret, SignedCiphertext<K>) {
+    let (shared_secret, ciphertext) = K::encapsulate(recipient_pk);
+    let signed_at = unix_now();
+    let signature = dilithium3::detached_sign(&signed_ciphertext_message::<K>(recipient_pk, signed_at, &ciphertext), signer_sk);
+    (shared_secret, SignedCiphertext { ciphertext, signed_at, signature })
+}
+
+/// Verifies the sender's signature over the ciphertext, our own public key and the
+/// timestamp, and only then decapsulates. An attacker who swaps in their own ciphertext
+/// cannot produce a valid signature, a ciphertext signed for someone else does not verify
+/// against `recipient_pk`, and one signed outside the accepted window is refused, so the
+/// recipient never derives a secret the attacker knows.
+fn decapsulate_verified<K: KemScheme>(
+    signed: &SignedCiphertext<K>,
+    recipient_pk: &K::PublicKey,
+    sk: &K::SecretKey,
+    signer_pk: &dilithium3::PublicKey,
+) -> Result<K::SharedSecret, SignedCiphertextError> {
+    let message = signed_ciphertext_message::<K>(recipient_pk, signed.signed_at, &signed.ciphertext);
+    dilithium3::verify_detached_signature(&signed.signature, &message, signer_pk)
+        .map_err(|_| SignedCiphertextError::BadSignature)?;
+    let now = unix_now();
+    if now.saturating_sub(signed.signed_at) > SIGNED_CIPHERTEXT_MAX_AGE_SECS
+        || signed.signed_at.saturating_sub(now) > SIGNED_CIPHERTEXT_MAX_SKEW_SECS
+    {
+        return Err(SignedCiphertextError::Stale { signed_at: signed.signed_at, now });
+    }
+    Ok(K::decapsulate(&signed.ciphertext, sk))
+}
+
+// Encrypted file container, version 1:
+//   magic (8) || version (1) || name length (1) || KEM name || KEM ciphertext length (4, BE)
+//   || KEM ciphertext || nonce (12) || AES-256-GCM output
+// Everything before the AEAD output is authenticated as associated data.
+const FILE_CONTAINER_MAGIC: &[u8] = b"QKEMFILE";
+const FILE_CONTAINER_VERSION: u8 = 1;
+const FILE_CONTAINER_NONCE_LEN: usize = 12;
+/// HKDF label for the file encryption key
+const FILE_KEY_INFO: &[u8] = b"Quantova FrodoKEM file encryption key";
+
+/// Error returned when sealing or opening an encrypted file
+#[derive(Debug)]
+enum FileContainerError {
+    Io(std::io::Error),
+    /// The file is not a container or is cut short
+    Malformed,
+    /// The container was written in a format this reader does not understand
+    UnsupportedVersion { got: u8, max_supported: u8 },
+    /// The container was sealed with another KEM than the one opening it
+    AlgorithmMismatch { expected: &'static str, found: String },
+    /// The AEAD tag did not verify: wrong secret key or modified container
+    Decryption,
+    /// A bundle lacks a section the requested operation needs
+    MissingSection(&'static str),
+}
+
+impl std::fmt::Display for FileContainerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileContainerError::Io(e) => write!(f, "{}", e),
+            FileContainerError::Malformed => write!(f, "not a valid encrypted file container"),
+            FileContainerError::UnsupportedVersion { got, max_supported } => write!(
+                f,
+                "unsupported file container version {} (this build reads up to version {})",
+                got, max_supported
+            ),
+            FileContainerError::AlgorithmMismatch { expected, found } => {
+                write!(f, "file was sealed with {}, expected {}", found, expected)
+            }
+            FileContainerError::Decryption => write!(f, "decryption failed: wrong key or modified file"),
+            FileContainerError::MissingSection(section) => write!(f, "bundle has no {} section", section),
+        }
+    }
+}
+
+impl From<std::io::Error> for FileContainerError {
+    fn from(e: std::io::Error) -> Self {
+        FileContainerError::Io(e)
+    }
+}
+
+/// Derives the AES-256-GCM file key from a KEM shared secret with HKDF-SHA256
+fn file_cipher(shared_secret: &[u8]) -> Aes256Gcm {
+    let key = kem_kdf::derive_key(shared_secret, FILE_KEY_INFO, 32);
+    Aes256Gcm::new_from_slice(&key).expect("File key is 32 bytes")
+}
+
+/// Encrypts the file at `in_path` to `recipient_pk` and writes the container to `out_path`.
+/// Each call encapsulates a fresh shared secret, so every file gets its own key.
+fn seal_file<K: KemScheme>(recipient_pk: &K::PublicKey, in_path: &Path, out_path: &Path) -> Result<(), FileContainerError> {
+    let plaintext = std::fs::read(in_path)?;
+    let (shared_secret, kem_ciphertext) = K::encapsulate(recipient_pk);
+    let mut nonce = [0u8; FILE_CONTAINER_NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let mut container = FILE_CONTAINER_MAGIC.to_vec();
+    container.push(FILE_CONTAINER_VERSION);
+    container.push(K::NAME.len() as u8);
+    container.extend_from_slice(K::NAME.as_bytes());
+    container.extend_from_slice(&(kem_ciphertext.as_bytes().len() as u32).to_be_bytes());
+    container.extend_from_slice(kem_ciphertext.as_bytes());
+    container.extend_from_slice(&nonce);
+    let sealed = file_cipher(shared_secret.as_bytes())
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &container })
+        .expect("AES-GCM encryption cannot fail for in-memory payloads");
+    container.extend_from_slice(&sealed);
+    std::fs::write(out_path, container)?;
+    Ok(())
+}
+
+/// Opens a container written by `seal_file` with the recipient's secret key and writes the
+/// plaintext to `out_path`. Nothing is written unless the whole file authenticates, and a
+/// container of an unknown version is rejected before the rest of it is parsed.
+fn open_file<K: KemScheme>(sk: &K::SecretKey, in_path: &Path, out_path: &Path) -> Result<(), FileContainerError> {
+    let container = std::fs::read(in_path)?;
+    let rest = container.strip_prefix(FILE_CONTAINER_MAGIC).ok_or(FileContainerError::Malformed)?;
+    let (&version, rest) = rest.split_first().ok_or(FileContainerError::Malformed)?;
+    let plaintext = match version {
+        1 => open_file_v1::<K>(sk, &container, rest)?,
+        got => return Err(FileContainerError::UnsupportedVersion { got, max_supported: FILE_CONTAINER_VERSION }),
+    };
+    std::fs::write(out_path, plaintext)?;
+    Ok(())
+}
+
+/// Parses and decrypts the body of a version 1 container; `rest` follows the version byte
+fn open_file_v1<K: KemScheme>(sk: &K::SecretKey, container: &[u8], rest: &[u8]) -> Result<Vec<u8>, FileContainerError> {
+    let (&name_len, rest) = rest.split_first().ok_or(FileContainerError::Malformed)?;
+    let (name, rest) = rest.split_at_checked(name_len as usize).ok_or(FileContainerError::Malformed)?;
+    if name != K::NAME.as_bytes() {
+        return Err(FileContainerError::AlgorithmMismatch {
+            expected: K::NAME,
+            found: String::from_utf8_lossy(name).into_owned(),
+        });
+    }
+    let (ct_len, rest) = rest.split_at_checked(4).ok_or(FileContainerError::Malformed)?;
+    let ct_len = u32::from_be_bytes(ct_len.try_into().expect("Split off 4 bytes")) as usize;
+    if ct_len != K::sizes().ciphertext {
+        return Err(FileContainerError::Malformed);
+    }
+    let (kem_ciphertext, rest) = rest.split_at_checked(ct_len).ok_or(FileContainerError::Malformed)?;
+    let (nonce, sealed) = rest.split_at_checked(FILE_CONTAINER_NONCE_LEN).ok_or(FileContainerError::Malformed)?;
+    let header = &container[..container.len() - sealed.len()];
+
+    let kem_ciphertext = K::Ciphertext::from_bytes(kem_ciphertext).map_err(|_| FileContainerError::Malformed)?;
+    let shared_secret = K::decapsulate(&kem_ciphertext, sk);
+    file_cipher(shared_secret.as_bytes())
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: sealed, aad: header })
+        .map_err(|_| FileContainerError::Decryption)
+}
+
+// Bundle file, version 1:
+//   magic (8) || version (1) || name length (1) || KEM name || sections
+// Each section is tag (1) || length (4, BE) || bytes. Sections are written in tag order and
+// each tag appears at most once. `keygen` writes the public and secret key, `seal` the KEM
+// ciphertext, the nonce and the AES-256-GCM output, whose associated data is the bundle
+// encoded without it.
+const BUNDLE_MAGIC: &[u8] = b"QKEMBNDL";
+const BUNDLE_VERSION: u8 = 1;
+const BUNDLE_SECTION_NAMES: [&str; 5] = ["public key", "secret key", "ciphertext", "nonce", "payload"];
+
+/// Artifacts held in a bundle file, indexed by section tag minus one
+#[derive(Debug, Default)]
+struct Bundle {
+    sections: [Option<Vec<u8>>; 5],
+}
+
+impl Bundle {
+    const PUBLIC_KEY: usize = 0;
+    const SECRET_KEY: usize = 1;
+    const CIPHERTEXT: usize = 2;
+    const NONCE: usize = 3;
+    const PAYLOAD: usize = 4;
+
+    fn with(mut self, section: usize, bytes: &[u8]) -> Self {
+        self.sections[section] = Some(bytes.to_vec());
+        self
+    }
+
+    /// Returns a section the caller's operation cannot do without
+    fn require(&self, section: usize) -> Result<&[u8], FileContainerError> {
+        self.sections[section].as_deref().ok_or(FileContainerError::MissingSection(BUNDLE_SECTION_NAMES[section]))
+    }
+
+    fn encode<K: KemScheme>(&self) -> Vec<u8> {
+        let mut bundle = BUNDLE_MAGIC.to_vec();
+        bundle.push(BUNDLE_VERSION);
+        bundle.push(K::NAME.len() as u8);
+        bundle.extend_from_slice(K::NAME.as_bytes());
+        for (index, bytes) in self.sections.iter().enumerate() {
+            if let Some(bytes) = bytes {
+                bundle.push(index as u8 + 1);
+                bundle.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+                bundle.extend_from_slice(bytes);
+            }
+        }
+        bundle
+    }
+
+    /// Parses a bundle written for `K`. Unknown, repeated or out-of-order sections and
+    /// trailing bytes are rejected as malformed.
+    fn decode<K: KemScheme>(bytes: &[u8]) -> Result<Self, FileContainerError> {
+        let rest = bytes.strip_prefix(BUNDLE_MAGIC).ok_or(FileContainerError::Malformed)?;
+        let (&version, rest) = rest.split_first().ok_or(FileContainerError::Malformed)?;
+        if version != BUNDLE_VERSION {
+            return Err(FileContainerError::UnsupportedVersion { got: version, max_supported: BUNDLE_VERSION });
+        }
+        let (&name_len, rest) = rest.split_first().ok_or(FileContainerError::Malformed)?;
+        let (name, mut rest) = rest.split_at_checked(name_len as usize).ok_or(FileContainerError::Malformed)?;
+        if name != K::NAME.as_bytes() {
+            return Err(FileContainerError::AlgorithmMismatch {
+                expected: K::NAME,
+                found: String::from_utf8_lossy(name).into_owned(),
+            });
+        }
+        let mut bundle = Bundle::default();
+        let mut next_index = 0;
+        while let Some((&tag, after_tag)) = rest.split_first() {
+            let index = (tag as usize).checked_sub(1).filter(|&index| index >= next_index && index < bundle.sections.len());
+            let index = index.ok_or(FileContainerError::Malformed)?;
+            let (len, after_len) = after_tag.split_at_checked(4).ok_or(FileContainerError::Malformed)?;
+            let len = u32::from_be_bytes(len.try_into().expect("Split off 4 bytes")) as usize;
+            let (section, after_section) = after_len.split_at_checked(len).ok_or(FileContainerError::Malformed)?;
+            bundle.sections[index] = Some(section.to_vec());
+            next_index = index + 1;
+            rest = after_section;
+        }
+        Ok(bundle)
+    }
+}
+
+/// Generates a `K` keypair and writes both keys to a bundle at `out_path`
+fn keygen_bundle<K: KemScheme>(out_path: &Path) -> Result<(), FileContainerError> {
+    let (pk, sk) = K::keypair();
+    let bundle = Bundle::default().with(Bundle::PUBLIC_KEY, pk.as_bytes()).with(Bundle::SECRET_KEY, sk.as_bytes());
+    std::fs::write(out_path, bundle.encode::<K>())?;
+    Ok(())
+}
+
+/// Encrypts the file at `in_path` to the public key in the `keys_path` bundle and writes the
+/// KEM ciphertext, nonce and encrypted file to a bundle at `out_path`
+fn seal_bundle<K: KemScheme>(keys_path: &Path, in_path: &Path, out_path: &Path) -> Result<(), FileContainerError> {
+    let keys = Bundle::decode::<K>(&std::fs::read(keys_path)?)?;
+    let recipient_pk =
+        K::PublicKey::from_bytes(keys.require(Bundle::PUBLIC_KEY)?).map_err(|_| FileContainerError::Malformed)?;
+    let plaintext = std::fs::read(in_path)?;
+    let (shared_secret, kem_ciphertext) = K::encapsulate(&recipient_pk);
+    let mut nonce = [0u8; FILE_CONTAINER_NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let bundle = Bundle::default().with(Bundle::CIPHERTEXT, kem_ciphertext.as_bytes()).with(Bundle::NONCE, &nonce);
+    let header = bundle.encode::<K>();
+    let sealed = file_cipher(shared_secret.as_bytes())
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &header })
+        .expect("AES-GCM encryption cannot fail for in-memory payloads");
+    std::fs::write(out_path, bundle.with(Bundle::PAYLOAD, &sealed).encode::<K>())?;
+    Ok(())
+}
+
+/// Decrypts a bundle written by `seal_bundle` with the secret key in the `keys_path` bundle
+/// and writes the plaintext to `out_path`. Nothing is written unless the whole bundle
+/// authenticates.
+fn open_bundle<K: KemScheme>(keys_path: &Path, in_path: &Path, out_path: &Path) -> Result<(), FileContainerError> {
+    let keys = Bundle::decode::<K>(&std::fs::read(keys_path)?)?;
+    let sk = K::SecretKey::from_bytes(keys.require(Bundle::SECRET_KEY)?).map_err(|_| FileContainerError::Malformed)?;
+    let sealed = Bundle::decode::<K>(&std::fs::read(in_path)?)?;
+    let kem_ciphertext = sealed.require(Bundle::CIPHERTEXT)?;
+    let nonce = sealed.require(Bundle::NONCE)?;
+    let payload = sealed.require(Bundle::PAYLOAD)?;
+    if kem_ciphertext.len() != K::sizes().ciphertext || nonce.len() != FILE_CONTAINER_NONCE_LEN {
+        return Err(FileContainerError::Malformed);
+    }
+
+    let header = Bundle::default().with(Bundle::CIPHERTEXT, kem_ciphertext).with(Bundle::NONCE, nonce).encode::<K>();
+    let kem_ciphertext = K::Ciphertext::from_bytes(kem_ciphertext).map_err(|_| FileContainerError::Malformed)?;
+    let shared_secret = K::decapsulate(&kem_ciphertext, &sk);
+    let plaintext = file_cipher(shared_secret.as_bytes())
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: payload, aad: &header })
+        .map_err(|_| FileContainerError::Decryption)?;
+    std::fs::write(out_path, plaintext)?;
+    Ok(())
+}
+
+/// File operation run from the command line instead of the example, reading and writing
+/// bundle files
+enum BundleCommand<'a> {
+    /// `keygen --bundle <keys>`
+    Keygen { out: &'a Path },
+    /// `seal <input> --from-bundle <keys> --bundle <output>`
+    Seal { keys: &'a Path, input: &'a Path, out: &'a Path },
+    /// `open <input> --from-bundle <keys> --out <output>`
+    Open { keys: &'a Path, input: &'a Path, out: &'a Path },
+}
+
+impl<'a> BundleCommand<'a> {
+    /// Parses the command in `args`, which start at the subcommand name. Returns `None` if
+    /// `args` is not a bundle command and an error message if it is one with missing arguments.
+    fn parse(args: &'a [String]) -> Option<Result<Self, &'static str>> {
+        let flag = |name: &str| args.iter().position(|arg| arg == name).and_then(|position| args.get(position + 1));
+        let path = |name: &str| flag(name).map(Path::new);
+        let input = args.get(1).filter(|arg| !arg.starts_with("--")).map(Path::new);
+        let command = match args.first()?.as_str() {
+            "keygen" => path("--bundle").map(|out| BundleCommand::Keygen { out }).ok_or("keygen needs --bundle <path>"),
+            "seal" => match (input, path("--from-bundle"), path("--bundle")) {
+                (Some(input), Some(keys), Some(out)) => Ok(BundleCommand::Seal { keys, input, out }),
+                _ => Err("usage: seal <input> --from-bundle <keys> --bundle <output>"),
+            },
+            "open" => match (input, path("--from-bundle"), path("--out")) {
+                (Some(input), Some(keys), Some(out)) => Ok(BundleCommand::Open { keys, input, out }),
+                _ => Err("usage: open <input> --from-bundle <keys> --out <output>"),
+            },
+            _ => return None,
+        };
+        Some(command)
+    }
+
+    fn run<K: KemScheme>(&self) -> Result<(), FileContainerError> {
+        match *self {
+            BundleCommand::Keygen { out } => keygen_bundle::<K>(out),
+            BundleCommand::Seal { keys, input, out } => seal_bundle::<K>(keys, input, out),
+            BundleCommand::Open { keys, input, out } => open_bundle::<K>(keys, input, out),
+        }
+    }
+}
+
+/// FrodoKEM variants provided by pqcrypto-frodo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrodoVariant {
+    FrodoKem640Aes,
+    FrodoKem640Shake,
+    FrodoKem976Aes,
+    FrodoKem976Shake,
+    FrodoKem1344Aes,
+    FrodoKem1344Shake,
+}
+
+impl FrodoVariant {
+    const ALL: [FrodoVariant; 6] = [
+        FrodoVariant::FrodoKem640Aes,
+        FrodoVariant::FrodoKem640Shake,
+        FrodoVariant::FrodoKem976Aes,
+        FrodoVariant::FrodoKem976Shake,
+        FrodoVariant::FrodoKem1344Aes,
+        FrodoVariant::FrodoKem1344Shake,
+    ];
+
+    /// Name accepted by `--variant`, matching the pqcrypto-frodo module
+    fn name(self) -> &'static str {
+        match self {
+            FrodoVariant::FrodoKem640Aes => "frodokem640aes",
+            FrodoVariant::FrodoKem640Shake => "frodokem640shake",
+            FrodoVariant::FrodoKem976Aes => "frodokem976aes",
+            FrodoVariant::FrodoKem976Shake => "frodokem976shake",
+            FrodoVariant::FrodoKem1344Aes => "frodokem1344aes",
+            FrodoVariant::FrodoKem1344Shake => "frodokem1344shake",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        FrodoVariant::ALL.into_iter().find(|variant| variant.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Key, ciphertext and shared secret sizes of a variant, known without performing a key
+    /// exchange, e.g. for sizing buffers that hold derived keys
+    fn sizes(self) -> KemSizes {
+        match self {
+            FrodoVariant::FrodoKem640Aes => kem_scheme::FrodoKem640Aes::sizes(),
+            FrodoVariant::FrodoKem640Shake => kem_scheme::FrodoKem640Shake::sizes(),
+            FrodoVariant::FrodoKem976Aes => kem_scheme::FrodoKem976Aes::sizes(),
+            FrodoVariant::FrodoKem976Shake => kem_scheme::FrodoKem976Shake::sizes(),
+            FrodoVariant::FrodoKem1344Aes => kem_scheme::FrodoKem1344Aes::sizes(),
+            FrodoVariant::FrodoKem1344Shake => kem_scheme::FrodoKem1344Shake::sizes(),
+        }
+    }
+
+    /// Runs a bundle file command with this variant
+    fn run_bundle_command(self, command: &BundleCommand) -> Result<(), FileContainerError> {
+        match self {
+            FrodoVariant::FrodoKem640Aes => command.run::<kem_scheme::FrodoKem640Aes>(),
+            FrodoVariant::FrodoKem640Shake => command.run::<kem_scheme::FrodoKem640Shake>(),
+            FrodoVariant::FrodoKem976Aes => command.run::<kem_scheme::FrodoKem976Aes>(),
+            FrodoVariant::FrodoKem976Shake => command.run::<kem_scheme::FrodoKem976Shake>(),
+            FrodoVariant::FrodoKem1344Aes => command.run::<kem_scheme::FrodoKem1344Aes>(),
+            FrodoVariant::FrodoKem1344Shake => command.run::<kem_scheme::FrodoKem1344Shake>(),
+        }
+    }
+
+    /// Runs the full example with this variant
+    fn run_example(self, mem_report: bool) {
+        match self {
+            FrodoVariant::FrodoKem640Aes => run_example::<kem_scheme::FrodoKem640Aes>(mem_report),
+            FrodoVariant::FrodoKem640Shake => run_example::<kem_scheme::FrodoKem640Shake>(mem_report),
+            FrodoVariant::FrodoKem976Aes => run_example::<kem_scheme::FrodoKem976Aes>(mem_report),
+            FrodoVariant::FrodoKem976Shake => run_example::<kem_scheme::FrodoKem976Shake>(mem_report),
+            FrodoVariant::FrodoKem1344Aes => run_example::<kem_scheme::FrodoKem1344Aes>(mem_report),
+            FrodoVariant::FrodoKem1344Shake => run_example::<kem_scheme::FrodoKem1344Shake>(mem_report),
+        }
+    }
+}
 
 fn main() {
-    println!("🔐 Starting FrodoKEM-976-AES Key Exchange Example...\n");
+    let args: Vec<String> = std::env::args().collect();
+    let mem_report = args.iter().any(|arg| arg == "--mem-report");
+    let variant_position = args.iter().position(|arg| arg == "--variant");
+    let variant = match variant_position {
+        None => FrodoVariant::FrodoKem976Aes,
+        Some(position) => match args.get(position + 1).map(|name| (name, FrodoVariant::from_name(name))) {
+            Some((_, Some(variant))) => variant,
+            Some((name, None)) => {
+                let names: Vec<&str> = FrodoVariant::ALL.iter().map(|variant| variant.name()).collect();
+                eprintln!("❌ Unknown variant {:?}; expected one of {}", name, names.join(", "));
+                std::process::exit(2);
+            }
+            None => {
+                eprintln!("❌ --variant needs a variant name");
+                std::process::exit(2);
+            }
+        },
+    };
+    // Bundle commands are parsed from what is left once the flags above are removed
+    let command_args: Vec<String> = (1..args.len())
+        .filter(|&index| args[index] != "--mem-report")
+        .filter(|&index| variant_position.is_none_or(|position| index != position && index != position + 1))
+        .map(|index| args[index].clone())
+        .collect();
+    match BundleCommand::parse(&command_args) {
+        None => variant.run_example(mem_report),
+        Some(Err(usage)) => {
+            eprintln!("❌ {}", usage);
+            std::process::exit(2);
+        }
+        Some(Ok(command)) => match variant.run_bundle_command(&command) {
+            Ok(()) => println!("✅ {} done with {}", command_args[0], variant.name()),
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+/// Walks through the key exchange, key confirmation, framing and key derivation with `K`
+fn run_example<K: KemScheme>(mem_report: bool) {
+    println!("🔐 Starting {} Key Exchange Example...\n", K::NAME);
 
     // ================================================================
     // STEP 1: Receiver (e.g., server or blockchain node) generates keypair
     // The public key will be shared with the sender
     // The secret key is kept private by the receiver
     // ================================================================
-    let (pk, sk) = frodokem976aes::keypair();
+    let ((pk, sk), keygen_peak) = measure_peak_heap(K::keypair);
 
     println!("📤 Public Key (Receiver):");
     println!("{}", hex::encode(pk.as_bytes()));
@@ -53,7 +637,7 @@ fn main() {
     //   - A random shared secret
     //   - A ciphertext that can be sent publicly
     // ================================================================
-    let (ss_sender, ct) = frodokem976aes::encapsulate(&pk);
+    let ((ss_sender, ct), encap_peak) = measure_peak_heap(|| K::encapsulate(&pk));
 
     println!("📦 Ciphertext (to be sent to receiver):");
     println!("{}", hex::encode(ct.as_bytes()));
@@ -67,7 +651,7 @@ fn main() {
     // STEP 3: Receiver decapsulates the ciphertext using their secret key
     // This should derive the same shared secret as the sender
     // ================================================================
-    let ss_receiver = frodokem976aes::decapsulate(&ct, &sk);
+    let (ss_receiver, decap_peak) = measure_peak_heap(|| K::decapsulate(&ct, &sk));
 
     println!("🔑 Shared Secret (Receiver side):");
     println!("{}", hex::encode(ss_receiver.as_bytes()));
@@ -77,13 +661,513 @@ fn main() {
     // STEP 4: Verify if shared secrets match
     // This is the basis of secure key exchange!
     // ================================================================
-    if ss_sender.as_bytes() == ss_receiver.as_bytes() {
+    if ct_eq(ss_sender.as_bytes(), ss_receiver.as_bytes()) {
         println!("🎉 ✅ Shared secrets match!");
-        println!("🔐 FrodoKEM-976-AES key exchange was successful and secure.\n");
+        println!("🔐 {} key exchange was successful and secure.\n", K::NAME);
     } else {
         println!("❌ Shared secrets DO NOT match!");
         println!("⚠️ Key exchange failed. Do not use this key for secure communication.\n");
     }
 
-    println!("🧪 FrodoKEM-976-AES example completed.");
+    // ================================================================
+    // STEP 5: Key confirmation
+    // The receiver sends a MAC tag derived from its shared secret, and
+    // the sender checks it in constant time before using the key.
+    // A mismatch aborts the exchange.
+    // ================================================================
+    let receiver_tag = confirm_key(ss_receiver.as_bytes(), Role::Responder);
+    if let Err(e) = verify_key_confirmation(ss_sender.as_bytes(), Role::Responder, &receiver_tag) {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+    println!("🤝 Key confirmation tag verified\n");
+
+    // ================================================================
+    // STEP 6: Reading the ciphertext off the wire
+    // A network layer may deliver only part of a frame. `ciphertext_from_frame`
+    // reports how many bytes are still missing instead of failing, and
+    // returns any bytes that follow the ciphertext.
+    // ================================================================
+    let mut wire = ct.as_bytes().to_vec();
+    wire.extend_from_slice(b"next-frame");
+    let partial = &wire[..wire.len() / 2];
+    match ciphertext_from_frame::<K>(partial) {
+        Err(FrameError::Incomplete { needed }) => println!("⏳ Partial frame: waiting for {} more bytes", needed),
+        Err(e) => println!("❌ {}", e),
+        Ok(_) => println!("❌ Partial frame was unexpectedly accepted"),
+    }
+    match ciphertext_from_frame::<K>(&wire) {
+        Ok((framed_ct, tail)) => {
+            let framed_ss = K::decapsulate(&framed_ct, &sk);
+            println!("📥 Full frame parsed ({} trailing bytes left)", tail.len());
+            println!("✅ Framed ciphertext decapsulates to the same secret: {}\n", ct_eq(framed_ss.as_bytes(), ss_sender.as_bytes()));
+        }
+        Err(e) => println!("❌ {}\n", e),
+    }
+
+    // ================================================================
+    // STEP 7: Derive symmetric keys from the shared secret
+    // The raw secret is never used as a key. Both sides run it through
+    // HKDF-SHA256 with a label per purpose, getting the same key for the
+    // same label and unrelated keys for different labels.
+    // ================================================================
+    let sender_key = kem_kdf::derive_key(ss_sender.as_bytes(), b"Quantova FrodoKEM encryption key", 32);
+    let receiver_key = kem_kdf::derive_key(ss_receiver.as_bytes(), b"Quantova FrodoKEM encryption key", 32);
+    let mac_key = kem_kdf::derive_key(ss_sender.as_bytes(), b"Quantova FrodoKEM MAC key", 32);
+    println!("🗝️ Encryption key derived ({} bytes, not shown)", sender_key.len());
+    println!("✅ Both sides derive the same key: {}", ct_eq(&sender_key, &receiver_key));
+    println!("✅ MAC key differs from encryption key: {}\n", !ct_eq(&sender_key, &mac_key));
+
+    // ================================================================
+    // STEP 8: The same exchange through the shared KEM interface
+    // `kem_scheme::run_kem_demo` runs keygen, encapsulate, decapsulate
+    // and the comparison for any `KemScheme`; the NTRU example runs the
+    // identical code with NTRU-HRSS-701.
+    // ================================================================
+    let run = kem_scheme::run_kem_demo::<K>();
+    println!("🔁 {} via KemScheme:", K::NAME);
+    println!("✅ Shared secrets match: {}", run.secrets_match);
+    println!("✅ Sizes as advertised: {} (ciphertext {} bytes)\n", run.sizes_match, run.sizes.ciphertext);
+
+    // ================================================================
+    // STEP 9: Authenticated KEM
+    // A plain KEM does not say who sent the ciphertext: an active
+    // attacker can replace it with one they encapsulated themselves.
+    // The sender signs the ciphertext with Dilithium3, and the receiver
+    // checks the signature before decapsulating, so a swapped or
+    // modified ciphertext is rejected without ever being decapsulated.
+    // ================================================================
+    let (sender_sign_pk, sender_sign_sk) = dilithium3::keypair();
+    let (signed_ss, signed) = encapsulate_signed::<K>(&pk, &sender_sign_sk);
+    println!("✍️ Ciphertext signed with Dilithium3 ({} byte signature)", signed.signature.as_bytes().len());
+    match decapsulate_verified::<K>(&signed, &pk, &sk, &sender_sign_pk) {
+        Ok(verified_ss) => println!("✅ Signature verified, shared secrets match: {}", ct_eq(verified_ss.as_bytes(), signed_ss.as_bytes())),
+        Err(e) => println!("❌ {}", e),
+    }
+
+    let mut tampered = signed.ciphertext.as_bytes().to_vec();
+    tampered[0] ^= 0x01;
+    let tampered = SignedCiphertext::<K> {
+        ciphertext: K::Ciphertext::from_bytes(&tampered).expect("Tampered ciphertext has the right length"),
+        signed_at: signed.signed_at,
+        signature: signed.signature.clone(),
+    };
+    match decapsulate_verified::<K>(&tampered, &pk, &sk, &sender_sign_pk) {
+        Err(e) => println!("🛑 Tampered ciphertext rejected before decapsulation: {}", e),
+        Ok(_) => println!("❌ Tampered ciphertext was accepted!"),
+    }
+
+    let (other_pk, other_sk) = K::keypair();
+    match decapsulate_verified::<K>(&signed, &other_pk, &other_sk, &sender_sign_pk) {
+        Err(e) => println!("🛑 Ciphertext redirected to another recipient rejected: {}\n", e),
+        Ok(_) => println!("❌ Redirected ciphertext was accepted!\n"),
+    }
+
+    // ================================================================
+    // STEP 10: Encrypt a file to the receiver
+    // `seal_file` encapsulates, derives an AES-256-GCM key with HKDF
+    // and writes a versioned container; `open_file` reverses it. A
+    // 4 MiB file makes a round trip, and a single flipped byte in the
+    // container makes opening fail.
+    // ================================================================
+    let dir = std::env::temp_dir();
+    let plain_path = dir.join(format!("quantova-frodo-{}.bin", std::process::id()));
+    let sealed_path = plain_path.with_extension("sealed");
+    let opened_path = plain_path.with_extension("opened");
+    let original: Vec<u8> = (0..4 * 1024 * 1024u32).map(|i| (i.wrapping_mul(2654435761) >> 24) as u8).collect();
+    let file_result = std::fs::write(&plain_path, &original)
+        .map_err(FileContainerError::from)
+        .and_then(|_| seal_file::<K>(&pk, &plain_path, &sealed_path))
+        .and_then(|_| open_file::<K>(&sk, &sealed_path, &opened_path))
+        .and_then(|_| Ok((std::fs::metadata(&sealed_path)?.len(), std::fs::read(&opened_path)?)));
+    match file_result {
+        Ok((sealed_len, opened)) => {
+            println!("📁 Sealed a {} byte file into a {} byte container", original.len(), sealed_len);
+            println!("✅ Opened file matches the original: {}", opened == original);
+        }
+        Err(e) => println!("❌ File round trip failed: {}", e),
+    }
+    if let Ok(mut container) = std::fs::read(&sealed_path) {
+        let last = container.len() - 1;
+        container[last] ^= 0x01;
+        let tampered = std::fs::write(&sealed_path, container)
+            .map_err(FileContainerError::from)
+            .and_then(|_| open_file::<K>(&sk, &sealed_path, &opened_path));
+        match tampered {
+            Err(e) => println!("🛑 Modified container rejected: {}\n", e),
+            Ok(_) => println!("❌ Modified container was accepted!\n"),
+        }
+    }
+    for path in [&plain_path, &sealed_path, &opened_path] {
+        let _ = std::fs::remove_file(path);
+    }
+
+    // ================================================================
+    // Shared secret sizes per variant, looked up without encapsulating
+    // ================================================================
+    println!("📏 Shared secret sizes by variant:");
+    for variant in FrodoVariant::ALL {
+        println!("   {:?}: {} bytes", variant, variant.sizes().shared_secret);
+    }
+    println!();
+
+    if mem_report {
+        println!("📊 Peak heap usage (bytes):");
+        println!("   Keygen     : {}", keygen_peak);
+        println!("   Encapsulate: {}", encap_peak);
+        println!("   Decapsulate: {}", decap_peak);
+        println!("   Overall    : {}", keygen_peak.max(encap_peak).max(decap_peak));
+        println!("   ⚠️ Stack usage of the underlying C implementation is not included.\n");
+    }
+
+    println!("🧪 {} example completed.", K::NAME);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kem_scheme::FrodoKem640Shake as Frodo;
+
+    fn sign_at(
+        recipient_pk: &<Frodo as KemScheme>::PublicKey,
+        ciphertext: <Frodo as KemScheme>::Ciphertext,
+        signed_at: u64,
+        signer_sk: &dilithium3::SecretKey,
+    ) -> SignedCiphertext<Frodo> {
+        let signature = dilithium3::detached_sign(&signed_ciphertext_message::<Frodo>(recipient_pk, signed_at, &ciphertext), signer_sk);
+        SignedCiphertext { ciphertext, signed_at, signature }
+    }
+
+    /// Paths for one test's plaintext, container and opened output under the temp directory
+    fn temp_paths(test: &str) -> [std::path::PathBuf; 3] {
+        let base = std::env::temp_dir().join(format!("quantova-frodo-test-{}-{}", std::process::id(), test));
+        [base.with_extension("bin"), base.with_extension("sealed"), base.with_extension("opened")]
+    }
+
+    #[test]
+    fn multi_megabyte_file_round_trips() {
+        let (pk, sk) = Frodo::keypair();
+        let [plain, sealed, opened] = temp_paths("round-trip");
+        let original: Vec<u8> = (0..6 * 1024 * 1024u32).map(|i| (i.wrapping_mul(2654435761) >> 24) as u8).collect();
+        std::fs::write(&plain, &original).unwrap();
+
+        let result = seal_file::<Frodo>(&pk, &plain, &sealed)
+            .and_then(|_| open_file::<Frodo>(&sk, &sealed, &opened))
+            .and_then(|_| Ok(std::fs::read(&opened)?));
+        let (_, other_sk) = Frodo::keypair();
+        let wrong_key = open_file::<Frodo>(&other_sk, &sealed, &opened);
+        for path in [&plain, &sealed, &opened] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert!(result.expect("round trip") == original);
+        assert!(matches!(wrong_key, Err(FileContainerError::Decryption)));
+    }
+
+    #[test]
+    fn modified_file_container_is_rejected() {
+        let (pk, sk) = Frodo::keypair();
+        let [plain, sealed, opened] = temp_paths("modified");
+        std::fs::write(&plain, vec![0x5a; 64 * 1024]).unwrap();
+        seal_file::<Frodo>(&pk, &plain, &sealed).unwrap();
+
+        let container = std::fs::read(&sealed).unwrap();
+        // One byte in the header, one in the KEM ciphertext, one in the AEAD output
+        let positions = [FILE_CONTAINER_MAGIC.len() + 2, container.len() / 2, container.len() - 1];
+        let results: Vec<_> = positions
+            .iter()
+            .map(|&position| {
+                let mut modified = container.clone();
+                modified[position] ^= 0x01;
+                std::fs::write(&sealed, modified).unwrap();
+                open_file::<Frodo>(&sk, &sealed, &opened)
+            })
+            .collect();
+        let opened_exists = opened.exists();
+        for path in [&plain, &sealed, &opened] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert!(results.iter().all(Result::is_err));
+        assert!(!opened_exists);
+    }
+
+    #[test]
+    fn bundles_round_trip_through_keygen_seal_and_open() {
+        let [plain, sealed, opened] = temp_paths("bundle");
+        let keys = plain.with_extension("keys");
+        std::fs::write(&plain, b"bundled file contents").unwrap();
+        let result = keygen_bundle::<Frodo>(&keys)
+            .and_then(|_| seal_bundle::<Frodo>(&keys, &plain, &sealed))
+            .and_then(|_| open_bundle::<Frodo>(&keys, &sealed, &opened))
+            .and_then(|_| Ok(std::fs::read(&opened)?));
+        let key_sections = std::fs::read(&keys).map(|bytes| Bundle::decode::<Frodo>(&bytes).unwrap());
+        let sealed_bundle = std::fs::read(&sealed).unwrap();
+        // The 976 variant must not read a 640 bundle
+        let other_variant = Bundle::decode::<kem_scheme::FrodoKem976Aes>(&sealed_bundle);
+        for path in [&plain, &sealed, &opened, &keys] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert_eq!(result.unwrap(), b"bundled file contents");
+        let key_sections = key_sections.unwrap();
+        assert!(key_sections.require(Bundle::PUBLIC_KEY).is_ok() && key_sections.require(Bundle::SECRET_KEY).is_ok());
+        assert!(matches!(key_sections.require(Bundle::CIPHERTEXT), Err(FileContainerError::MissingSection("ciphertext"))));
+        assert!(matches!(other_variant, Err(FileContainerError::AlgorithmMismatch { .. })));
+    }
+
+    #[test]
+    fn bundles_missing_a_required_section_are_rejected() {
+        let [plain, sealed, opened] = temp_paths("bundle-missing");
+        let keys = plain.with_extension("keys");
+        let (pk, _) = Frodo::keypair();
+        std::fs::write(&plain, b"contents").unwrap();
+        // A public key alone can seal but not open
+        std::fs::write(&keys, Bundle::default().with(Bundle::PUBLIC_KEY, pk.as_bytes()).encode::<Frodo>()).unwrap();
+        let sealed_ok = seal_bundle::<Frodo>(&keys, &plain, &sealed);
+        let open_without_sk = open_bundle::<Frodo>(&keys, &sealed, &opened);
+        // A key bundle is not a sealed file
+        let open_keys = open_bundle::<Frodo>(&keys, &keys, &opened);
+        let opened_exists = opened.exists();
+        for path in [&plain, &sealed, &opened, &keys] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert!(sealed_ok.is_ok());
+        assert!(matches!(open_without_sk, Err(FileContainerError::MissingSection("secret key"))));
+        assert!(matches!(open_keys, Err(FileContainerError::MissingSection("secret key"))));
+        assert!(!opened_exists);
+    }
+
+    #[test]
+    fn modified_or_malformed_bundles_are_rejected() {
+        let [plain, sealed, opened] = temp_paths("bundle-modified");
+        let keys = plain.with_extension("keys");
+        std::fs::write(&plain, vec![0x5a; 4096]).unwrap();
+        keygen_bundle::<Frodo>(&keys).unwrap();
+        seal_bundle::<Frodo>(&keys, &plain, &sealed).unwrap();
+        let bundle = std::fs::read(&sealed).unwrap();
+        // One byte of the KEM ciphertext section, past its tag and length, and one of the payload
+        let ciphertext_start = Bundle::default().encode::<Frodo>().len() + 5;
+        let results: Vec<_> = [ciphertext_start + 10, bundle.len() - 1]
+            .into_iter()
+            .map(|position| {
+                let mut modified = bundle.clone();
+                modified[position] ^= 0x01;
+                std::fs::write(&sealed, modified).unwrap();
+                open_bundle::<Frodo>(&keys, &sealed, &opened)
+            })
+            .collect();
+        for path in [&plain, &sealed, &opened, &keys] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert!(results.iter().all(|result| matches!(result, Err(FileContainerError::Decryption))));
+        let header = Bundle::default().encode::<Frodo>();
+        let section = |tag: u8| [&[tag][..], &1u32.to_be_bytes(), b"x"].concat();
+        for malformed in [
+            [&header[..], &section(6)].concat(),
+            [&header[..], &section(2), &section(2)].concat(),
+            [&header[..], &section(2), &section(1)].concat(),
+            [&header[..], &[1, 0, 0, 0, 2, b'x']].concat(),
+            header[..header.len() - 1].to_vec(),
+        ] {
+            assert!(matches!(Bundle::decode::<Frodo>(&malformed), Err(FileContainerError::Malformed)));
+        }
+        let mut future = header.clone();
+        future[BUNDLE_MAGIC.len()] = BUNDLE_VERSION + 1;
+        assert!(matches!(Bundle::decode::<Frodo>(&future), Err(FileContainerError::UnsupportedVersion { .. })));
+    }
+
+    #[test]
+    fn v2_file_container_is_rejected_by_v1_reader() {
+        let (pk, sk) = Frodo::keypair();
+        let [plain, sealed, opened] = temp_paths("v2");
+        std::fs::write(&plain, b"file contents").unwrap();
+        seal_file::<Frodo>(&pk, &plain, &sealed).unwrap();
+
+        let mut container = std::fs::read(&sealed).unwrap();
+        container[FILE_CONTAINER_MAGIC.len()] = 2;
+        std::fs::write(&sealed, container).unwrap();
+        let result = open_file::<Frodo>(&sk, &sealed, &opened);
+        let opened_exists = opened.exists();
+        for path in [&plain, &sealed, &opened] {
+            let _ = std::fs::remove_file(path);
+        }
+
+        assert!(matches!(
+            result,
+            Err(FileContainerError::UnsupportedVersion { got: 2, max_supported: FILE_CONTAINER_VERSION })
+        ));
+        assert!(!opened_exists);
+    }
+
+    #[test]
+    fn signed_ciphertext_round_trips() {
+        let (pk, sk) = Frodo::keypair();
+        let (sign_pk, sign_sk) = dilithium3::keypair();
+        let (sender_ss, signed) = encapsulate_signed::<Frodo>(&pk, &sign_sk);
+
+        let recipient_ss = decapsulate_verified::<Frodo>(&signed, &pk, &sk, &sign_pk).expect("valid signature");
+        assert!(ct_eq(sender_ss.as_bytes(), recipient_ss.as_bytes()));
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let (pk, sk) = Frodo::keypair();
+        let (sign_pk, sign_sk) = dilithium3::keypair();
+        let (_, mut signed) = encapsulate_signed::<Frodo>(&pk, &sign_sk);
+
+        let mut tampered = signed.ciphertext.as_bytes().to_vec();
+        tampered[0] ^= 0x01;
+        signed.ciphertext = <Frodo as KemScheme>::Ciphertext::from_bytes(&tampered).unwrap();
+        assert!(matches!(
+            decapsulate_verified::<Frodo>(&signed, &pk, &sk, &sign_pk),
+            Err(SignedCiphertextError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn swapped_ciphertext_is_rejected() {
+        let (pk, sk) = Frodo::keypair();
+        let (sign_pk, sign_sk) = dilithium3::keypair();
+        let (_, mut signed) = encapsulate_signed::<Frodo>(&pk, &sign_sk);
+
+        // The attacker keeps the sender's signature but substitutes a ciphertext of their own
+        let (_, attacker_ct) = Frodo::encapsulate(&pk);
+        signed.ciphertext = attacker_ct;
+        assert!(matches!(
+            decapsulate_verified::<Frodo>(&signed, &pk, &sk, &sign_pk),
+            Err(SignedCiphertextError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn forged_signature_is_rejected() {
+        let (pk, sk) = Frodo::keypair();
+        let (sign_pk, _) = dilithium3::keypair();
+        let (_, attacker_sign_sk) = dilithium3::keypair();
+        let (_, forged) = encapsulate_signed::<Frodo>(&pk, &attacker_sign_sk);
+
+        assert!(matches!(
+            decapsulate_verified::<Frodo>(&forged, &pk, &sk, &sign_pk),
+            Err(SignedCiphertextError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn ciphertext_signed_for_another_recipient_is_rejected() {
+        let (alice_pk, _) = Frodo::keypair();
+        let (bob_pk, bob_sk) = Frodo::keypair();
+        let (sign_pk, sign_sk) = dilithium3::keypair();
+
+        // Alice forwards a ciphertext signed for her to Bob, who must not accept it as his
+        let (_, signed_for_alice) = encapsulate_signed::<Frodo>(&alice_pk, &sign_sk);
+        assert!(matches!(
+            decapsulate_verified::<Frodo>(&signed_for_alice, &bob_pk, &bob_sk, &sign_pk),
+            Err(SignedCiphertextError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn stale_and_future_signatures_are_rejected() {
+        let (pk, sk) = Frodo::keypair();
+        let (sign_pk, sign_sk) = dilithium3::keypair();
+        let now = unix_now();
+
+        let (_, ct) = Frodo::encapsulate(&pk);
+        let stale = sign_at(&pk, ct, now - SIGNED_CIPHERTEXT_MAX_AGE_SECS - 60, &sign_sk);
+        assert!(matches!(
+            decapsulate_verified::<Frodo>(&stale, &pk, &sk, &sign_pk),
+            Err(SignedCiphertextError::Stale { .. })
+        ));
+
+        let (_, ct) = Frodo::encapsulate(&pk);
+        let future = sign_at(&pk, ct, now + SIGNED_CIPHERTEXT_MAX_SKEW_SECS + 60, &sign_sk);
+        assert!(matches!(
+            decapsulate_verified::<Frodo>(&future, &pk, &sk, &sign_pk),
+            Err(SignedCiphertextError::Stale { .. })
+        ));
+    }
+
+    #[test]
+    fn re_signed_timestamp_must_match_signature() {
+        let (pk, sk) = Frodo::keypair();
+        let (sign_pk, sign_sk) = dilithium3::keypair();
+        let (_, ct) = Frodo::encapsulate(&pk);
+        let mut old = sign_at(&pk, ct, unix_now() - SIGNED_CIPHERTEXT_MAX_AGE_SECS - 60, &sign_sk);
+
+        // Bumping the timestamp to get past the age check breaks the signature instead
+        old.signed_at = unix_now();
+        assert!(matches!(
+            decapsulate_verified::<Frodo>(&old, &pk, &sk, &sign_pk),
+            Err(SignedCiphertextError::BadSignature)
+        ));
+    }
+
+    #[test]
+    fn ciphertext_frames_are_parsed_from_partial_buffers() {
+        let (pk, _) = Frodo::keypair();
+        let (_, ct) = Frodo::encapsulate(&pk);
+        let len = Frodo::sizes().ciphertext;
+
+        assert!(matches!(ciphertext_from_frame::<Frodo>(&[]), Err(FrameError::Incomplete { needed }) if needed == len));
+        assert!(matches!(
+            ciphertext_from_frame::<Frodo>(&ct.as_bytes()[..len - 10]),
+            Err(FrameError::Incomplete { needed: 10 })
+        ));
+
+        let (parsed, tail) = ciphertext_from_frame::<Frodo>(ct.as_bytes()).unwrap();
+        assert_eq!(parsed.as_bytes(), ct.as_bytes());
+        assert!(tail.is_empty());
+
+        let mut buffer = ct.as_bytes().to_vec();
+        buffer.extend_from_slice(b"next frame");
+        let (parsed, tail) = ciphertext_from_frame::<Frodo>(&buffer).unwrap();
+        assert_eq!(parsed.as_bytes(), ct.as_bytes());
+        assert_eq!(tail, b"next frame");
+    }
+
+    #[test]
+    fn variant_sizes_match_pqcrypto_frodo() {
+        let expected = [
+            pqcrypto_frodo::frodokem640aes::shared_secret_bytes(),
+            pqcrypto_frodo::frodokem640shake::shared_secret_bytes(),
+            pqcrypto_frodo::frodokem976aes::shared_secret_bytes(),
+            pqcrypto_frodo::frodokem976shake::shared_secret_bytes(),
+            pqcrypto_frodo::frodokem1344aes::shared_secret_bytes(),
+            pqcrypto_frodo::frodokem1344shake::shared_secret_bytes(),
+        ];
+        for (variant, expected) in FrodoVariant::ALL.into_iter().zip(expected) {
+            assert_eq!(variant.sizes().shared_secret, expected, "{:?}", variant);
+        }
+    }
+
+    /// First vector (`count = 0`) of the NIST FrodoKEM-976-AES KAT response file
+    /// `PQCkemKAT_31296.rsp` from the round 3 submission package, trimmed to one vector so it
+    /// is small enough to commit. See the pqcrypto README for how to produce it.
+    const FRODO_976_AES_KAT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/vectors/frodokem976aes_kat0.rsp");
+
+    /// Hex value of the first `name = ...` line of a KAT response file.
+    fn kat_field(rsp: &str, name: &str) -> Vec<u8> {
+        let prefix = format!("{} = ", name);
+        let line = rsp.lines().find_map(|line| line.strip_prefix(&prefix)).unwrap_or_else(|| panic!("KAT has no {} field", name));
+        hex::decode(line.trim()).unwrap()
+    }
+
+    /// pqcrypto-frodo cannot take the KAT's seeded RNG, so keygen and encapsulation cannot be
+    /// replayed; decapsulating the first vector's ciphertext with its secret key still checks
+    /// the implementation against the reference shared secret.
+    #[test]
+    #[ignore = "needs tests/vectors/frodokem976aes_kat0.rsp, trimmed from the NIST KAT file"]
+    fn frodo_976_aes_matches_the_nist_kat() {
+        use pqcrypto_frodo::frodokem976aes;
+
+        let rsp = std::fs::read_to_string(FRODO_976_AES_KAT)
+            .unwrap_or_else(|e| panic!("cannot read {}: {}", FRODO_976_AES_KAT, e));
+        let sk = frodokem976aes::SecretKey::from_bytes(&kat_field(&rsp, "sk")).unwrap();
+        let ct = frodokem976aes::Ciphertext::from_bytes(&kat_field(&rsp, "ct")).unwrap();
+        let ss = frodokem976aes::decapsulate(&ct, &sk);
+        assert_eq!(ss.as_bytes(), kat_field(&rsp, "ss").as_slice());
+    }
 }
@@ -0,0 +1,90 @@
+//! # Peak heap usage for `--mem-report`
+//!
+//! `CountingAllocator` wraps the system allocator and records current and peak heap usage,
+//! and `measure_peak_heap` reports how far an operation pushed the heap above where it
+//! started. The counters only move when a binary installs the allocator, so each example
+//! that offers `--mem-report` declares it itself:
+//!
+//! ```toml
+//! [dependencies]
+//! heap_stats = { path = "../heap-stats" }
+//! ```
+//!
+//! ```
+//! #[global_allocator]
+//! static ALLOCATOR: heap_stats::CountingAllocator = heap_stats::CountingAllocator;
+//!
+//! fn main() {
+//!     let (buffer, peak) = heap_stats::measure_peak_heap(|| vec![0u8; 4096]);
+//!     assert!(peak >= buffer.len());
+//! }
+//! ```
+//!
+//! Shared by the FrodoKEM and SPHINCS+ examples.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CURRENT_HEAP: AtomicUsize = AtomicUsize::new(0);
+static PEAK_HEAP: AtomicUsize = AtomicUsize::new(0);
+
+/// Global allocator that forwards to `System` and counts the bytes currently allocated
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT_HEAP.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK_HEAP.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT_HEAP.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+/// Runs `op` and returns its result together with the peak heap bytes allocated above the
+/// usage at the start of the call.
+///
+/// Reports 0 unless `CountingAllocator` is the global allocator. The counters are
+/// process-wide, so allocations made by other threads during `op` are included.
+pub fn measure_peak_heap<T>(op: impl FnOnce() -> T) -> (T, usize) {
+    let baseline = CURRENT_HEAP.load(Ordering::Relaxed);
+    PEAK_HEAP.store(baseline, Ordering::Relaxed);
+    let result = op();
+    (result, PEAK_HEAP.load(Ordering::Relaxed).saturating_sub(baseline))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    /// `measure_peak_heap` resets the process-wide peak, so tests that call it take this lock
+    /// to keep the test harness's threads from resetting each other's measurements
+    static MEASURING: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn peak_covers_a_freed_allocation() {
+        let _guard = MEASURING.lock().unwrap();
+        let ((), peak) = measure_peak_heap(|| {
+            let buffer = vec![0u8; 1 << 20];
+            std::hint::black_box(&buffer);
+        });
+        assert!(peak >= 1 << 20, "peak {} below the 1 MiB allocated", peak);
+    }
+
+    #[test]
+    fn result_is_passed_through() {
+        let _guard = MEASURING.lock().unwrap();
+        let (value, _) = measure_peak_heap(|| vec![7u8; 64]);
+        assert_eq!(value, vec![7u8; 64]);
+    }
+}
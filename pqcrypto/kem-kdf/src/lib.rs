@@ -0,0 +1,210 @@
+//! # Key derivation, confirmation and comparison for KEM shared secrets
+//!
+//! A KEM shared secret is uniformly random key material, but it should still not be used
+//! directly as a symmetric key: one secret often has to key several things (an AEAD, a MAC,
+//! each direction of a channel), and those keys must be independent. `derive_key` runs the
+//! secret through HKDF-SHA256 with an `info` label naming the purpose, so each label yields
+//! an unrelated key. `confirm_key` and `verify_key_confirmation` let each party prove it
+//! derived the same secret, and `ct_eq` compares secrets and derived keys without leaking
+//! through timing how many leading bytes agree.
+//!
+//! Shared by the NTRU and FrodoKEM examples.
+//!
+//! ```toml
+//! [dependencies]
+//! kem_kdf = { path = "../kem-kdf" }
+//! ```
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+/// Largest `out_len` HKDF-SHA256 can produce: 255 blocks of 32 bytes
+pub const MAX_OUTPUT_LEN: usize = 255 * 32;
+
+/// Derives `out_len` bytes of key material from a KEM shared secret with HKDF-SHA256
+///
+/// The output is deterministic for the same `shared_secret`, `info` and `out_len`, and
+/// unrelated keys come out for different `info` labels. No salt is used: the shared secret
+/// is already uniformly random. The key is wiped from memory when the returned buffer is dropped.
+///
+/// # Panics
+///
+/// If `out_len` exceeds [`MAX_OUTPUT_LEN`].
+pub fn derive_key(shared_secret: &[u8], info: &[u8], out_len: usize) -> Zeroizing<Vec<u8>> {
+    assert!(out_len <= MAX_OUTPUT_LEN, "HKDF-SHA256 output is limited to {} bytes", MAX_OUTPUT_LEN);
+    let mut key = Zeroizing::new(vec![0u8; out_len]);
+    Hkdf::<Sha256>::new(None, shared_secret)
+        .expand(info, &mut key)
+        .expect("Output length was checked against the HKDF-SHA256 limit");
+    key
+}
+
+/// HKDF label for the key that key-confirmation tags are computed with
+const CONFIRMATION_KEY_INFO: &[u8] = b"Quantova KEM key confirmation key v2";
+
+/// Which party of a KEM exchange computes a key-confirmation tag
+///
+/// Each role MACs its own label, so the two tags of one exchange differ and a party cannot
+/// pass confirmation by reflecting the peer's tag back to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// The sender, who encapsulated against the peer's public key
+    Initiator,
+    /// The recipient, who decapsulated with its secret key
+    Responder,
+}
+
+impl Role {
+    fn label(self) -> &'static [u8] {
+        match self {
+            Role::Initiator => b"initiator confirms",
+            Role::Responder => b"responder confirms",
+        }
+    }
+}
+
+/// Error returned when a peer's key-confirmation tag does not match our shared secret
+#[derive(Debug)]
+pub struct KeyConfirmationError;
+
+impl std::fmt::Display for KeyConfirmationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "key confirmation failed: peer derived a different shared secret")
+    }
+}
+
+fn confirmation_mac(shared_secret: &[u8], role: Role) -> Hmac<Sha256> {
+    let key = derive_key(shared_secret, CONFIRMATION_KEY_INFO, 32);
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).expect("HMAC accepts keys of any length");
+    mac.update(role.label());
+    mac
+}
+
+/// Computes the key-confirmation tag that the party in `role` sends to its peer: HMAC-SHA256
+/// over the role's label, keyed by a confirmation key derived from the shared secret
+pub fn confirm_key(shared_secret: &[u8], role: Role) -> [u8; 32] {
+    confirmation_mac(shared_secret, role).finalize().into_bytes().into()
+}
+
+/// Checks, in constant time, a tag the peer computed as `peer_role` against our own shared
+/// secret
+pub fn verify_key_confirmation(shared_secret: &[u8], peer_role: Role, peer_tag: &[u8]) -> Result<(), KeyConfirmationError> {
+    confirmation_mac(shared_secret, peer_role)
+        .verify_slice(peer_tag)
+        .map_err(|_| KeyConfirmationError)
+}
+
+/// Compares two byte strings in constant time. Inputs of different lengths are never equal;
+/// only the lengths themselves, which are public for KEM outputs, can leak through timing
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    bool::from(ConstantTimeEq::ct_eq(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zeroize::ZeroizeOnDrop;
+
+    const SECRET: &[u8] = &[0x42; 32];
+
+    #[test]
+    fn derive_key_returns_the_requested_length() {
+        for out_len in [0, 1, 16, 32, 33, 64, MAX_OUTPUT_LEN] {
+            assert_eq!(derive_key(SECRET, b"length", out_len).len(), out_len);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "limited to")]
+    fn derive_key_rejects_lengths_beyond_hkdf_limit() {
+        derive_key(SECRET, b"length", MAX_OUTPUT_LEN + 1);
+    }
+
+    #[test]
+    fn derive_key_is_deterministic() {
+        assert_eq!(*derive_key(SECRET, b"label", 32), *derive_key(SECRET, b"label", 32));
+    }
+
+    #[test]
+    fn different_info_gives_different_keys() {
+        assert_ne!(*derive_key(SECRET, b"encryption key", 32), *derive_key(SECRET, b"MAC key", 32));
+    }
+
+    #[test]
+    fn different_secrets_give_different_keys() {
+        let mut other = SECRET.to_vec();
+        other[31] ^= 0x01;
+        assert_ne!(*derive_key(SECRET, b"label", 32), *derive_key(&other, b"label", 32));
+    }
+
+    #[test]
+    fn derived_keys_are_wiped_on_drop() {
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+        let mut key = derive_key(SECRET, b"label", 32);
+        assert_zeroize_on_drop(&key);
+        // Dropping runs the same zeroize as this explicit call, which wipes the bytes in place
+        // before releasing the buffer
+        let buffer: &mut Vec<u8> = &mut key;
+        let (ptr, capacity) = (buffer.as_ptr(), buffer.capacity());
+        zeroize::Zeroize::zeroize(buffer);
+        assert!(buffer.is_empty());
+        assert_eq!((buffer.as_ptr(), buffer.capacity()), (ptr, capacity));
+        // SAFETY: the allocation is still owned by `buffer`; only its length was reset
+        let wiped = unsafe { std::slice::from_raw_parts(ptr, capacity) };
+        assert!(wiped.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn key_confirmation_accepts_the_peer_tag() {
+        let tag = confirm_key(SECRET, Role::Responder);
+        assert!(verify_key_confirmation(SECRET, Role::Responder, &tag).is_ok());
+        let tag = confirm_key(SECRET, Role::Initiator);
+        assert!(verify_key_confirmation(SECRET, Role::Initiator, &tag).is_ok());
+    }
+
+    #[test]
+    fn key_confirmation_tags_differ_by_role() {
+        assert_ne!(confirm_key(SECRET, Role::Initiator), confirm_key(SECRET, Role::Responder));
+    }
+
+    #[test]
+    fn reflected_tag_is_rejected() {
+        // The responder echoes the initiator's own tag instead of computing one
+        let initiator_tag = confirm_key(SECRET, Role::Initiator);
+        assert!(verify_key_confirmation(SECRET, Role::Responder, &initiator_tag).is_err());
+    }
+
+    #[test]
+    fn key_confirmation_rejects_a_different_secret() {
+        let tag = confirm_key(&[0x43; 32], Role::Responder);
+        assert!(verify_key_confirmation(SECRET, Role::Responder, &tag).is_err());
+        assert!(verify_key_confirmation(SECRET, Role::Responder, &tag[..31]).is_err());
+    }
+
+    #[test]
+    fn ct_eq_accepts_equal_inputs() {
+        assert!(ct_eq(b"shared secret", b"shared secret"));
+        assert!(ct_eq(b"", b""));
+    }
+
+    #[test]
+    fn ct_eq_rejects_unequal_inputs_of_the_same_length() {
+        let secret = [0x5au8; 32];
+        for i in 0..secret.len() {
+            let mut flipped = secret;
+            flipped[i] ^= 0x01;
+            assert!(!ct_eq(&flipped, &secret), "bit flip at byte {} compared equal", i);
+        }
+    }
+
+    #[test]
+    fn ct_eq_rejects_length_mismatch() {
+        let secret = [0x5au8; 32];
+        assert!(!ct_eq(&secret[..31], &secret));
+        assert!(!ct_eq(&secret, &secret[..31]));
+        assert!(!ct_eq(&[], &secret));
+    }
+}
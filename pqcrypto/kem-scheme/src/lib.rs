@@ -0,0 +1,228 @@
+//! # A common interface for KEM examples
+//!
+//! The NTRU and FrodoKEM examples walk through the same key exchange: the recipient
+//! generates a keypair, the sender encapsulates against the public key, the recipient
+//! decapsulates, and both shared secrets are compared. `KemScheme` captures the three
+//! operations and the sizes of their outputs, and `run_kem_demo` runs that exchange for any
+//! implementation, so a new KEM only needs an impl of the trait. Schemes that wrap a
+//! pqcrypto module are declared with the `kem_scheme!` macro below.
+//!
+//! Implementations are behind features so an example only builds the pqcrypto crate it uses:
+//!
+//! ```toml
+//! [dependencies]
+//! kem_scheme = { path = "../kem-scheme", features = ["ntru"] }
+//! ```
+
+use pqcrypto_traits::kem::{Ciphertext, PublicKey, SecretKey, SharedSecret};
+use kem_kdf::ct_eq;
+
+/// Sizes in bytes of a KEM's keys, ciphertext and shared secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KemSizes {
+    pub public_key: usize,
+    pub secret_key: usize,
+    pub ciphertext: usize,
+    pub shared_secret: usize,
+}
+
+/// A key encapsulation mechanism with its key, ciphertext and shared secret types
+pub trait KemScheme {
+    /// Human-readable algorithm name, e.g. `"NTRU-HRSS-701"`
+    const NAME: &'static str;
+
+    type PublicKey: PublicKey;
+    type SecretKey: SecretKey;
+    type Ciphertext: Ciphertext;
+    type SharedSecret: SharedSecret;
+
+    /// Generates a fresh keypair for the recipient
+    fn keypair() -> (Self::PublicKey, Self::SecretKey);
+
+    /// Creates a shared secret and the ciphertext that carries it to the holder of `pk`
+    fn encapsulate(pk: &Self::PublicKey) -> (Self::SharedSecret, Self::Ciphertext);
+
+    /// Recovers the shared secret from `ct` with the recipient's secret key
+    fn decapsulate(ct: &Self::Ciphertext, sk: &Self::SecretKey) -> Self::SharedSecret;
+
+    /// Sizes of the scheme's outputs, known without running it
+    fn sizes() -> KemSizes;
+}
+
+/// Declares a unit struct for one pqcrypto KEM module and implements `KemScheme` for it
+macro_rules! kem_scheme {
+    ($(#[$meta:meta])* $feature:literal, $scheme:ident, $name:literal, $($module:ident)::+) => {
+        $(#[$meta])*
+        #[cfg(feature = $feature)]
+        pub struct $scheme;
+
+        #[cfg(feature = $feature)]
+        impl KemScheme for $scheme {
+            const NAME: &'static str = $name;
+
+            type PublicKey = $($module)::+::PublicKey;
+            type SecretKey = $($module)::+::SecretKey;
+            type Ciphertext = $($module)::+::Ciphertext;
+            type SharedSecret = $($module)::+::SharedSecret;
+
+            fn keypair() -> (Self::PublicKey, Self::SecretKey) {
+                $($module)::+::keypair()
+            }
+
+            fn encapsulate(pk: &Self::PublicKey) -> (Self::SharedSecret, Self::Ciphertext) {
+                $($module)::+::encapsulate(pk)
+            }
+
+            fn decapsulate(ct: &Self::Ciphertext, sk: &Self::SecretKey) -> Self::SharedSecret {
+                $($module)::+::decapsulate(ct, sk)
+            }
+
+            fn sizes() -> KemSizes {
+                KemSizes {
+                    public_key: $($module)::+::public_key_bytes(),
+                    secret_key: $($module)::+::secret_key_bytes(),
+                    ciphertext: $($module)::+::ciphertext_bytes(),
+                    shared_secret: $($module)::+::shared_secret_bytes(),
+                }
+            }
+        }
+    };
+}
+
+kem_scheme!(
+    /// NTRU-HRSS-701 from pqcrypto-ntru
+    "ntru", NtruHrss701, "NTRU-HRSS-701", pqcrypto_ntru::ntruhrss701
+);
+kem_scheme!(
+    /// NTRU-HPS-2048-509 from pqcrypto-ntru, NIST level 1
+    "ntru", NtruHps2048509, "NTRU-HPS-2048-509", pqcrypto_ntru::ntruhps2048509
+);
+kem_scheme!(
+    /// NTRU-HPS-2048-677 from pqcrypto-ntru, NIST level 3
+    "ntru", NtruHps2048677, "NTRU-HPS-2048-677", pqcrypto_ntru::ntruhps2048677
+);
+kem_scheme!(
+    /// NTRU-HPS-4096-821 from pqcrypto-ntru, NIST level 5
+    "ntru", NtruHps4096821, "NTRU-HPS-4096-821", pqcrypto_ntru::ntruhps4096821
+);
+kem_scheme!(
+    /// FrodoKEM-640 with AES128 matrix generation, NIST level 1
+    "frodo", FrodoKem640Aes, "FrodoKEM-640-AES", pqcrypto_frodo::frodokem640aes
+);
+kem_scheme!(
+    /// FrodoKEM-640 with SHAKE128 matrix generation, NIST level 1
+    "frodo", FrodoKem640Shake, "FrodoKEM-640-SHAKE", pqcrypto_frodo::frodokem640shake
+);
+kem_scheme!(
+    /// FrodoKEM-976 with AES128 matrix generation, NIST level 3
+    "frodo", FrodoKem976Aes, "FrodoKEM-976-AES", pqcrypto_frodo::frodokem976aes
+);
+kem_scheme!(
+    /// FrodoKEM-976 with SHAKE128 matrix generation, NIST level 3
+    "frodo", FrodoKem976Shake, "FrodoKEM-976-SHAKE", pqcrypto_frodo::frodokem976shake
+);
+kem_scheme!(
+    /// FrodoKEM-1344 with AES128 matrix generation, NIST level 5
+    "frodo", FrodoKem1344Aes, "FrodoKEM-1344-AES", pqcrypto_frodo::frodokem1344aes
+);
+kem_scheme!(
+    /// FrodoKEM-1344 with SHAKE128 matrix generation, NIST level 5
+    "frodo", FrodoKem1344Shake, "FrodoKEM-1344-SHAKE", pqcrypto_frodo::frodokem1344shake
+);
+
+/// Outcome of one key exchange run by `run_kem_demo`
+#[derive(Debug, Clone, Copy)]
+pub struct KemRun {
+    pub sizes: KemSizes,
+    /// The sender's and recipient's shared secrets are equal, compared in constant time
+    pub secrets_match: bool,
+    /// Every key, ciphertext and shared secret has the length `K::sizes()` reports
+    pub sizes_match: bool,
+}
+
+/// Runs keygen, encapsulate and decapsulate with `K` and compares the two shared secrets
+pub fn run_kem_demo<K: KemScheme>() -> KemRun {
+    let sizes = K::sizes();
+    let (pk, sk) = K::keypair();
+    let (sender_secret, ciphertext) = K::encapsulate(&pk);
+    let recipient_secret = K::decapsulate(&ciphertext, &sk);
+    KemRun {
+        sizes,
+        secrets_match: ct_eq(sender_secret.as_bytes(), recipient_secret.as_bytes()),
+        sizes_match: pk.as_bytes().len() == sizes.public_key
+            && sk.as_bytes().len() == sizes.secret_key
+            && ciphertext.as_bytes().len() == sizes.ciphertext
+            && recipient_secret.as_bytes().len() == sizes.shared_secret,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_round_trip<K: KemScheme>() {
+        let run = run_kem_demo::<K>();
+        assert!(run.secrets_match, "{}: shared secrets differ", K::NAME);
+        assert!(run.sizes_match, "{}: output sizes differ from sizes()", K::NAME);
+        assert_eq!(run.sizes, K::sizes());
+    }
+
+    #[cfg(feature = "ntru")]
+    #[test]
+    fn ntru_schemes_round_trip() {
+        assert_round_trip::<NtruHrss701>();
+        assert_round_trip::<NtruHps2048509>();
+        assert_round_trip::<NtruHps2048677>();
+        assert_round_trip::<NtruHps4096821>();
+    }
+
+    #[cfg(feature = "frodo")]
+    #[test]
+    fn frodo_schemes_round_trip() {
+        assert_round_trip::<FrodoKem640Aes>();
+        assert_round_trip::<FrodoKem640Shake>();
+        assert_round_trip::<FrodoKem976Aes>();
+        assert_round_trip::<FrodoKem976Shake>();
+        assert_round_trip::<FrodoKem1344Aes>();
+        assert_round_trip::<FrodoKem1344Shake>();
+    }
+
+    /// Decapsulates with a secret key other than the one the ciphertext was made for
+    #[cfg(feature = "ntru")]
+    struct WrongRecipient;
+
+    #[cfg(feature = "ntru")]
+    impl KemScheme for WrongRecipient {
+        const NAME: &'static str = "NTRU-HRSS-701 (wrong recipient)";
+
+        type PublicKey = pqcrypto_ntru::ntruhrss701::PublicKey;
+        type SecretKey = pqcrypto_ntru::ntruhrss701::SecretKey;
+        type Ciphertext = pqcrypto_ntru::ntruhrss701::Ciphertext;
+        type SharedSecret = pqcrypto_ntru::ntruhrss701::SharedSecret;
+
+        fn keypair() -> (Self::PublicKey, Self::SecretKey) {
+            NtruHrss701::keypair()
+        }
+
+        fn encapsulate(pk: &Self::PublicKey) -> (Self::SharedSecret, Self::Ciphertext) {
+            NtruHrss701::encapsulate(pk)
+        }
+
+        fn decapsulate(ct: &Self::Ciphertext, _sk: &Self::SecretKey) -> Self::SharedSecret {
+            let (_, other_sk) = NtruHrss701::keypair();
+            NtruHrss701::decapsulate(ct, &other_sk)
+        }
+
+        fn sizes() -> KemSizes {
+            NtruHrss701::sizes()
+        }
+    }
+
+    #[cfg(feature = "ntru")]
+    #[test]
+    fn mismatched_secrets_are_reported() {
+        let run = run_kem_demo::<WrongRecipient>();
+        assert!(!run.secrets_match);
+        assert!(run.sizes_match);
+    }
+}
@@ -0,0 +1,116 @@
+//! # Hybrid vs pure NTRU handshake cost
+//!
+//! Measures a full X25519 + NTRU-HRSS-701 hybrid handshake (both encapsulations, both
+//! decapsulations and the HKDF-SHA256 combine) against a plain NTRU-HRSS-701 handshake,
+//! so the overhead of the hybrid can be judged with data.
+//!
+//! Run with `cargo bench --bench hybrid`. Before the Criterion runs, the combined public key
+//! and ciphertext sizes are printed along with a quick estimate of the per-handshake delta.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use hkdf::Hkdf;
+use kem_kdf::ct_eq;
+use pqcrypto_ntru::ntruhrss701::{self, ciphertext_bytes, decapsulate, encapsulate, keypair, public_key_bytes};
+use pqcrypto_traits::kem::SharedSecret;
+use rand::rngs::OsRng;
+use sha2::Sha256;
+use std::time::{Duration, Instant};
+use x25519_dalek::{EphemeralSecret, PublicKey as X25519PublicKey, StaticSecret};
+
+/// Size of an X25519 public key, which is also the size of its "ciphertext" (the ephemeral key)
+const X25519_LEN: usize = 32;
+
+/// HKDF info string binding the combined key to this construction
+const HYBRID_INFO: &[u8] = b"Quantova X25519+NTRU-HRSS-701 hybrid v1";
+
+/// Derives the session key from both shared secrets, classical first
+fn combine(classical: &[u8], post_quantum: &[u8]) -> [u8; 32] {
+    let mut ikm = Vec::with_capacity(classical.len() + post_quantum.len());
+    ikm.extend_from_slice(classical);
+    ikm.extend_from_slice(post_quantum);
+    let mut okm = [0u8; 32];
+    Hkdf::<Sha256>::new(None, &ikm)
+        .expand(HYBRID_INFO, &mut okm)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    okm
+}
+
+/// Recipient key pair for the hybrid: a static X25519 key and an NTRU key
+struct HybridRecipient {
+    x25519_secret: StaticSecret,
+    x25519_public: X25519PublicKey,
+    ntru_public: ntruhrss701::PublicKey,
+    ntru_secret: ntruhrss701::SecretKey,
+}
+
+impl HybridRecipient {
+    fn generate() -> Self {
+        let x25519_secret = StaticSecret::random_from_rng(OsRng);
+        let x25519_public = X25519PublicKey::from(&x25519_secret);
+        let (ntru_public, ntru_secret) = keypair();
+        Self { x25519_secret, x25519_public, ntru_public, ntru_secret }
+    }
+}
+
+/// One pure NTRU handshake: encapsulate to the recipient, then decapsulate
+fn ntru_handshake(public_key: &ntruhrss701::PublicKey, secret_key: &ntruhrss701::SecretKey) -> bool {
+    let (sender_secret, ciphertext) = encapsulate(public_key);
+    let recipient_secret = decapsulate(&ciphertext, secret_key);
+    ct_eq(sender_secret.as_bytes(), recipient_secret.as_bytes())
+}
+
+/// One hybrid handshake: ephemeral X25519 plus NTRU encapsulation on the sender side,
+/// the matching operations on the recipient side, and the HKDF combine on both
+fn hybrid_handshake(recipient: &HybridRecipient) -> bool {
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = X25519PublicKey::from(&ephemeral);
+    let sender_classical = ephemeral.diffie_hellman(&recipient.x25519_public);
+    let (sender_pq, ciphertext) = encapsulate(&recipient.ntru_public);
+    let sender_key = combine(sender_classical.as_bytes(), sender_pq.as_bytes());
+
+    let recipient_classical = recipient.x25519_secret.diffie_hellman(&ephemeral_public);
+    let recipient_pq = decapsulate(&ciphertext, &recipient.ntru_secret);
+    let recipient_key = combine(recipient_classical.as_bytes(), recipient_pq.as_bytes());
+
+    ct_eq(&sender_key, &recipient_key)
+}
+
+/// Average wall-clock time of `iterations` calls to `f`, for the quick delta estimate
+fn average(iterations: u32, mut f: impl FnMut() -> bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        assert!(black_box(f()), "Handshake produced mismatched keys");
+    }
+    start.elapsed() / iterations
+}
+
+fn report_sizes_and_delta(recipient: &HybridRecipient) {
+    println!("Public key:  NTRU {} bytes, hybrid {} bytes", public_key_bytes(), public_key_bytes() + X25519_LEN);
+    println!("Ciphertext:  NTRU {} bytes, hybrid {} bytes", ciphertext_bytes(), ciphertext_bytes() + X25519_LEN);
+
+    let ntru = average(200, || ntru_handshake(&recipient.ntru_public, &recipient.ntru_secret));
+    let hybrid = average(200, || hybrid_handshake(recipient));
+    let overhead = hybrid.saturating_sub(ntru);
+    println!(
+        "Handshake:   NTRU {:?}, hybrid {:?}, delta {:?} ({:.1}%)",
+        ntru,
+        hybrid,
+        overhead,
+        overhead.as_secs_f64() / ntru.as_secs_f64() * 100.0
+    );
+}
+
+fn bench_handshakes(c: &mut Criterion) {
+    let recipient = HybridRecipient::generate();
+    report_sizes_and_delta(&recipient);
+
+    let mut group = c.benchmark_group("handshake");
+    group.bench_function("ntru-hrss701", |b| {
+        b.iter(|| ntru_handshake(black_box(&recipient.ntru_public), black_box(&recipient.ntru_secret)))
+    });
+    group.bench_function("x25519+ntru-hrss701", |b| b.iter(|| hybrid_handshake(black_box(&recipient))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_handshakes);
+criterion_main!(benches);
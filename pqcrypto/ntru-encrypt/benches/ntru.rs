@@ -0,0 +1,35 @@
+//! # NTRU-HRSS-701 operation cost
+//!
+//! Measures key generation, encapsulation and decapsulation separately, so each phase can be
+//! checked against a latency budget. Every benchmark counts one operation per iteration, so
+//! Criterion reports throughput in operations per second next to the time per operation.
+//!
+//! Run with `cargo bench --bench ntru`. Encapsulation and decapsulation reuse one key pair
+//! (and decapsulation one ciphertext) generated before timing starts, so only the operation
+//! itself is measured.
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use pqcrypto_ntru::ntruhrss701::{decapsulate, encapsulate, keypair};
+use pqcrypto_traits::kem::SharedSecret;
+
+fn bench_ntru(c: &mut Criterion) {
+    let (public_key, secret_key) = keypair();
+    let (shared_secret, ciphertext) = encapsulate(&public_key);
+    assert_eq!(
+        decapsulate(&ciphertext, &secret_key).as_bytes(),
+        shared_secret.as_bytes(),
+        "Key pair does not round-trip"
+    );
+
+    let mut group = c.benchmark_group("ntru-hrss701");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("keygen", |b| b.iter(keypair));
+    group.bench_function("encapsulate", |b| b.iter(|| encapsulate(black_box(&public_key))));
+    group.bench_function("decapsulate", |b| {
+        b.iter(|| decapsulate(black_box(&ciphertext), black_box(&secret_key)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_ntru);
+criterion_main!(benches);
@@ -14,6 +14,13 @@
 //! This implementation uses NTRU-HRSS-701, which is one of the parameter sets that offers
 //! a high security level (equivalent to AES-256).
 //!
+//! Run with `--params <name>` (e.g. `--params ntruhps2048677`) to run a key exchange with
+//! another parameter set instead, or `--params all` to compare key and ciphertext sizes
+//! across every set built into the binary. `--check-secret-health` rejects a degenerate
+//! shared secret (e.g. all zeros) after decapsulation instead of using it as key material.
+//! The encrypted key container step reads its password from `NTRU_KEY_PASSWORD`, or prompts
+//! for one if the variable is unset.
+//!
 //! ## Dependencies Required
 //! 
 //! ```toml
@@ -21,6 +28,12 @@
 //! pqcrypto-traits = "0.3.4"
 //! pqcrypto-ntru = "0.5.1"
 //! rand = "0.8.5"
+//! argon2 = "0.5"
+//! aes-gcm = "0.10"
+//! kem_kdf = { path = "../kem-kdf" }
+//! kem_scheme = { path = "../kem-scheme", features = ["ntru"] }
+//! base64 = "0.22"
+//! zeroize = "1.8"
 //! ```
 
 use pqcrypto_ntru::{
@@ -31,9 +44,695 @@ use pqcrypto_ntru::{
 };
 use pqcrypto_traits::kem::{PublicKey, SecretKey, Ciphertext, SharedSecret};
 use rand::rngs::OsRng;
+use rand::RngCore;
+use aes_gcm::{Aes256Gcm, Nonce, aead::{Aead, KeyInit, Payload}};
+use argon2::{Argon2, Params};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use zeroize::Zeroizing;
+use kem_kdf::{Role, confirm_key, ct_eq, verify_key_confirmation};
+use kem_scheme::{KemRun, KemScheme, KemSizes, NtruHps2048509, NtruHps2048677, NtruHps4096821, NtruHrss701, run_kem_demo};
+
+/// Reason a shared secret was judged degenerate by `assert_secret_healthy`
+#[derive(Debug)]
+enum HealthError {
+    Empty,
+    AllZero,
+    /// Every byte has the same value
+    Constant(u8),
+    /// One byte value occurs far more often than a random secret allows
+    Skewed { byte: u8, count: usize },
+}
+
+impl std::fmt::Display for HealthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HealthError::Empty => write!(f, "shared secret is empty"),
+            HealthError::AllZero => write!(f, "shared secret is all zeros"),
+            HealthError::Constant(byte) => write!(f, "every byte of the shared secret is {:#04x}", byte),
+            HealthError::Skewed { byte, count } => {
+                write!(f, "byte {:#04x} occurs {} times in the shared secret", byte, count)
+            }
+        }
+    }
+}
+
+/// Rejects a shared secret that looks degenerate, e.g. the all-zero output of a broken
+/// decapsulation, before it is used as key material
+///
+/// Besides the all-zero and constant cases, secrets of 16 bytes or more fail if any byte
+/// value makes up over a quarter of them. For a uniformly random 32-byte secret that happens
+/// with probability below 2^-32, so a failure points at a broken KEM, not bad luck. This is
+/// a sanity check for catastrophic failures, not a measure of entropy.
+fn assert_secret_healthy(secret: &[u8]) -> Result<(), HealthError> {
+    let Some(&first) = secret.first() else {
+        return Err(HealthError::Empty);
+    };
+    if secret.iter().all(|&b| b == first) {
+        return Err(if first == 0 { HealthError::AllZero } else { HealthError::Constant(first) });
+    }
+    if secret.len() >= 16 {
+        let mut counts = [0usize; 256];
+        for &b in secret {
+            counts[b as usize] += 1;
+        }
+        let (byte, &count) = counts.iter().enumerate().max_by_key(|&(_, count)| *count).unwrap();
+        if count > secret.len() / 4 {
+            return Err(HealthError::Skewed { byte: byte as u8, count });
+        }
+    }
+    Ok(())
+}
+
+/// Decapsulates `ciphertext` and, when `check_health` is set, runs `assert_secret_healthy` on
+/// the result so a degenerate secret comes back as an error instead of key material
+fn decapsulate_with_health_check(
+    ciphertext: &pqcrypto_ntru::ntruhrss701::Ciphertext,
+    secret_key: &pqcrypto_ntru::ntruhrss701::SecretKey,
+    check_health: bool,
+) -> Result<pqcrypto_ntru::ntruhrss701::SharedSecret, HealthError> {
+    let shared_secret = decapsulate(ciphertext, secret_key);
+    if check_health {
+        assert_secret_healthy(shared_secret.as_bytes())?;
+    }
+    Ok(shared_secret)
+}
+
+/// Error returned when a ciphertext cannot be opened with the given secret key
+#[derive(Debug)]
+enum DecryptError {
+    /// The authentication check failed: the ciphertext was made for a different key pair
+    /// or was modified in transit
+    WrongKey,
+}
+
+impl std::fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecryptError::WrongKey => write!(f, "decryption failed: ciphertext was not made for this secret key"),
+        }
+    }
+}
+
+/// Decapsulates `ciphertext` and authenticates the result with the sender's key-confirmation tag
+///
+/// NTRU uses implicit rejection: decapsulating with the wrong (but correctly sized) secret key
+/// does not fail, it returns a pseudorandom shared secret unrelated to the sender's. Plain
+/// `decapsulate` therefore cannot notice a key mismatch. The sender sends its initiator
+/// `confirm_key` tag alongside the ciphertext, and a tag that does not match is reported as
+/// `DecryptError::WrongKey` instead of handing back a useless key.
+fn decapsulate_checked(
+    ciphertext: &pqcrypto_ntru::ntruhrss701::Ciphertext,
+    secret_key: &pqcrypto_ntru::ntruhrss701::SecretKey,
+    sender_tag: &[u8],
+) -> Result<pqcrypto_ntru::ntruhrss701::SharedSecret, DecryptError> {
+    let shared_secret = decapsulate(ciphertext, secret_key);
+    verify_key_confirmation(shared_secret.as_bytes(), Role::Initiator, sender_tag).map_err(|_| DecryptError::WrongKey)?;
+    Ok(shared_secret)
+}
+
+/// Error returned when rebuilding an NTRU value from untrusted bytes
+#[derive(Debug)]
+enum ParseError {
+    /// The input does not have the size of the expected value
+    LengthMismatch { expected: usize, actual: usize },
+    /// pqcrypto rejected the bytes
+    Rejected(pqcrypto_traits::Error),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::LengthMismatch { expected, actual } => write!(f, "expected {} bytes, got {}", expected, actual),
+            ParseError::Rejected(e) => write!(f, "bytes rejected: {}", e),
+        }
+    }
+}
+
+/// Validated construction from untrusted bytes, so length checks live in one place
+trait CiphertextExt: Sized {
+    fn from_validated_bytes(bytes: &[u8]) -> Result<Self, ParseError>;
+}
+
+impl CiphertextExt for pqcrypto_ntru::ntruhrss701::Ciphertext {
+    fn from_validated_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let expected = ciphertext_bytes();
+        if bytes.len() != expected {
+            return Err(ParseError::LengthMismatch { expected, actual: bytes.len() });
+        }
+        <Self as Ciphertext>::from_bytes(bytes).map_err(ParseError::Rejected)
+    }
+}
+
+/// NTRU parameter sets provided by pqcrypto-ntru
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NtruParams {
+    Hrss701,
+    Hps2048509,
+    Hps2048677,
+    Hps4096821,
+}
+
+impl NtruParams {
+    const ALL: [NtruParams; 4] = [
+        NtruParams::Hrss701,
+        NtruParams::Hps2048509,
+        NtruParams::Hps2048677,
+        NtruParams::Hps4096821,
+    ];
+
+    /// Key, ciphertext and shared secret sizes of a parameter set, known without performing a
+    /// key exchange, e.g. for sizing buffers that hold derived keys
+    fn sizes(self) -> KemSizes {
+        match self {
+            NtruParams::Hrss701 => NtruHrss701::sizes(),
+            NtruParams::Hps2048509 => NtruHps2048509::sizes(),
+            NtruParams::Hps2048677 => NtruHps2048677::sizes(),
+            NtruParams::Hps4096821 => NtruHps4096821::sizes(),
+        }
+    }
+}
+
+/// Runs a full key exchange with `params` through `kem_scheme::run_kem_demo`, or returns
+/// `None` if the binary was built without that parameter set's feature
+fn run_params(params: NtruParams) -> Option<KemRun> {
+    match params {
+        #[cfg(feature = "hrss701")]
+        NtruParams::Hrss701 => Some(run_kem_demo::<NtruHrss701>()),
+        #[cfg(feature = "hps2048509")]
+        NtruParams::Hps2048509 => Some(run_kem_demo::<NtruHps2048509>()),
+        #[cfg(feature = "hps2048677")]
+        NtruParams::Hps2048677 => Some(run_kem_demo::<NtruHps2048677>()),
+        #[cfg(feature = "hps4096821")]
+        NtruParams::Hps4096821 => Some(run_kem_demo::<NtruHps4096821>()),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// Runs `run_params` for each of `selected` and prints their sizes side by side
+fn compare_params(selected: &[NtruParams]) {
+    println!("{:<16} {:>10} {:>10} {:>10} {:>8}  key exchange", "parameter set", "public", "secret", "ciphertext", "secret");
+    for &params in selected {
+        match run_params(params) {
+            Some(run) => println!(
+                "{:<16} {:>10} {:>10} {:>10} {:>8}  {}",
+                params.name(),
+                run.sizes.public_key,
+                run.sizes.secret_key,
+                run.sizes.ciphertext,
+                run.sizes.shared_secret,
+                if !run.secrets_match {
+                    "MISMATCH"
+                } else if !run.sizes_match {
+                    "unexpected output sizes"
+                } else {
+                    "shared secrets match"
+                }
+            ),
+            None => println!("{:<16} not built into this binary (enable the \"{}\" feature)", params.name(), params.feature()),
+        }
+    }
+}
+
+impl NtruParams {
+    /// Numeric identifier written into ciphertext envelopes
+    fn id(self) -> u16 {
+        match self {
+            NtruParams::Hrss701 => 1,
+            NtruParams::Hps2048509 => 2,
+            NtruParams::Hps2048677 => 3,
+            NtruParams::Hps4096821 => 4,
+        }
+    }
+
+    fn from_id(id: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|params| params.id() == id)
+    }
+
+    /// Algorithm identifier written into exported key containers
+    fn name(self) -> &'static str {
+        match self {
+            NtruParams::Hrss701 => "ntruhrss701",
+            NtruParams::Hps2048509 => "ntruhps2048509",
+            NtruParams::Hps2048677 => "ntruhps2048677",
+            NtruParams::Hps4096821 => "ntruhps4096821",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|params| params.name() == name)
+    }
+
+    /// Cargo feature that builds this parameter set into the `--params` dispatcher
+    fn feature(self) -> &'static str {
+        self.name().trim_start_matches("ntru")
+    }
+}
+
+const CIPHERTEXT_ENVELOPE_VERSION: u8 = 1;
+/// Version byte plus the big-endian algorithm id
+const CIPHERTEXT_ENVELOPE_HEADER_LEN: usize = 3;
+
+/// Error returned when a ciphertext envelope does not hold a ciphertext for the expected
+/// parameter set
+#[derive(Debug)]
+enum CiphertextEnvelopeError {
+    /// The input is shorter than the header
+    Malformed,
+    UnsupportedVersion(u8),
+    UnknownAlgorithm(u16),
+    /// The envelope names a different parameter set than the one the caller decapsulates with
+    AlgorithmMismatch { expected: NtruParams, found: NtruParams },
+    /// The ciphertext does not have the size of the named parameter set
+    Length(ParseError),
+}
+
+impl std::fmt::Display for CiphertextEnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CiphertextEnvelopeError::Malformed => write!(f, "ciphertext envelope is too short"),
+            CiphertextEnvelopeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported ciphertext envelope version {}", version)
+            }
+            CiphertextEnvelopeError::UnknownAlgorithm(id) => write!(f, "unknown algorithm id {} in ciphertext envelope", id),
+            CiphertextEnvelopeError::AlgorithmMismatch { expected, found } => {
+                write!(f, "ciphertext is for {:?}, expected {:?}", found, expected)
+            }
+            CiphertextEnvelopeError::Length(e) => write!(f, "ciphertext length: {}", e),
+        }
+    }
+}
+
+/// A KEM ciphertext tagged with a format version and the parameter set that produced it
+///
+/// Layout: version (1) | algorithm id (2, big-endian) | ciphertext
+#[derive(Debug, Clone)]
+struct CiphertextEnvelope {
+    version: u8,
+    alg_id: u16,
+    bytes: Vec<u8>,
+}
+
+impl CiphertextEnvelope {
+    fn new(params: NtruParams, ciphertext: &[u8]) -> Self {
+        Self { version: CIPHERTEXT_ENVELOPE_VERSION, alg_id: params.id(), bytes: ciphertext.to_vec() }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(CIPHERTEXT_ENVELOPE_HEADER_LEN + self.bytes.len());
+        out.push(self.version);
+        out.extend_from_slice(&self.alg_id.to_be_bytes());
+        out.extend_from_slice(&self.bytes);
+        out
+    }
+
+    /// Parses an envelope and checks, before anything is decapsulated, that it holds a
+    /// ciphertext of the right length for `expected`
+    fn decode(data: &[u8], expected: NtruParams) -> Result<Self, CiphertextEnvelopeError> {
+        if data.len() < CIPHERTEXT_ENVELOPE_HEADER_LEN {
+            return Err(CiphertextEnvelopeError::Malformed);
+        }
+        let version = data[0];
+        if version != CIPHERTEXT_ENVELOPE_VERSION {
+            return Err(CiphertextEnvelopeError::UnsupportedVersion(version));
+        }
+        let alg_id = u16::from_be_bytes([data[1], data[2]]);
+        let found = NtruParams::from_id(alg_id).ok_or(CiphertextEnvelopeError::UnknownAlgorithm(alg_id))?;
+        if found != expected {
+            return Err(CiphertextEnvelopeError::AlgorithmMismatch { expected, found });
+        }
+        let bytes = &data[CIPHERTEXT_ENVELOPE_HEADER_LEN..];
+        if bytes.len() != found.sizes().ciphertext {
+            return Err(CiphertextEnvelopeError::Length(ParseError::LengthMismatch {
+                expected: found.sizes().ciphertext,
+                actual: bytes.len(),
+            }));
+        }
+        Ok(Self { version, alg_id, bytes: bytes.to_vec() })
+    }
+}
+
+/// Magic bytes at the start of an encrypted key container
+const KEY_CONTAINER_MAGIC: &[u8; 4] = b"QPK8";
+const KEY_CONTAINER_VERSION: u8 = 1;
+const KEY_CONTAINER_SALT_LEN: usize = 16;
+const KEY_CONTAINER_NONCE_LEN: usize = 12;
+/// Upper bounds on the Argon2 costs read from a container, so a crafted container cannot
+/// make the import allocate more than 1 GiB or run for minutes
+const KEY_CONTAINER_MAX_M_COST_KIB: u32 = 1 << 20;
+const KEY_CONTAINER_MAX_T_COST: u32 = 10;
+const KEY_CONTAINER_MAX_P_COST: u32 = 16;
+
+/// Error returned when an encrypted key container cannot be opened
+#[derive(Debug)]
+enum KeyContainerError {
+    /// The bytes are not a key container or are cut short
+    Malformed,
+    UnsupportedVersion(u8),
+    UnknownAlgorithm(String),
+    /// The stored Argon2 parameters are invalid or above the import limits
+    InvalidKdfParams,
+    /// The password is wrong or the container was modified
+    Decryption,
+    /// The decrypted key does not have the size of the named parameter set
+    KeyLength { expected: usize, actual: usize },
+}
+
+impl std::fmt::Display for KeyContainerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyContainerError::Malformed => write!(f, "not a valid encrypted key container"),
+            KeyContainerError::UnsupportedVersion(version) => write!(f, "unsupported key container version {}", version),
+            KeyContainerError::UnknownAlgorithm(name) => write!(f, "unknown algorithm {:?} in key container", name),
+            KeyContainerError::InvalidKdfParams => write!(f, "invalid Argon2 parameters in key container"),
+            KeyContainerError::Decryption => write!(f, "wrong password or corrupted key container"),
+            KeyContainerError::KeyLength { expected, actual } => {
+                write!(f, "decrypted key is {} bytes, expected {}", actual, expected)
+            }
+        }
+    }
+}
+
+/// Derives the 256-bit container key from a password with Argon2id; it is wiped on drop
+fn derive_container_key(password: &[u8], salt: &[u8], params: Params) -> Result<Zeroizing<[u8; 32]>, KeyContainerError> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+        .hash_password_into(password, salt, key.as_mut_slice())
+        .map_err(|_| KeyContainerError::InvalidKdfParams)?;
+    Ok(key)
+}
+
+/// Wraps a secret key in a self-describing, password-protected container inspired by
+/// PKCS#8's EncryptedPrivateKeyInfo (it is not DER and not byte-compatible with PKCS#8)
+///
+/// Layout, with integers big-endian:
+///
+/// ```text
+/// "QPK8" | version (1) | algorithm name length (1) | algorithm name
+///        | Argon2id memory KiB (4) | iterations (4) | parallelism (4) | salt (16) | nonce (12)
+///        | AES-256-GCM ciphertext of the secret key, with tag (16)
+/// ```
+///
+/// Everything before the ciphertext is authenticated as associated data, so the algorithm
+/// and KDF parameters cannot be swapped without the decryption failing.
+fn export_pkcs8_encrypted(secret_key: &[u8], password: &[u8], params: NtruParams) -> Vec<u8> {
+    let kdf_params = Params::default();
+    let mut salt = [0u8; KEY_CONTAINER_SALT_LEN];
+    let mut nonce = [0u8; KEY_CONTAINER_NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let name = params.name().as_bytes();
+    let mut container = Vec::new();
+    container.extend_from_slice(KEY_CONTAINER_MAGIC);
+    container.push(KEY_CONTAINER_VERSION);
+    container.push(name.len() as u8);
+    container.extend_from_slice(name);
+    container.extend_from_slice(&kdf_params.m_cost().to_be_bytes());
+    container.extend_from_slice(&kdf_params.t_cost().to_be_bytes());
+    container.extend_from_slice(&kdf_params.p_cost().to_be_bytes());
+    container.extend_from_slice(&salt);
+    container.extend_from_slice(&nonce);
+
+    let key = derive_container_key(password, &salt, kdf_params).expect("Default Argon2 parameters are valid");
+    let cipher = Aes256Gcm::new_from_slice(key.as_slice()).expect("Container key is 32 bytes");
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: secret_key, aad: &container })
+        .expect("AES-GCM encryption of a secret key cannot fail");
+    container.extend_from_slice(&ciphertext);
+    container
+}
+
+/// Opens a container written by `export_pkcs8_encrypted`, returning the parameter set it
+/// names and the secret key bytes, which are wiped on drop
+fn import_pkcs8_encrypted(
+    container: &[u8],
+    password: &[u8],
+) -> Result<(NtruParams, Zeroizing<Vec<u8>>), KeyContainerError> {
+    fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], KeyContainerError> {
+        if input.len() < len {
+            return Err(KeyContainerError::Malformed);
+        }
+        let (head, rest) = input.split_at(len);
+        *input = rest;
+        Ok(head)
+    }
+    fn take_u32(input: &mut &[u8]) -> Result<u32, KeyContainerError> {
+        let bytes: [u8; 4] = take(input, 4)?.try_into().map_err(|_| KeyContainerError::Malformed)?;
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    let mut rest = container;
+    if take(&mut rest, KEY_CONTAINER_MAGIC.len())? != KEY_CONTAINER_MAGIC {
+        return Err(KeyContainerError::Malformed);
+    }
+    let version = take(&mut rest, 1)?[0];
+    if version != KEY_CONTAINER_VERSION {
+        return Err(KeyContainerError::UnsupportedVersion(version));
+    }
+    let name_len = take(&mut rest, 1)?[0] as usize;
+    let name = String::from_utf8_lossy(take(&mut rest, name_len)?).into_owned();
+    let params = NtruParams::from_name(&name).ok_or(KeyContainerError::UnknownAlgorithm(name))?;
+    let (m_cost, t_cost, p_cost) = (take_u32(&mut rest)?, take_u32(&mut rest)?, take_u32(&mut rest)?);
+    if m_cost > KEY_CONTAINER_MAX_M_COST_KIB || t_cost > KEY_CONTAINER_MAX_T_COST || p_cost > KEY_CONTAINER_MAX_P_COST {
+        return Err(KeyContainerError::InvalidKdfParams);
+    }
+    let kdf_params = Params::new(m_cost, t_cost, p_cost, None).map_err(|_| KeyContainerError::InvalidKdfParams)?;
+    let salt = take(&mut rest, KEY_CONTAINER_SALT_LEN)?;
+    let nonce = take(&mut rest, KEY_CONTAINER_NONCE_LEN)?;
+    let header = &container[..container.len() - rest.len()];
+
+    let key = derive_container_key(password, salt, kdf_params)?;
+    let cipher = Aes256Gcm::new_from_slice(key.as_slice()).expect("Container key is 32 bytes");
+    let secret_key = Zeroizing::new(
+        cipher
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: rest, aad: header })
+            .map_err(|_| KeyContainerError::Decryption)?,
+    );
+    let expected = params.sizes().secret_key;
+    if secret_key.len() != expected {
+        return Err(KeyContainerError::KeyLength { expected, actual: secret_key.len() });
+    }
+    Ok((params, secret_key))
+}
+
+/// Algorithm part of the PEM labels, as in `-----BEGIN NTRU-HRSS-701 PUBLIC KEY-----`
+const PEM_ALGORITHM: &str = "NTRU-HRSS-701";
+/// Base64 characters per line in a PEM body
+const PEM_LINE_LEN: usize = 64;
+
+/// Error returned when a PEM block cannot be read back as an NTRU key
+#[derive(Debug)]
+enum PemError {
+    /// The text is not a single BEGIN/END block with matching labels
+    Malformed,
+    /// The block holds a different parameter set or key type than the one requested
+    LabelMismatch { expected: String, found: String },
+    Base64(base64::DecodeError),
+    /// The decoded key has the wrong size or was rejected by pqcrypto
+    Key(ParseError),
+}
+
+impl std::fmt::Display for PemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PemError::Malformed => write!(f, "not a PEM block"),
+            PemError::LabelMismatch { expected, found } => {
+                write!(f, "PEM block is labeled {:?}, expected {:?}", found, expected)
+            }
+            PemError::Base64(e) => write!(f, "invalid base64 in PEM body: {}", e),
+            PemError::Key(e) => write!(f, "invalid key in PEM block: {}", e),
+        }
+    }
+}
+
+/// Wraps `bytes` in a PEM block labeled `label`, with the base64 body split into 64-character lines
+fn to_pem(label: &str, bytes: &[u8]) -> String {
+    let body = BASE64.encode(bytes);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(PEM_LINE_LEN) {
+        pem.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// Reads the body of a PEM block, checking that it is labeled `expected_label`
+fn from_pem(text: &str, expected_label: &str) -> Result<Vec<u8>, PemError> {
+    let mut lines = text.trim().lines().map(str::trim);
+    let begin = lines.next().and_then(|line| line.strip_prefix("-----BEGIN ")?.strip_suffix("-----"));
+    let end = lines.next_back().and_then(|line| line.strip_prefix("-----END ")?.strip_suffix("-----"));
+    let (Some(label), Some(end)) = (begin, end) else {
+        return Err(PemError::Malformed);
+    };
+    if label != end {
+        return Err(PemError::Malformed);
+    }
+    if label != expected_label {
+        return Err(PemError::LabelMismatch { expected: expected_label.to_string(), found: label.to_string() });
+    }
+    BASE64.decode(lines.collect::<String>()).map_err(PemError::Base64)
+}
+
+fn public_key_pem_label() -> String {
+    format!("{} PUBLIC KEY", PEM_ALGORITHM)
+}
+
+fn secret_key_pem_label() -> String {
+    format!("{} SECRET KEY", PEM_ALGORITHM)
+}
+
+/// Exports a public key as a `NTRU-HRSS-701 PUBLIC KEY` PEM block
+fn public_key_to_pem(pk: &pqcrypto_ntru::ntruhrss701::PublicKey) -> String {
+    to_pem(&public_key_pem_label(), pk.as_bytes())
+}
+
+/// Exports a secret key as a `NTRU-HRSS-701 SECRET KEY` PEM block
+///
+/// The block is not encrypted; use `export_pkcs8_encrypted` for keys stored on disk.
+fn secret_key_to_pem(sk: &pqcrypto_ntru::ntruhrss701::SecretKey) -> String {
+    to_pem(&secret_key_pem_label(), sk.as_bytes())
+}
+
+/// Reads a public key written by `public_key_to_pem`, checking the label and length first
+fn public_key_from_pem(text: &str) -> Result<pqcrypto_ntru::ntruhrss701::PublicKey, PemError> {
+    let bytes = from_pem(text, &public_key_pem_label())?;
+    let expected = public_key_bytes();
+    if bytes.len() != expected {
+        return Err(PemError::Key(ParseError::LengthMismatch { expected, actual: bytes.len() }));
+    }
+    <pqcrypto_ntru::ntruhrss701::PublicKey as PublicKey>::from_bytes(&bytes)
+        .map_err(|e| PemError::Key(ParseError::Rejected(e)))
+}
+
+/// Reads a secret key written by `secret_key_to_pem`, checking the label and length first
+fn secret_key_from_pem(text: &str) -> Result<pqcrypto_ntru::ntruhrss701::SecretKey, PemError> {
+    let bytes = from_pem(text, &secret_key_pem_label())?;
+    let expected = secret_key_bytes();
+    if bytes.len() != expected {
+        return Err(PemError::Key(ParseError::LengthMismatch { expected, actual: bytes.len() }));
+    }
+    <pqcrypto_ntru::ntruhrss701::SecretKey as SecretKey>::from_bytes(&bytes)
+        .map_err(|e| PemError::Key(ParseError::Rejected(e)))
+}
+
+/// HKDF info binding the AES key derived from an NTRU shared secret to `encrypt_with_ntru`
+const NTRU_ENVELOPE_INFO: &[u8] = b"Quantova NTRU envelope v1";
+const NTRU_ENVELOPE_NONCE_LEN: usize = 12;
+/// Envelope format written by `encrypt_with_ntru`; `decrypt_with_ntru` reads this version
+/// and no later one
+const NTRU_ENVELOPE_VERSION: u8 = 1;
+
+/// A payload encrypted to an NTRU public key by `encrypt_with_ntru`
+#[derive(Debug, Clone)]
+struct NtruEnvelope {
+    /// Format version, authenticated along with the NTRU ciphertext
+    version: u8,
+    /// NTRU ciphertext encapsulating the shared secret
+    kem_ciphertext: Vec<u8>,
+    nonce: [u8; NTRU_ENVELOPE_NONCE_LEN],
+    /// AES-256-GCM ciphertext of the payload, with tag
+    aead_ciphertext: Vec<u8>,
+}
+
+/// Error returned when an `NtruEnvelope` cannot be opened
+#[derive(Debug)]
+enum EnvelopeError {
+    /// The NTRU ciphertext has the wrong size
+    Ciphertext(ParseError),
+    /// The AEAD tag did not verify: wrong secret key or modified envelope
+    Decryption,
+    /// The envelope was written in a format this reader does not understand
+    UnsupportedVersion { got: u8, max_supported: u8 },
+}
+
+impl std::fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvelopeError::Ciphertext(e) => write!(f, "invalid NTRU ciphertext: {}", e),
+            EnvelopeError::Decryption => write!(f, "decryption failed: wrong key or modified envelope"),
+            EnvelopeError::UnsupportedVersion { got, max_supported } => {
+                write!(f, "unsupported envelope version {} (this build reads up to version {})", got, max_supported)
+            }
+        }
+    }
+}
+
+/// Derives the AES-256-GCM key from an NTRU shared secret with HKDF-SHA256
+fn envelope_cipher(shared_secret: &[u8]) -> Aes256Gcm {
+    let key = kem_kdf::derive_key(shared_secret, NTRU_ENVELOPE_INFO, 32);
+    Aes256Gcm::new_from_slice(&key).expect("Envelope key is 32 bytes")
+}
+
+/// Encrypts `plaintext` to `pk`: encapsulates a fresh shared secret, derives an AES-256-GCM
+/// key from it and encrypts under a random nonce. The format version and the NTRU
+/// ciphertext are authenticated as associated data.
+fn encrypt_with_ntru(pk: &pqcrypto_ntru::ntruhrss701::PublicKey, plaintext: &[u8]) -> NtruEnvelope {
+    let (shared_secret, kem_ciphertext) = encapsulate(pk);
+    let mut nonce = [0u8; NTRU_ENVELOPE_NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+    let aad = [&[NTRU_ENVELOPE_VERSION][..], kem_ciphertext.as_bytes()].concat();
+    let aead_ciphertext = envelope_cipher(shared_secret.as_bytes())
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext, aad: &aad })
+        .expect("AES-GCM encryption cannot fail for in-memory payloads");
+    NtruEnvelope {
+        version: NTRU_ENVELOPE_VERSION,
+        kem_ciphertext: kem_ciphertext.as_bytes().to_vec(),
+        nonce,
+        aead_ciphertext,
+    }
+}
+
+/// Opens an envelope made by `encrypt_with_ntru` with the recipient's secret key, rejecting
+/// envelopes of an unknown version before interpreting any of their fields
+fn decrypt_with_ntru(
+    sk: &pqcrypto_ntru::ntruhrss701::SecretKey,
+    envelope: &NtruEnvelope,
+) -> Result<Vec<u8>, EnvelopeError> {
+    match envelope.version {
+        1 => decrypt_with_ntru_v1(sk, envelope),
+        got => Err(EnvelopeError::UnsupportedVersion { got, max_supported: NTRU_ENVELOPE_VERSION }),
+    }
+}
+
+fn decrypt_with_ntru_v1(
+    sk: &pqcrypto_ntru::ntruhrss701::SecretKey,
+    envelope: &NtruEnvelope,
+) -> Result<Vec<u8>, EnvelopeError> {
+    let kem_ciphertext = pqcrypto_ntru::ntruhrss701::Ciphertext::from_validated_bytes(&envelope.kem_ciphertext)
+        .map_err(EnvelopeError::Ciphertext)?;
+    let shared_secret = decapsulate(&kem_ciphertext, sk);
+    let aad = [&[envelope.version][..], &envelope.kem_ciphertext].concat();
+    envelope_cipher(shared_secret.as_bytes())
+        .decrypt(Nonce::from_slice(&envelope.nonce), Payload { msg: &envelope.aead_ciphertext, aad: &aad })
+        .map_err(|_| EnvelopeError::Decryption)
+}
 
 /// Main function demonstrating the NTRU key encapsulation workflow
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(position) = args.iter().position(|arg| arg == "--params") {
+        let selected = match args.get(position + 1).map(String::as_str) {
+            Some("all") => NtruParams::ALL.to_vec(),
+            Some(name) => match NtruParams::from_name(name) {
+                Some(params) => vec![params],
+                None => {
+                    let names: Vec<&str> = NtruParams::ALL.iter().map(|params| params.name()).collect();
+                    eprintln!("Unknown parameter set {:?}; expected one of {} or all", name, names.join(", "));
+                    std::process::exit(2);
+                }
+            },
+            None => {
+                eprintln!("--params needs a parameter set name or all");
+                std::process::exit(2);
+            }
+        };
+        compare_params(&selected);
+        return;
+    }
+
     println!("NTRU-HRSS-701 Post-Quantum Cryptography Example");
     println!("===============================================");
     
@@ -72,20 +771,190 @@ fn main() {
     // Decapsulation is performed by the recipient (e.g., Bob) using their private key.
     // This recovers the shared secret from the received ciphertext.
     println!("\nDecapsulating shared secret...");
-    let shared_secret_2 = decapsulate(&ciphertext, &sk);
+    let received = match pqcrypto_ntru::ntruhrss701::Ciphertext::from_validated_bytes(ciphertext.as_bytes()) {
+        Ok(received) => received,
+        Err(e) => {
+            eprintln!("Received ciphertext rejected: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = pqcrypto_ntru::ntruhrss701::Ciphertext::from_validated_bytes(&ct_bytes[1..]) {
+        println!("Truncated ciphertext rejected: {}", e);
+    }
+    // Optional defensive check, enabled with --check-secret-health: refuse to continue with a
+    // degenerate shared secret
+    let check_health = args.iter().any(|arg| arg == "--check-secret-health");
+    let shared_secret_2 = match decapsulate_with_health_check(&received, &sk, check_health) {
+        Ok(shared_secret) => shared_secret,
+        Err(e) => {
+            eprintln!("Unhealthy shared secret: {}", e);
+            return;
+        }
+    };
     
     // Step 4: Verify that both parties have the same shared secret
     // -----------------------------------------------------------
     // In a real-world scenario, both parties would now have the same shared secret
-    // that can be used for symmetric encryption.
-    let success = shared_secret_1.as_bytes() == shared_secret_2.as_bytes();
+    // that can be used for symmetric encryption. The comparison runs in constant time, so
+    // it does not reveal how many leading bytes agree.
+    let success = ct_eq(shared_secret_1.as_bytes(), shared_secret_2.as_bytes());
     println!("Shared secrets match: {}", success);
     
     // Display a preview of the shared secret (for demonstration purposes only)
     let ss_bytes = shared_secret_1.as_bytes();
     println!("Shared secret (first 16 bytes): {:02x?}", &ss_bytes[..16.min(ss_bytes.len())]);
     
-    println!("\nThe shared secret can now be used for symmetric encryption (e.g., with AES)");
+    // Step 5: Confirm the key
+    // -----------------------
+    // A matching comparison inside one process proves little in a real two-party setting.
+    // Bob sends Alice a MAC tag derived from his shared secret, and Alice checks it against
+    // her own before trusting the key. Each side tags its own role, so Bob cannot simply
+    // echo a tag Alice sent him.
+    println!("\nConfirming key...");
+    let bob_tag = confirm_key(shared_secret_2.as_bytes(), Role::Responder);
+    if let Err(e) = verify_key_confirmation(shared_secret_1.as_bytes(), Role::Responder, &bob_tag) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    println!("Key confirmation tag verified");
+    
+    // Step 6: Decapsulate with the wrong key
+    // --------------------------------------
+    // Decapsulating with an unrelated secret key silently yields a different shared secret.
+    // With Alice's tag attached to the ciphertext, the mismatch becomes a clean error.
+    println!("\nDecapsulating with an unrelated secret key...");
+    let alice_tag = confirm_key(shared_secret_1.as_bytes(), Role::Initiator);
+    let (_, other_sk) = keypair();
+    match decapsulate_checked(&ciphertext, &other_sk, &alice_tag) {
+        Err(e) => println!("Rejected as expected: {}", e),
+        Ok(_) => println!("Wrong key was not detected!"),
+    }
+    match decapsulate_checked(&ciphertext, &sk, &alice_tag) {
+        Ok(_) => println!("Checked decapsulation with the right key succeeded"),
+        Err(e) => println!("Checked decapsulation failed: {}", e),
+    }
+    
+    // Step 7: Store the secret key encrypted at rest
+    // -----------------------------------------------
+    // The key is wrapped in a password-protected container that names its parameter set,
+    // then read back. A wrong password is rejected rather than yielding garbage. The password
+    // comes from NTRU_KEY_PASSWORD or a prompt, and the container goes to the temp directory.
+    println!("\nStoring secret key in an encrypted container...");
+    match read_password() {
+        Some(password) => {
+            let key_path = std::env::temp_dir().join("ntru_secret_key.qpk8");
+            if let Err(e) = secure_store_secret_key(&sk, password.as_bytes(), &key_path) {
+                eprintln!("Failed to store secret key: {}", e);
+                std::process::exit(1);
+            }
+            println!("Wrote {}", key_path.display());
+            let container = std::fs::read(&key_path).expect("Failed to read back the key container");
+            match import_pkcs8_encrypted(&container, password.as_bytes()) {
+                Ok((params, restored)) => {
+                    println!("Restored {:?} secret key: matches original: {}", params, ct_eq(&restored, sk.as_bytes()))
+                }
+                Err(e) => println!("Failed to import key container: {}", e),
+            }
+            let wrong_password = [password.as_bytes(), b"!"].concat();
+            match import_pkcs8_encrypted(&container, &wrong_password) {
+                Err(e) => println!("Wrong password rejected: {}", e),
+                Ok(_) => println!("Wrong password was accepted!"),
+            }
+            let _ = std::fs::remove_file(&key_path);
+        }
+        None => println!("No password given; skipping the encrypted key container"),
+    }
+    
+    // Step 8: Encrypt a payload
+    // -------------------------
+    // The shared secret keys AES-256-GCM through HKDF-SHA256. A corrupted nonce must make
+    // decryption fail instead of returning a garbled payload.
+    println!("\nEncrypting a payload to the public key...");
+    let payload = b"Quantova NTRU payload";
+    let envelope = encrypt_with_ntru(&pk, payload);
+    println!("Envelope: {} byte NTRU ciphertext, {} byte AEAD ciphertext", envelope.kem_ciphertext.len(), envelope.aead_ciphertext.len());
+    match decrypt_with_ntru(&sk, &envelope) {
+        Ok(decrypted) => println!("Decrypted payload matches: {}", decrypted == payload),
+        Err(e) => println!("Decryption failed: {}", e),
+    }
+    let mut corrupted = envelope.clone();
+    corrupted.nonce[0] ^= 1;
+    match decrypt_with_ntru(&sk, &corrupted) {
+        Err(e) => println!("Corrupted nonce rejected: {}", e),
+        Ok(_) => println!("Corrupted nonce was accepted!"),
+    }
+
+    // Step 9: Export the key pair as PEM
+    // ----------------------------------
+    // The labels name the parameter set, so a key for another NTRU variant, or a public key
+    // offered as a secret key, is rejected on import rather than misread.
+    println!("\nExporting keys as PEM...");
+    let pk_pem = public_key_to_pem(&pk);
+    let sk_pem = secret_key_to_pem(&sk);
+    println!("{} ({} lines)", pk_pem.lines().next().unwrap_or(""), pk_pem.lines().count());
+    match (public_key_from_pem(&pk_pem), secret_key_from_pem(&sk_pem)) {
+        (Ok(pk_restored), Ok(sk_restored)) => println!(
+            "PEM keys restored: public key matches: {}, secret key matches: {}",
+            ct_eq(pk_restored.as_bytes(), pk.as_bytes()),
+            ct_eq(sk_restored.as_bytes(), sk.as_bytes())
+        ),
+        (Err(e), _) | (_, Err(e)) => println!("Failed to import PEM key: {}", e),
+    }
+    match secret_key_from_pem(&pk_pem) {
+        Err(e) => println!("Public key offered as secret key rejected: {}", e),
+        Ok(_) => println!("Public key was accepted as a secret key!"),
+    }
+    match public_key_from_pem(&pk_pem.replace(PEM_ALGORITHM, "NTRU-HPS-2048-509")) {
+        Err(e) => println!("Key for another parameter set rejected: {}", e),
+        Ok(_) => println!("Key for another parameter set was accepted!"),
+    }
+
+    // Step 10: Send the ciphertext in a versioned envelope
+    // -----------------------------------------------------
+    // The envelope names the parameter set, so a ciphertext for another NTRU variant is
+    // rejected with a clear error before it reaches `decapsulate`.
+    println!("\nWrapping the ciphertext in an envelope...");
+    let wire = CiphertextEnvelope::new(NtruParams::Hrss701, ciphertext.as_bytes()).encode();
+    match CiphertextEnvelope::decode(&wire, NtruParams::Hrss701)
+        .map_err(|e| e.to_string())
+        .and_then(|envelope| {
+            pqcrypto_ntru::ntruhrss701::Ciphertext::from_validated_bytes(&envelope.bytes).map_err(|e| e.to_string())
+        }) {
+        Ok(received) => println!(
+            "Envelope decoded ({} bytes), shared secrets match: {}",
+            wire.len(),
+            ct_eq(decapsulate(&received, &sk).as_bytes(), shared_secret_1.as_bytes())
+        ),
+        Err(e) => println!("Envelope rejected: {}", e),
+    }
+    let (_, other_ciphertext) = pqcrypto_ntru::ntruhps2048509::encapsulate(&pqcrypto_ntru::ntruhps2048509::keypair().0);
+    let foreign = CiphertextEnvelope::new(NtruParams::Hps2048509, other_ciphertext.as_bytes()).encode();
+    match CiphertextEnvelope::decode(&foreign, NtruParams::Hrss701) {
+        Err(e) => println!("Envelope for another parameter set rejected: {}", e),
+        Ok(_) => println!("Envelope for another parameter set was accepted!"),
+    }
+    match CiphertextEnvelope::decode(&wire[..wire.len() - 1], NtruParams::Hrss701) {
+        Err(e) => println!("Truncated envelope rejected: {}", e),
+        Ok(_) => println!("Truncated envelope was accepted!"),
+    }
+
+    // Step 11: Run the same exchange through the shared KEM interface
+    // ---------------------------------------------------------------
+    // `kem_scheme::run_kem_demo` drives keygen, encapsulate and decapsulate for any
+    // `KemScheme`; the FrodoKEM example runs the identical code with its own scheme.
+    let run = run_kem_demo::<NtruHrss701>();
+    println!(
+        "\n{} via KemScheme: shared secrets match: {}, sizes as advertised: {}",
+        NtruHrss701::NAME,
+        run.secrets_match,
+        run.sizes_match
+    );
+
+    // Shared secret sizes for every parameter set, looked up without running a key exchange
+    println!("\nShared secret sizes by parameter set:");
+    for params in NtruParams::ALL {
+        println!("  {:?}: {} bytes", params, params.sizes().shared_secret);
+    }
 }
 
 /// This function illustrates the conceptual workflow of using NTRU for secure communication
@@ -111,7 +980,7 @@ fn example_encrypt_decrypt_workflow() {
     let bob_shared_secret = decapsulate(&ciphertext, &bob_secret_key);
     
     // Step 5: Verify that both parties have the same shared secret
-    assert_eq!(alice_shared_secret.as_bytes(), bob_shared_secret.as_bytes());
+    assert!(ct_eq(alice_shared_secret.as_bytes(), bob_shared_secret.as_bytes()), "Shared secrets differ");
     
     // Step 6: Now both parties can use this shared secret for symmetric encryption
     // For example, they could use the shared secret as a key for AES encryption
@@ -122,15 +991,27 @@ fn example_encrypt_decrypt_workflow() {
 /// These are provided as templates for real-world implementations.
 
 /// Example function showing how to properly handle key generation with error checking
-fn generate_keypair_with_error_handling() -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+///
+/// `keypair()` itself cannot fail, so there is nothing to catch there. What can go wrong is
+/// the trip through bytes, so the helper rebuilds the keys from the bytes it is about to
+/// return and checks with an encapsulate/decapsulate round trip that they still work together.
+/// The secret key bytes are wiped when the caller drops them.
+fn generate_keypair_with_error_handling() -> Result<(Vec<u8>, Zeroizing<Vec<u8>>), &'static str> {
     // Generate the keypair
-    let (pk, sk) = match std::panic::catch_unwind(|| keypair()) {
-        Ok(keypair) => keypair,
-        Err(_) => return Err("Failed to generate NTRU keypair"),
-    };
+    let (pk, sk) = keypair();
     
     // Convert to byte vectors for storage or transmission
-    Ok((pk.as_bytes().to_vec(), sk.as_bytes().to_vec()))
+    let pk_bytes = pk.as_bytes().to_vec();
+    let sk_bytes = Zeroizing::new(sk.as_bytes().to_vec());
+    
+    // Make sure the stored form reconstructs to a working key pair
+    let (pk_restored, sk_restored) = reconstruct_keys_from_bytes(&pk_bytes, &sk_bytes)?;
+    let (shared_secret, ciphertext) = encapsulate(&pk_restored);
+    if !ct_eq(decapsulate(&ciphertext, &sk_restored).as_bytes(), shared_secret.as_bytes()) {
+        return Err("Reconstructed keys do not form a working key pair");
+    }
+    
+    Ok((pk_bytes, sk_bytes))
 }
 
 /// Example function showing how to reconstruct keys from stored bytes
@@ -163,18 +1044,323 @@ fn reconstruct_keys_from_bytes(
     Ok((pk, sk))
 }
 
-/// Example function demonstrating how to securely store a secret key
-/// Note: In a real application, you would use a secure storage solution
-fn secure_store_secret_key(sk: &pqcrypto_ntru::ntruhrss701::SecretKey) -> Result<(), &'static str> {
-    // Convert the secret key to bytes
-    let sk_bytes = sk.as_bytes();
-    
-    // In a real application, you would:
-    // 1. Use a secure storage solution (e.g., keyring, TPM, HSM)
-    // 2. Consider encrypting the key before storage
-    // 3. Implement access controls
-    
-    // This is just a placeholder for demonstration
-    println!("Secret key would be securely stored here");
-    Ok(())
-}
\ No newline at end of file
+/// Environment variable the demo reads the key container password from
+const PASSWORD_ENV_VAR: &str = "NTRU_KEY_PASSWORD";
+
+/// Reads the key container password from `NTRU_KEY_PASSWORD`, or prompts for it on stdin
+///
+/// Returns `None` if neither gives a non-empty password, e.g. when stdin is closed.
+fn read_password() -> Option<Zeroizing<String>> {
+    use std::io::Write;
+
+    if let Ok(password) = std::env::var(PASSWORD_ENV_VAR) {
+        return (!password.is_empty()).then(|| Zeroizing::new(password));
+    }
+    print!("Password for the key container (or set {}): ", PASSWORD_ENV_VAR);
+    std::io::stdout().flush().ok()?;
+    let mut line = Zeroizing::new(String::new());
+    std::io::stdin().read_line(&mut line).ok()?;
+    let password = line.trim_end_matches(['\r', '\n']);
+    (!password.is_empty()).then(|| Zeroizing::new(password.to_owned()))
+}
+
+/// Example function demonstrating how to store a secret key encrypted at rest
+/// Note: the password should come from the user or a secrets manager, never from source code
+fn secure_store_secret_key(
+    sk: &pqcrypto_ntru::ntruhrss701::SecretKey,
+    password: &[u8],
+    path: &std::path::Path,
+) -> std::io::Result<()> {
+    let container = export_pkcs8_encrypted(sk.as_bytes(), password, NtruParams::Hrss701);
+    std::fs::write(path, container)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_container_round_trips_and_rejects_wrong_password() {
+        let (_, sk) = keypair();
+        let container = export_pkcs8_encrypted(sk.as_bytes(), b"test password", NtruParams::Hrss701);
+
+        let (params, restored) = import_pkcs8_encrypted(&container, b"test password").expect("right password");
+        assert_eq!(params, NtruParams::Hrss701);
+        assert!(ct_eq(&restored, sk.as_bytes()));
+        assert!(matches!(import_pkcs8_encrypted(&container, b"wrong password"), Err(KeyContainerError::Decryption)));
+    }
+
+    #[test]
+    fn truncated_key_container_is_malformed() {
+        let (_, sk) = keypair();
+        let container = export_pkcs8_encrypted(sk.as_bytes(), b"test password", NtruParams::Hrss701);
+        // Cut inside the Argon2 cost fields, so `take_u32` runs out of input
+        let header_len = KEY_CONTAINER_MAGIC.len() + 2 + NtruParams::Hrss701.name().len();
+        for len in [0, header_len + 2, header_len + 6] {
+            assert!(
+                matches!(import_pkcs8_encrypted(&container[..len], b"test password"), Err(KeyContainerError::Malformed)),
+                "truncated to {} bytes",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn key_container_kdf_costs_above_the_limits_are_refused() {
+        let (_, sk) = keypair();
+        let container = export_pkcs8_encrypted(sk.as_bytes(), b"test password", NtruParams::Hrss701);
+        let costs_at = KEY_CONTAINER_MAGIC.len() + 2 + NtruParams::Hrss701.name().len();
+        let limits = [KEY_CONTAINER_MAX_M_COST_KIB, KEY_CONTAINER_MAX_T_COST, KEY_CONTAINER_MAX_P_COST];
+        for (field, limit) in limits.into_iter().enumerate() {
+            let mut crafted = container.clone();
+            let at = costs_at + 4 * field;
+            crafted[at..at + 4].copy_from_slice(&(limit + 1).to_be_bytes());
+            assert!(
+                matches!(import_pkcs8_encrypted(&crafted, b"test password"), Err(KeyContainerError::InvalidKdfParams)),
+                "cost field {} above its limit",
+                field
+            );
+        }
+    }
+
+    /// Runs the same zeroize a `Zeroizing` buffer runs on drop and checks, through the still
+    /// owned allocation, that every byte was wiped in place
+    fn assert_wiped_in_place(buffer: &mut Vec<u8>) {
+        let (ptr, capacity) = (buffer.as_ptr(), buffer.capacity());
+        zeroize::Zeroize::zeroize(buffer);
+        assert_eq!((buffer.as_ptr(), buffer.capacity()), (ptr, capacity));
+        // SAFETY: the allocation is still owned by `buffer`; only its length was reset
+        let wiped = unsafe { std::slice::from_raw_parts(ptr, capacity) };
+        assert!(wiped.iter().all(|&b| b == 0));
+    }
+
+    fn assert_zeroize_on_drop<T: zeroize::ZeroizeOnDrop>(_: &T) {}
+
+    #[test]
+    fn exported_secret_key_bytes_are_wiped() {
+        let (_, mut sk_bytes) = generate_keypair_with_error_handling().unwrap();
+        assert_zeroize_on_drop(&sk_bytes);
+        assert!(sk_bytes.iter().any(|&b| b != 0));
+        assert_wiped_in_place(&mut sk_bytes);
+    }
+
+    #[test]
+    fn container_keys_and_restored_secret_keys_are_wiped() {
+        let key = derive_container_key(b"test password", &[7; KEY_CONTAINER_SALT_LEN], Params::default()).unwrap();
+        assert_zeroize_on_drop(&key);
+
+        let (_, sk) = keypair();
+        let container = export_pkcs8_encrypted(sk.as_bytes(), b"test password", NtruParams::Hrss701);
+        let (_, mut restored) = import_pkcs8_encrypted(&container, b"test password").unwrap();
+        assert_zeroize_on_drop(&restored);
+        assert_wiped_in_place(&mut restored);
+    }
+
+    #[test]
+    fn ntru_envelope_round_trips() {
+        let (pk, sk) = keypair();
+        let envelope = encrypt_with_ntru(&pk, b"Quantova NTRU payload");
+        assert_eq!(decrypt_with_ntru(&sk, &envelope).unwrap(), b"Quantova NTRU payload");
+    }
+
+    #[test]
+    fn corrupted_nonce_fails_decryption() {
+        let (pk, sk) = keypair();
+        let mut envelope = encrypt_with_ntru(&pk, b"Quantova NTRU payload");
+        envelope.nonce[0] ^= 1;
+        assert!(matches!(decrypt_with_ntru(&sk, &envelope), Err(EnvelopeError::Decryption)));
+    }
+
+    #[test]
+    fn ntru_envelope_rejects_wrong_key_and_modified_ciphertext() {
+        let (pk, _) = keypair();
+        let (_, other_sk) = keypair();
+        let envelope = encrypt_with_ntru(&pk, b"Quantova NTRU payload");
+        assert!(matches!(decrypt_with_ntru(&other_sk, &envelope), Err(EnvelopeError::Decryption)));
+
+        let mut truncated = envelope.clone();
+        truncated.kem_ciphertext.pop();
+        assert!(matches!(decrypt_with_ntru(&other_sk, &truncated), Err(EnvelopeError::Ciphertext(_))));
+    }
+
+    #[test]
+    fn v2_envelope_is_rejected_by_v1_reader() {
+        let (pk, sk) = keypair();
+        let mut envelope = encrypt_with_ntru(&pk, b"payload");
+        envelope.version = 2;
+        assert!(matches!(
+            decrypt_with_ntru(&sk, &envelope),
+            Err(EnvelopeError::UnsupportedVersion { got: 2, max_supported: NTRU_ENVELOPE_VERSION })
+        ));
+    }
+
+    #[test]
+    fn degenerate_secrets_are_unhealthy() {
+        assert!(matches!(assert_secret_healthy(&[]), Err(HealthError::Empty)));
+        assert!(matches!(assert_secret_healthy(&[0; 32]), Err(HealthError::AllZero)));
+        assert!(matches!(assert_secret_healthy(&[0xab; 32]), Err(HealthError::Constant(0xab))));
+
+        let mut skewed: Vec<u8> = (0..32).collect();
+        skewed[..9].fill(0x55);
+        assert!(matches!(assert_secret_healthy(&skewed), Err(HealthError::Skewed { byte: 0x55, count: 9 })));
+    }
+
+    #[test]
+    fn decapsulated_secret_is_healthy() {
+        let (pk, sk) = keypair();
+        let (shared_secret, ciphertext) = encapsulate(&pk);
+        assert!(assert_secret_healthy(shared_secret.as_bytes()).is_ok());
+
+        let checked = decapsulate_with_health_check(&ciphertext, &sk, true).expect("healthy secret");
+        assert!(ct_eq(checked.as_bytes(), shared_secret.as_bytes()));
+    }
+
+    #[test]
+    fn decapsulate_checked_accepts_the_intended_key() {
+        let (pk, sk) = keypair();
+        let (shared_secret, ciphertext) = encapsulate(&pk);
+        let tag = confirm_key(shared_secret.as_bytes(), Role::Initiator);
+
+        let opened = decapsulate_checked(&ciphertext, &sk, &tag).expect("right key must decapsulate");
+        assert!(ct_eq(opened.as_bytes(), shared_secret.as_bytes()));
+    }
+
+    #[test]
+    fn decapsulate_checked_rejects_wrong_keypair() {
+        let (pk, _) = keypair();
+        let (_, other_sk) = keypair();
+        let (shared_secret, ciphertext) = encapsulate(&pk);
+        let tag = confirm_key(shared_secret.as_bytes(), Role::Initiator);
+
+        assert!(matches!(decapsulate_checked(&ciphertext, &other_sk, &tag), Err(DecryptError::WrongKey)));
+    }
+
+    #[test]
+    fn decapsulate_checked_rejects_responder_tag() {
+        let (pk, sk) = keypair();
+        let (shared_secret, ciphertext) = encapsulate(&pk);
+        let tag = confirm_key(shared_secret.as_bytes(), Role::Responder);
+
+        assert!(matches!(decapsulate_checked(&ciphertext, &sk, &tag), Err(DecryptError::WrongKey)));
+    }
+
+    #[test]
+    fn pem_keys_round_trip() {
+        let (pk, sk) = keypair();
+        let pem = public_key_to_pem(&pk);
+        assert!(pem.starts_with("-----BEGIN NTRU-HRSS-701 PUBLIC KEY-----\n"));
+        assert!(pem.lines().all(|line| line.len() <= PEM_LINE_LEN || line.starts_with("-----")));
+        assert_eq!(public_key_from_pem(&pem).expect("public key").as_bytes(), pk.as_bytes());
+        let restored = secret_key_from_pem(&secret_key_to_pem(&sk)).expect("secret key");
+        assert!(ct_eq(restored.as_bytes(), sk.as_bytes()));
+    }
+
+    #[test]
+    fn public_key_pem_is_not_read_as_a_secret_key() {
+        let (pk, _) = keypair();
+        match secret_key_from_pem(&public_key_to_pem(&pk)) {
+            Err(PemError::LabelMismatch { expected, found }) => {
+                assert_eq!(expected, "NTRU-HRSS-701 SECRET KEY");
+                assert_eq!(found, "NTRU-HRSS-701 PUBLIC KEY");
+            }
+            other => panic!("expected LabelMismatch, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn pem_with_mismatched_or_missing_armor_is_malformed() {
+        let (pk, _) = keypair();
+        let pem = public_key_to_pem(&pk);
+        let mismatched = pem.replace("-----END NTRU-HRSS-701 PUBLIC KEY-----", "-----END NTRU-HRSS-701 SECRET KEY-----");
+        assert!(matches!(public_key_from_pem(&mismatched), Err(PemError::Malformed)));
+        let without_end = pem.replace("-----END NTRU-HRSS-701 PUBLIC KEY-----\n", "");
+        assert!(matches!(public_key_from_pem(&without_end), Err(PemError::Malformed)));
+        assert!(matches!(public_key_from_pem(""), Err(PemError::Malformed)));
+    }
+
+    #[test]
+    fn pem_with_bad_base64_is_rejected() {
+        let (pk, _) = keypair();
+        let pem = public_key_to_pem(&pk);
+        let body_line = pem.lines().nth(1).expect("body line");
+        let corrupted = pem.replacen(body_line, &format!("!{}", &body_line[1..]), 1);
+        assert!(matches!(public_key_from_pem(&corrupted), Err(PemError::Base64(_))));
+    }
+
+    #[test]
+    fn pem_body_of_the_wrong_length_is_rejected() {
+        let pem = to_pem(&public_key_pem_label(), &[0u8; 32]);
+        assert!(matches!(
+            public_key_from_pem(&pem),
+            Err(PemError::Key(ParseError::LengthMismatch { expected, actual: 32 })) if expected == public_key_bytes()
+        ));
+        let pem = to_pem(&secret_key_pem_label(), &[0u8; 32]);
+        assert!(matches!(
+            secret_key_from_pem(&pem),
+            Err(PemError::Key(ParseError::LengthMismatch { expected, actual: 32 })) if expected == secret_key_bytes()
+        ));
+    }
+
+    #[test]
+    fn ciphertext_envelope_round_trips() {
+        let ciphertext: Vec<u8> = (0..NtruParams::Hrss701.sizes().ciphertext).map(|i| i as u8).collect();
+        let encoded = CiphertextEnvelope::new(NtruParams::Hrss701, &ciphertext).encode();
+        assert_eq!(encoded.len(), CIPHERTEXT_ENVELOPE_HEADER_LEN + ciphertext.len());
+        let decoded = CiphertextEnvelope::decode(&encoded, NtruParams::Hrss701).expect("envelope");
+        assert_eq!(decoded.version, CIPHERTEXT_ENVELOPE_VERSION);
+        assert_eq!(decoded.alg_id, NtruParams::Hrss701.id());
+        assert_eq!(decoded.bytes, ciphertext);
+    }
+
+    #[test]
+    fn ciphertext_envelope_header_errors_are_reported() {
+        let ciphertext = vec![0u8; NtruParams::Hrss701.sizes().ciphertext];
+        let encoded = CiphertextEnvelope::new(NtruParams::Hrss701, &ciphertext).encode();
+        let decode = |data: &[u8]| CiphertextEnvelope::decode(data, NtruParams::Hrss701);
+
+        assert!(matches!(decode(&encoded[..2]), Err(CiphertextEnvelopeError::Malformed)));
+
+        let mut future = encoded.clone();
+        future[0] = CIPHERTEXT_ENVELOPE_VERSION + 1;
+        assert!(matches!(decode(&future), Err(CiphertextEnvelopeError::UnsupportedVersion(2))));
+
+        let mut unknown = encoded.clone();
+        unknown[1..3].copy_from_slice(&0xBEEFu16.to_be_bytes());
+        assert!(matches!(decode(&unknown), Err(CiphertextEnvelopeError::UnknownAlgorithm(0xBEEF))));
+
+        assert!(matches!(
+            CiphertextEnvelope::decode(&encoded, NtruParams::Hps2048509),
+            Err(CiphertextEnvelopeError::AlgorithmMismatch { expected: NtruParams::Hps2048509, found: NtruParams::Hrss701 })
+        ));
+    }
+
+    #[test]
+    fn ciphertext_envelope_of_the_wrong_length_is_rejected() {
+        let expected = NtruParams::Hrss701.sizes().ciphertext;
+        for actual in [0, expected - 1, expected + 1] {
+            let encoded = CiphertextEnvelope::new(NtruParams::Hrss701, &vec![0u8; actual]).encode();
+            assert!(
+                matches!(
+                    CiphertextEnvelope::decode(&encoded, NtruParams::Hrss701),
+                    Err(CiphertextEnvelopeError::Length(ParseError::LengthMismatch { expected: e, actual: a }))
+                        if e == expected && a == actual
+                ),
+                "{}-byte ciphertext",
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn parameter_set_sizes_match_pqcrypto_ntru() {
+        let expected = [
+            pqcrypto_ntru::ntruhrss701::shared_secret_bytes(),
+            pqcrypto_ntru::ntruhps2048509::shared_secret_bytes(),
+            pqcrypto_ntru::ntruhps2048677::shared_secret_bytes(),
+            pqcrypto_ntru::ntruhps4096821::shared_secret_bytes(),
+        ];
+        for (params, expected) in NtruParams::ALL.into_iter().zip(expected) {
+            assert_eq!(params.sizes().shared_secret, expected, "{:?}", params);
+        }
+    }
+}
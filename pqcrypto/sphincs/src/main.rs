@@ -6,36 +6,154 @@
 // 1. Generate a public-secret key pair
 // 2. Sign a message using the private key
 // 3. Verify the signature using the public key
+// 4. Sign with a slow 's' variant under a time budget
+//
+// Pass `--mem-report` to print the peak heap usage of each operation.
 
 // Import necessary cryptographic libraries from pqcrypto-sphincsplus and pqcrypto-traits
-use pqcrypto_sphincsplus::sphincssha256128frobust;
+use pqcrypto_sphincsplus::{sphincssha256128frobust, sphincssha256128srobust};
 use pqcrypto_traits::sign::{PublicKey, SignedMessage};
+use heap_stats::{CountingAllocator, measure_peak_heap};
+use std::fmt;
 use std::str;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Heap accounting for `--mem-report`: heap_stats' counting allocator records
+// current and peak heap usage so each operation's peak can be reported.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Debug)]
+enum SignError {
+    /// Signing did not finish within the budget
+    Timeout(Duration),
+    /// The signing thread panicked before producing a signature
+    WorkerFailed,
+}
+
+impl fmt::Display for SignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignError::Timeout(budget) => write!(f, "signing exceeded the {:?} budget", budget),
+            SignError::WorkerFailed => write!(f, "signing thread stopped without producing a signature"),
+        }
+    }
+}
+
+/// Signs with the slow SPHINCS+ 's' variant on a worker thread and gives up after `timeout`.
+///
+/// The underlying C signing routine cannot be interrupted, so on timeout the worker is
+/// abandoned: it runs to completion in the background and its result is dropped. Callers
+/// regain control within the budget, but the CPU time is still spent.
+fn sign_with_timeout(
+    message: &[u8],
+    sk: &sphincssha256128srobust::SecretKey,
+    timeout: Duration,
+) -> Result<sphincssha256128srobust::SignedMessage, SignError> {
+    let (sender, receiver) = mpsc::channel();
+    let message = message.to_vec();
+    let sk = sk.clone();
+    thread::spawn(move || {
+        // The receiver is gone if the caller already timed out, so a failed send is expected
+        let _ = sender.send(sphincssha256128srobust::sign(&message, &sk));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(signed_message) => Ok(signed_message),
+        Err(RecvTimeoutError::Timeout) => Err(SignError::Timeout(timeout)),
+        Err(RecvTimeoutError::Disconnected) => Err(SignError::WorkerFailed),
+    }
+}
 
 fn main() {
+    let mem_report = std::env::args().any(|arg| arg == "--mem-report");
+
     // === Step 1: Generate a Key Pair ===
-    let (pk, sk) = sphincssha256128frobust::keypair();
+    let ((pk, sk), keygen_peak) = measure_peak_heap(sphincssha256128frobust::keypair);
     println!("Public Key: {:?}", pk.as_bytes());
 
     // === Step 2: Define the Message ===
     let message = b"Quantum Resistant Blockchain Message";
 
     // === Step 3: Sign the Message ===
-    let signed_message = sphincssha256128frobust::sign(message, &sk);
+    let (signed_message, sign_peak) = measure_peak_heap(|| sphincssha256128frobust::sign(message, &sk));
     println!(
         "Signed Message Length: {} bytes",
         signed_message.as_bytes().len()
     );
 
     // === Step 4: Verify the Signed Message ===
-    match sphincssha256128frobust::open(&signed_message, &pk) {
+    let (opened, verify_peak) = measure_peak_heap(|| sphincssha256128frobust::open(&signed_message, &pk));
+    match opened {
         Ok(valid_message) => {
-            // Convert Box<[u8]> to &str safely
-            let msg_str = str::from_utf8(&valid_message).expect("Invalid UTF-8 data");
-            println!("Verified successfully: {}", msg_str);
+            // Signed messages are arbitrary bytes, so only display them as text when they are
+            // valid UTF-8 and fall back to a hex preview otherwise
+            match str::from_utf8(&valid_message) {
+                Ok(msg_str) => println!("Verified successfully: {}", msg_str),
+                Err(_) => {
+                    let preview_len = valid_message.len().min(32);
+                    println!(
+                        "Verified successfully (binary message, {} bytes): {}{}",
+                        valid_message.len(),
+                        hex::encode(&valid_message[..preview_len]),
+                        if valid_message.len() > preview_len { "..." } else { "" }
+                    );
+                }
+            }
         }
         Err(_) => {
             println!("Signature verification failed!");
         }
     }
+
+    // === Step 5: Sign Under a Time Budget ===
+    // The 's' (small) variants trade signing speed for shorter signatures and can take far
+    // longer than the 'f' variant above. A generous budget succeeds; a 1 ms budget times out
+    // on real hardware instead of blocking the caller.
+    let (_, slow_sk) = sphincssha256128srobust::keypair();
+    for budget in [Duration::from_secs(30), Duration::from_millis(1)] {
+        let start = Instant::now();
+        match sign_with_timeout(message, &slow_sk, budget) {
+            Ok(signed) => println!(
+                "Signed with {:?} budget in {:?} ({} bytes)",
+                budget,
+                start.elapsed(),
+                signed.as_bytes().len()
+            ),
+            Err(e) => println!("Gave up after {:?}: {}", start.elapsed(), e),
+        }
+    }
+
+    // === Optional: Report Peak Heap Usage ===
+    if mem_report {
+        println!("Peak heap usage (bytes):");
+        println!("  Keygen : {}", keygen_peak);
+        println!("  Sign   : {}", sign_peak);
+        println!("  Verify : {}", verify_peak);
+        println!("  Overall: {}", keygen_peak.max(sign_peak).max(verify_peak));
+        println!("Note: stack usage of the underlying C implementation is not included.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_variant_signs_within_a_generous_budget() {
+        let (pk, sk) = sphincssha256128srobust::keypair();
+        let signed = sign_with_timeout(b"budgeted", &sk, Duration::from_secs(60)).unwrap();
+        assert_eq!(sphincssha256128srobust::open(&signed, &pk).unwrap(), b"budgeted");
+    }
+
+    #[test]
+    fn slow_variant_times_out_without_blocking_the_caller() {
+        let (_, sk) = sphincssha256128srobust::keypair();
+        let start = Instant::now();
+        let result = sign_with_timeout(b"budgeted", &sk, Duration::ZERO);
+        assert!(matches!(result, Err(SignError::Timeout(budget)) if budget == Duration::ZERO));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
 }
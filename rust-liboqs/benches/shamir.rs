@@ -0,0 +1,45 @@
+//! # Shamir split / reconstruct cost
+//!
+//! Measures splitting a 2528-byte secret (the size of a Dilithium2 secret key) into shares
+//! and reconstructing it from a quorum, for a range of threshold-of-total configurations.
+//! Criterion reports throughput in secret bytes per second next to the time per operation.
+//!
+//! Run with `cargo bench --bench shamir`. Reconstruction is timed over exactly `threshold`
+//! shares produced before timing starts, so only the interpolation itself is measured.
+
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use project::shamir::{self, Shares};
+
+/// Length of a Dilithium2 secret key, the size of secret the threshold demo shares
+const SECRET_LEN: usize = 2528;
+
+/// (threshold, total) pairs to measure
+const CONFIGURATIONS: [(usize, usize); 4] = [(2, 3), (3, 5), (5, 9), (7, 15)];
+
+fn bench_shamir(c: &mut Criterion) {
+    let secret: Vec<u8> = (0..SECRET_LEN).map(|_| rand::random()).collect();
+
+    let mut group = c.benchmark_group("shamir");
+    group.throughput(Throughput::Bytes(SECRET_LEN as u64));
+    for (threshold, total) in CONFIGURATIONS {
+        let label = format!("{}-of-{}", threshold, total);
+        let shares = shamir::split(&secret, threshold, total).expect("Configuration is valid");
+        let quorum: Shares = shares.into_iter().filter(|(i, _)| *i < threshold).collect();
+        assert_eq!(
+            shamir::reconstruct(&quorum, threshold).expect("Quorum is complete").as_slice(),
+            secret.as_slice(),
+            "Shares do not round-trip"
+        );
+
+        group.bench_with_input(BenchmarkId::new("split", &label), &(threshold, total), |b, &(t, n)| {
+            b.iter(|| shamir::split(black_box(&secret), t, n))
+        });
+        group.bench_with_input(BenchmarkId::new("reconstruct", &label), &threshold, |b, &t| {
+            b.iter(|| shamir::reconstruct(black_box(&quorum), t))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_shamir);
+criterion_main!(benches);
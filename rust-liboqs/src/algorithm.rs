@@ -0,0 +1,415 @@
+use oqs::kem::{self, Kem};
+use oqs::sig::{self, Sig};
+use std::fmt;
+
+/// Algorithm identifiers aligned with the code points other stacks use.
+///
+/// ML-DSA and ML-KEM use the NIST OIDs from the CSOR registry. The round 3 Dilithium,
+/// Falcon and SPHINCS+ variants have no NIST assignment, so they use the OIDs published by
+/// the Open Quantum Safe project, which oqs-provider and other liboqs-based tools recognize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlgorithmId {
+    MlDsa44,
+    MlDsa65,
+    MlDsa87,
+    MlKem512,
+    MlKem768,
+    MlKem1024,
+    Dilithium2,
+    Dilithium3,
+    Dilithium5,
+    Falcon512,
+    Falcon1024,
+    SphincsSha2128f,
+    SphincsSha2128s,
+    SphincsSha2192f,
+    SphincsSha2192s,
+    SphincsSha2256f,
+    SphincsSha2256s,
+}
+
+impl AlgorithmId {
+    pub const ALL: [AlgorithmId; 17] = [
+        AlgorithmId::MlDsa44,
+        AlgorithmId::MlDsa65,
+        AlgorithmId::MlDsa87,
+        AlgorithmId::MlKem512,
+        AlgorithmId::MlKem768,
+        AlgorithmId::MlKem1024,
+        AlgorithmId::Dilithium2,
+        AlgorithmId::Dilithium3,
+        AlgorithmId::Dilithium5,
+        AlgorithmId::Falcon512,
+        AlgorithmId::Falcon1024,
+        AlgorithmId::SphincsSha2128f,
+        AlgorithmId::SphincsSha2128s,
+        AlgorithmId::SphincsSha2192f,
+        AlgorithmId::SphincsSha2192s,
+        AlgorithmId::SphincsSha2256f,
+        AlgorithmId::SphincsSha2256s,
+    ];
+
+    /// Returns the dotted-decimal object identifier for this algorithm.
+    pub fn to_oid(self) -> &'static str {
+        match self {
+            AlgorithmId::MlDsa44 => "2.16.840.1.101.3.4.3.17",
+            AlgorithmId::MlDsa65 => "2.16.840.1.101.3.4.3.18",
+            AlgorithmId::MlDsa87 => "2.16.840.1.101.3.4.3.19",
+            AlgorithmId::MlKem512 => "2.16.840.1.101.3.4.4.1",
+            AlgorithmId::MlKem768 => "2.16.840.1.101.3.4.4.2",
+            AlgorithmId::MlKem1024 => "2.16.840.1.101.3.4.4.3",
+            AlgorithmId::Dilithium2 => "1.3.6.1.4.1.2.267.7.4.4",
+            AlgorithmId::Dilithium3 => "1.3.6.1.4.1.2.267.7.6.5",
+            AlgorithmId::Dilithium5 => "1.3.6.1.4.1.2.267.7.8.7",
+            AlgorithmId::Falcon512 => "1.3.9999.3.11",
+            AlgorithmId::Falcon1024 => "1.3.9999.3.14",
+            AlgorithmId::SphincsSha2128f => "1.3.9999.6.4.13",
+            AlgorithmId::SphincsSha2128s => "1.3.9999.6.4.16",
+            AlgorithmId::SphincsSha2192f => "1.3.9999.6.5.10",
+            AlgorithmId::SphincsSha2192s => "1.3.9999.6.5.12",
+            AlgorithmId::SphincsSha2256f => "1.3.9999.6.6.10",
+            AlgorithmId::SphincsSha2256s => "1.3.9999.6.6.12",
+        }
+    }
+
+    /// Looks up an algorithm by its dotted-decimal object identifier.
+    pub fn from_oid(oid: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|id| id.to_oid() == oid)
+    }
+
+    /// Returns the algorithm name as spelled by liboqs.
+    pub fn liboqs_name(self) -> &'static str {
+        match self {
+            AlgorithmId::MlDsa44 => "ML-DSA-44",
+            AlgorithmId::MlDsa65 => "ML-DSA-65",
+            AlgorithmId::MlDsa87 => "ML-DSA-87",
+            AlgorithmId::MlKem512 => "ML-KEM-512",
+            AlgorithmId::MlKem768 => "ML-KEM-768",
+            AlgorithmId::MlKem1024 => "ML-KEM-1024",
+            AlgorithmId::Dilithium2 => "Dilithium2",
+            AlgorithmId::Dilithium3 => "Dilithium3",
+            AlgorithmId::Dilithium5 => "Dilithium5",
+            AlgorithmId::Falcon512 => "Falcon-512",
+            AlgorithmId::Falcon1024 => "Falcon-1024",
+            AlgorithmId::SphincsSha2128f => "SPHINCS+-SHA2-128f-simple",
+            AlgorithmId::SphincsSha2128s => "SPHINCS+-SHA2-128s-simple",
+            AlgorithmId::SphincsSha2192f => "SPHINCS+-SHA2-192f-simple",
+            AlgorithmId::SphincsSha2192s => "SPHINCS+-SHA2-192s-simple",
+            AlgorithmId::SphincsSha2256f => "SPHINCS+-SHA2-256f-simple",
+            AlgorithmId::SphincsSha2256s => "SPHINCS+-SHA2-256s-simple",
+        }
+    }
+
+    /// Looks up an algorithm by name, ignoring case and hyphens, so `dilithium3`,
+    /// `ML-DSA-65` and `falcon512` are all accepted.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalize = |s: &str| s.replace('-', "").to_ascii_lowercase();
+        let wanted = normalize(name);
+        Self::ALL.into_iter().find(|id| normalize(id.liboqs_name()) == wanted)
+    }
+
+    /// Returns the COSE algorithm identifier. ML-DSA uses the IANA-registered code points;
+    /// the other signature schemes are mapped into the private-use range (< -65536).
+    /// KEMs have no COSE signature identifier.
+    pub fn cose_id(self) -> Option<i64> {
+        match self {
+            AlgorithmId::MlDsa44 => Some(-48),
+            AlgorithmId::MlDsa65 => Some(-49),
+            AlgorithmId::MlDsa87 => Some(-50),
+            AlgorithmId::Dilithium2 => Some(-65537),
+            AlgorithmId::Dilithium3 => Some(-65538),
+            AlgorithmId::Dilithium5 => Some(-65539),
+            AlgorithmId::Falcon512 => Some(-65540),
+            AlgorithmId::Falcon1024 => Some(-65541),
+            AlgorithmId::SphincsSha2128f => Some(-65542),
+            AlgorithmId::SphincsSha2128s => Some(-65543),
+            AlgorithmId::SphincsSha2192f => Some(-65544),
+            AlgorithmId::SphincsSha2192s => Some(-65545),
+            AlgorithmId::SphincsSha2256f => Some(-65546),
+            AlgorithmId::SphincsSha2256s => Some(-65547),
+            AlgorithmId::MlKem512 | AlgorithmId::MlKem768 | AlgorithmId::MlKem1024 => None,
+        }
+    }
+
+    /// Returns whether signing the same message twice with the same key yields identical
+    /// signatures in the liboqs build. Both kinds of signature always verify; only byte
+    /// equality of repeated signatures differs.
+    ///
+    /// - Dilithium2/3/5: deterministic. liboqs builds the round 3 reference code without
+    ///   `DILITHIUM_RANDOMIZED_SIGNING`.
+    /// - ML-DSA-44/65/87: randomized. liboqs uses FIPS 204 hedged signing, which mixes fresh
+    ///   randomness into every signature.
+    /// - Falcon-512/1024: randomized. Every signature draws a fresh salt.
+    /// - SPHINCS+: randomized. The round 3 code draws fresh randomness for every signature.
+    /// - ML-KEM: not a signature scheme, so `false`.
+    pub fn is_deterministic(self) -> bool {
+        matches!(self, AlgorithmId::Dilithium2 | AlgorithmId::Dilithium3 | AlgorithmId::Dilithium5)
+    }
+
+    /// Maps a liboqs signature algorithm to its identifier, if it has one.
+    pub fn from_sig(algorithm: sig::Algorithm) -> Option<Self> {
+        match algorithm {
+            sig::Algorithm::MlDsa44 => Some(AlgorithmId::MlDsa44),
+            sig::Algorithm::MlDsa65 => Some(AlgorithmId::MlDsa65),
+            sig::Algorithm::MlDsa87 => Some(AlgorithmId::MlDsa87),
+            sig::Algorithm::Dilithium2 => Some(AlgorithmId::Dilithium2),
+            sig::Algorithm::Dilithium3 => Some(AlgorithmId::Dilithium3),
+            sig::Algorithm::Dilithium5 => Some(AlgorithmId::Dilithium5),
+            sig::Algorithm::Falcon512 => Some(AlgorithmId::Falcon512),
+            sig::Algorithm::Falcon1024 => Some(AlgorithmId::Falcon1024),
+            sig::Algorithm::SphincsSha2128fSimple => Some(AlgorithmId::SphincsSha2128f),
+            sig::Algorithm::SphincsSha2128sSimple => Some(AlgorithmId::SphincsSha2128s),
+            sig::Algorithm::SphincsSha2192fSimple => Some(AlgorithmId::SphincsSha2192f),
+            sig::Algorithm::SphincsSha2192sSimple => Some(AlgorithmId::SphincsSha2192s),
+            sig::Algorithm::SphincsSha2256fSimple => Some(AlgorithmId::SphincsSha2256f),
+            sig::Algorithm::SphincsSha2256sSimple => Some(AlgorithmId::SphincsSha2256s),
+            _ => None,
+        }
+    }
+
+    /// Returns the liboqs signature algorithm, or `None` for KEMs.
+    pub fn to_sig(self) -> Option<sig::Algorithm> {
+        match self {
+            AlgorithmId::MlDsa44 => Some(sig::Algorithm::MlDsa44),
+            AlgorithmId::MlDsa65 => Some(sig::Algorithm::MlDsa65),
+            AlgorithmId::MlDsa87 => Some(sig::Algorithm::MlDsa87),
+            AlgorithmId::Dilithium2 => Some(sig::Algorithm::Dilithium2),
+            AlgorithmId::Dilithium3 => Some(sig::Algorithm::Dilithium3),
+            AlgorithmId::Dilithium5 => Some(sig::Algorithm::Dilithium5),
+            AlgorithmId::Falcon512 => Some(sig::Algorithm::Falcon512),
+            AlgorithmId::Falcon1024 => Some(sig::Algorithm::Falcon1024),
+            AlgorithmId::SphincsSha2128f => Some(sig::Algorithm::SphincsSha2128fSimple),
+            AlgorithmId::SphincsSha2128s => Some(sig::Algorithm::SphincsSha2128sSimple),
+            AlgorithmId::SphincsSha2192f => Some(sig::Algorithm::SphincsSha2192fSimple),
+            AlgorithmId::SphincsSha2192s => Some(sig::Algorithm::SphincsSha2192sSimple),
+            AlgorithmId::SphincsSha2256f => Some(sig::Algorithm::SphincsSha2256fSimple),
+            AlgorithmId::SphincsSha2256s => Some(sig::Algorithm::SphincsSha2256sSimple),
+            AlgorithmId::MlKem512 | AlgorithmId::MlKem768 | AlgorithmId::MlKem1024 => None,
+        }
+    }
+
+    /// Returns the liboqs KEM algorithm, or `None` for signature schemes.
+    pub fn to_kem(self) -> Option<kem::Algorithm> {
+        match self {
+            AlgorithmId::MlKem512 => Some(kem::Algorithm::MlKem512),
+            AlgorithmId::MlKem768 => Some(kem::Algorithm::MlKem768),
+            AlgorithmId::MlKem1024 => Some(kem::Algorithm::MlKem1024),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    /// Module-LWE / Module-SIS lattices: ML-KEM, ML-DSA and round 3 Dilithium.
+    ModuleLattice,
+    /// NTRU lattices: Falcon.
+    NtruLattice,
+    /// Hash functions only: SPHINCS+.
+    HashBased,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Signature,
+    Kem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Published as the named FIPS standard.
+    Standardized(&'static str),
+    /// Selected by NIST, with the named standard still in draft.
+    Draft(&'static str),
+    /// A round 3 submission replaced by the named standard.
+    Superseded(&'static str),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Standardized(standard) => write!(f, "standardized in {}", standard),
+            Status::Draft(standard) => write!(f, "selected, {} in draft", standard),
+            Status::Superseded(standard) => write!(f, "superseded by {}", standard),
+        }
+    }
+}
+
+/// Facts about an algorithm, for presenting choices in UIs and documentation.
+///
+/// Sizes are in bytes, as the linked liboqs encodes them. Falcon signatures vary in length;
+/// the value given is the maximum, and typical signatures are somewhat shorter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlgorithmInfo {
+    pub family: Family,
+    pub kind: Kind,
+    pub nist_level: u8,
+    pub status: Status,
+    pub public_key_len: usize,
+    pub secret_key_len: usize,
+    /// `None` for KEMs.
+    pub signature_len: Option<usize>,
+    /// `None` for signature schemes.
+    pub ciphertext_len: Option<usize>,
+}
+
+/// Returns the metadata for `id`. The sizes are read from liboqs, so this fails if the
+/// algorithm is not enabled in the linked build.
+pub fn algorithm_info(id: AlgorithmId) -> Result<AlgorithmInfo, oqs::Error> {
+    let (family, nist_level, status) = match id {
+        AlgorithmId::MlDsa44 => (Family::ModuleLattice, 2, Status::Standardized("FIPS 204")),
+        AlgorithmId::MlDsa65 => (Family::ModuleLattice, 3, Status::Standardized("FIPS 204")),
+        AlgorithmId::MlDsa87 => (Family::ModuleLattice, 5, Status::Standardized("FIPS 204")),
+        AlgorithmId::MlKem512 => (Family::ModuleLattice, 1, Status::Standardized("FIPS 203")),
+        AlgorithmId::MlKem768 => (Family::ModuleLattice, 3, Status::Standardized("FIPS 203")),
+        AlgorithmId::MlKem1024 => (Family::ModuleLattice, 5, Status::Standardized("FIPS 203")),
+        AlgorithmId::Dilithium2 => (Family::ModuleLattice, 2, Status::Superseded("FIPS 204")),
+        AlgorithmId::Dilithium3 => (Family::ModuleLattice, 3, Status::Superseded("FIPS 204")),
+        AlgorithmId::Dilithium5 => (Family::ModuleLattice, 5, Status::Superseded("FIPS 204")),
+        AlgorithmId::Falcon512 => (Family::NtruLattice, 1, Status::Draft("FIPS 206")),
+        AlgorithmId::Falcon1024 => (Family::NtruLattice, 5, Status::Draft("FIPS 206")),
+        AlgorithmId::SphincsSha2128f | AlgorithmId::SphincsSha2128s => (Family::HashBased, 1, Status::Superseded("FIPS 205")),
+        AlgorithmId::SphincsSha2192f | AlgorithmId::SphincsSha2192s => (Family::HashBased, 3, Status::Superseded("FIPS 205")),
+        AlgorithmId::SphincsSha2256f | AlgorithmId::SphincsSha2256s => (Family::HashBased, 5, Status::Superseded("FIPS 205")),
+    };
+
+    if let Some(algorithm) = id.to_sig() {
+        let sig = Sig::new(algorithm)?;
+        return Ok(AlgorithmInfo {
+            family,
+            kind: Kind::Signature,
+            nist_level,
+            status,
+            public_key_len: sig.length_public_key(),
+            secret_key_len: sig.length_secret_key(),
+            signature_len: Some(sig.length_signature()),
+            ciphertext_len: None,
+        });
+    }
+    let kem = Kem::new(id.to_kem().expect("Every algorithm is a signature scheme or a KEM."))?;
+    Ok(AlgorithmInfo {
+        family,
+        kind: Kind::Kem,
+        nist_level,
+        status,
+        public_key_len: kem.length_public_key(),
+        secret_key_len: kem.length_secret_key(),
+        signature_len: None,
+        ciphertext_len: Some(kem.length_ciphertext()),
+    })
+}
+
+/// Returns the first algorithm in the client's preference order that the server supports,
+/// or `None` when the two sides have nothing in common.
+pub fn negotiate(client_prefs: &[AlgorithmId], server_supported: &[AlgorithmId]) -> Option<AlgorithmId> {
+    client_prefs.iter().copied().find(|id| server_supported.contains(id))
+}
+
+/// Signature and KEM algorithms one side of a handshake supports, most preferred first.
+#[derive(Debug, Clone, Default)]
+pub struct AlgorithmPreferences {
+    pub signatures: Vec<AlgorithmId>,
+    pub kems: Vec<AlgorithmId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedSuite {
+    pub signature: AlgorithmId,
+    pub kem: AlgorithmId,
+}
+
+/// Agrees on one signature algorithm and one KEM, each by [`negotiate`]. Entries listed in
+/// the wrong category (e.g. a KEM among the signatures) are ignored.
+pub fn negotiate_suite(client: &AlgorithmPreferences, server: &AlgorithmPreferences) -> Option<NegotiatedSuite> {
+    let is_signature = |id: &AlgorithmId| id.to_sig().is_some();
+    let client_signatures: Vec<_> = client.signatures.iter().copied().filter(is_signature).collect();
+    let client_kems: Vec<_> = client.kems.iter().copied().filter(|id| !is_signature(id)).collect();
+    Some(NegotiatedSuite {
+        signature: negotiate(&client_signatures, &server.signatures)?,
+        kem: negotiate(&client_kems, &server.kems)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_algorithm_round_trips_through_its_oid_and_name() {
+        for id in AlgorithmId::ALL {
+            assert_eq!(AlgorithmId::from_oid(id.to_oid()), Some(id));
+            assert_eq!(AlgorithmId::from_name(id.liboqs_name()), Some(id));
+            assert_eq!(id.to_sig().and_then(AlgorithmId::from_sig), id.to_sig().map(|_| id));
+            assert!(id.to_sig().is_some() != id.to_kem().is_some());
+        }
+    }
+
+    #[test]
+    fn sphincs_info_is_hash_based_with_liboqs_sizes() {
+        let info = algorithm_info(AlgorithmId::SphincsSha2192s).unwrap();
+        let sig = Sig::new(sig::Algorithm::SphincsSha2192sSimple).unwrap();
+        assert_eq!((info.family, info.kind, info.nist_level), (Family::HashBased, Kind::Signature, 3));
+        assert_eq!(info.public_key_len, sig.length_public_key());
+        assert_eq!(info.signature_len, Some(sig.length_signature()));
+        assert_eq!(info.ciphertext_len, None);
+
+        let info = algorithm_info(AlgorithmId::MlKem768).unwrap();
+        let kem = Kem::new(kem::Algorithm::MlKem768).unwrap();
+        assert_eq!(info.ciphertext_len, Some(kem.length_ciphertext()));
+        assert_eq!(info.signature_len, None);
+    }
+
+    #[test]
+    fn deterministic_algorithms_sign_identically_twice() {
+        let message = b"sign me twice";
+        for id in AlgorithmId::ALL.into_iter().filter(|id| id.to_sig().is_some()) {
+            let sig = Sig::new(id.to_sig().unwrap()).unwrap();
+            let (public_key, secret_key) = sig.keypair().unwrap();
+            let first = sig.sign(message, &secret_key).unwrap();
+            let second = sig.sign(message, &secret_key).unwrap();
+            assert!(sig.verify(message, &first, &public_key).is_ok(), "{}", id.liboqs_name());
+            assert!(sig.verify(message, &second, &public_key).is_ok(), "{}", id.liboqs_name());
+            if id.is_deterministic() {
+                assert_eq!(first, second, "{}", id.liboqs_name());
+            }
+        }
+        assert!(!AlgorithmId::MlKem768.is_deterministic());
+    }
+
+    #[test]
+    fn negotiation_follows_the_client_preference_order() {
+        let server = [AlgorithmId::Falcon512, AlgorithmId::Dilithium3, AlgorithmId::MlDsa65];
+        let client = [AlgorithmId::MlDsa87, AlgorithmId::MlDsa65, AlgorithmId::Falcon512];
+        assert_eq!(negotiate(&client, &server), Some(AlgorithmId::MlDsa65));
+        assert_eq!(negotiate(&server, &client), Some(AlgorithmId::Falcon512));
+    }
+
+    #[test]
+    fn negotiation_without_overlap_fails() {
+        assert_eq!(negotiate(&[AlgorithmId::MlDsa87], &[AlgorithmId::Falcon512]), None);
+        assert_eq!(negotiate(&[], &[AlgorithmId::Falcon512]), None);
+
+        let client = AlgorithmPreferences { signatures: vec![AlgorithmId::MlDsa65], kems: vec![AlgorithmId::MlKem1024] };
+        let server = AlgorithmPreferences { signatures: vec![AlgorithmId::MlDsa65], kems: vec![AlgorithmId::MlKem768] };
+        assert_eq!(negotiate_suite(&client, &server), None);
+    }
+
+    #[test]
+    fn kems_offered_as_signatures_are_ignored() {
+        let client = AlgorithmPreferences {
+            signatures: vec![AlgorithmId::MlKem768, AlgorithmId::Dilithium2],
+            kems: vec![AlgorithmId::Falcon512, AlgorithmId::MlKem768],
+        };
+        let server = AlgorithmPreferences {
+            signatures: vec![AlgorithmId::MlKem768, AlgorithmId::Dilithium2],
+            kems: vec![AlgorithmId::Falcon512, AlgorithmId::MlKem768],
+        };
+        assert_eq!(
+            negotiate_suite(&client, &server),
+            Some(NegotiatedSuite { signature: AlgorithmId::Dilithium2, kem: AlgorithmId::MlKem768 })
+        );
+
+        let kem_only = AlgorithmPreferences { signatures: vec![AlgorithmId::MlKem768], kems: vec![AlgorithmId::MlKem768] };
+        assert_eq!(negotiate_suite(&kem_only, &server), None);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::algorithm::AlgorithmId;
+use crate::key_file::{self, DecodeError};
+use std::fmt;
+
+pub const BEGIN_SIGNATURE: &str = "-----BEGIN QUANTOVA SIGNATURE-----";
+const END_SIGNATURE: &str = "-----END QUANTOVA SIGNATURE-----";
+
+/// Base64 characters per armored body line.
+const LINE_LEN: usize = 64;
+
+const CRC24_INIT: u32 = 0xB7_04CE;
+const CRC24_POLY: u32 = 0x186_4CFB;
+
+#[derive(Debug)]
+pub enum ArmorError {
+    MissingBegin,
+    MissingEnd,
+    MissingAlgorithm,
+    UnknownAlgorithm(String),
+    MissingChecksum,
+    InvalidBase64(DecodeError),
+    ChecksumMismatch { expected: u32, actual: u32 },
+}
+
+impl fmt::Display for ArmorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArmorError::MissingBegin => write!(f, "no {} line", BEGIN_SIGNATURE),
+            ArmorError::MissingEnd => write!(f, "no {} line", END_SIGNATURE),
+            ArmorError::MissingAlgorithm => write!(f, "armor has no Algorithm header"),
+            ArmorError::UnknownAlgorithm(name) => write!(f, "{:?} is not a known signature algorithm", name),
+            ArmorError::MissingChecksum => write!(f, "armor has no checksum line"),
+            ArmorError::InvalidBase64(e) => write!(f, "invalid base64 in armor: {}", e),
+            ArmorError::ChecksumMismatch { expected, actual } => {
+                write!(f, "checksum mismatch: armor says {:06x}, body hashes to {:06x}", expected, actual)
+            }
+        }
+    }
+}
+
+impl From<DecodeError> for ArmorError {
+    fn from(e: DecodeError) -> Self {
+        ArmorError::InvalidBase64(e)
+    }
+}
+
+/// CRC-24 as used by OpenPGP ASCII armor (RFC 4880, section 6.1).
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc = CRC24_INIT;
+    for &byte in data {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x100_0000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0xFF_FFFF
+}
+
+/// Wraps a signature in a PGP-style ASCII armor block that can be pasted into emails and
+/// issue trackers:
+///
+/// ```text
+/// -----BEGIN QUANTOVA SIGNATURE-----
+/// Algorithm: ML-DSA-65
+///
+/// <base64, 64 characters per line>
+/// =<base64 CRC-24 of the signature>
+/// -----END QUANTOVA SIGNATURE-----
+/// ```
+pub fn armor_signature(signature: &[u8], algorithm: AlgorithmId) -> String {
+    let body = key_file::encode_base64(signature);
+    let mut out = format!("{}\nAlgorithm: {}\n\n", BEGIN_SIGNATURE, algorithm.liboqs_name());
+    // Base64 output is ASCII, so splitting on byte boundaries is safe.
+    for line in body.as_bytes().chunks(LINE_LEN) {
+        out.push_str(std::str::from_utf8(line).unwrap());
+        out.push('\n');
+    }
+    let checksum = crc24(signature).to_be_bytes();
+    out.push('=');
+    out.push_str(&key_file::encode_base64(&checksum[1..]));
+    out.push('\n');
+    out.push_str(END_SIGNATURE);
+    out.push('\n');
+    out
+}
+
+/// Parses an armor block written by [`armor_signature`], checking the CRC-24 and resolving
+/// the algorithm header. Text before the BEGIN line and after the END line is ignored, as
+/// are trailing spaces and CRLF line endings.
+pub fn dearmor_signature(text: &str) -> Result<(AlgorithmId, Vec<u8>), ArmorError> {
+    let mut lines = text.lines().map(str::trim_end);
+    lines.find(|line| *line == BEGIN_SIGNATURE).ok_or(ArmorError::MissingBegin)?;
+
+    let mut algorithm = None;
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':')
+            && key.trim().eq_ignore_ascii_case("Algorithm")
+        {
+            let name = value.trim();
+            let id = AlgorithmId::from_name(name)
+                .filter(|id| id.to_sig().is_some())
+                .ok_or_else(|| ArmorError::UnknownAlgorithm(name.to_string()))?;
+            algorithm = Some(id);
+        }
+    }
+    let algorithm = algorithm.ok_or(ArmorError::MissingAlgorithm)?;
+
+    let mut body = String::new();
+    let mut checksum = None;
+    for line in lines.by_ref() {
+        if line == END_SIGNATURE {
+            let checksum = checksum.ok_or(ArmorError::MissingChecksum)?;
+            let signature = key_file::decode_base64(&body)?;
+            let actual = crc24(&signature);
+            if actual != checksum {
+                return Err(ArmorError::ChecksumMismatch { expected: checksum, actual });
+            }
+            return Ok((algorithm, signature));
+        }
+        if let Some(encoded) = line.strip_prefix('=')
+            && encoded.len() == 4
+        {
+            let bytes = key_file::decode_base64(encoded)?;
+            checksum = Some(bytes.iter().fold(0u32, |acc, &b| acc << 8 | b as u32));
+        } else if checksum.is_none() {
+            body.push_str(line);
+        } else {
+            // Body lines after the checksum mean the armor was mangled.
+            return Err(ArmorError::MissingChecksum);
+        }
+    }
+    Err(ArmorError::MissingEnd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_signature() -> Vec<u8> {
+        // Long enough to wrap over several body lines
+        (0..200u32).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn crc24_matches_known_values() {
+        assert_eq!(crc24(b""), 0xB7_04CE);
+        assert_eq!(crc24(b"123456789"), 0x21_CF02);
+    }
+
+    #[test]
+    fn armored_signature_round_trips() {
+        let signature = sample_signature();
+        let armored = armor_signature(&signature, AlgorithmId::MlDsa65);
+        assert!(armored.lines().all(|line| line.len() <= LINE_LEN));
+        let (algorithm, decoded) = dearmor_signature(&format!("Signed-off text\n\n{}trailer\n", armored)).unwrap();
+        assert_eq!(algorithm, AlgorithmId::MlDsa65);
+        assert_eq!(decoded, signature);
+    }
+
+    #[test]
+    fn crlf_line_endings_are_accepted() {
+        let signature = sample_signature();
+        let armored = armor_signature(&signature, AlgorithmId::Falcon512).replace('\n', "\r\n");
+        let (algorithm, decoded) = dearmor_signature(&armored).unwrap();
+        assert_eq!(algorithm, AlgorithmId::Falcon512);
+        assert_eq!(decoded, signature);
+    }
+
+    #[test]
+    fn a_modified_body_fails_the_checksum() {
+        let armored = armor_signature(&sample_signature(), AlgorithmId::MlDsa65);
+        let body_line = armored.lines().nth(3).unwrap();
+        let replacement = if body_line.starts_with('A') { "B" } else { "A" };
+        let flipped = format!("{}{}", replacement, &body_line[1..]);
+        let tampered = armored.replacen(body_line, &flipped, 1);
+        assert!(matches!(dearmor_signature(&tampered), Err(ArmorError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn incomplete_armor_is_rejected() {
+        let armored = armor_signature(&sample_signature(), AlgorithmId::MlDsa65);
+
+        let without_end = armored.replace(&format!("{}\n", END_SIGNATURE), "");
+        assert!(matches!(dearmor_signature(&without_end), Err(ArmorError::MissingEnd)));
+
+        let without_algorithm = armored.replace("Algorithm: ML-DSA-65\n", "");
+        assert!(matches!(dearmor_signature(&without_algorithm), Err(ArmorError::MissingAlgorithm)));
+
+        let without_begin = armored.replace(BEGIN_SIGNATURE, "");
+        assert!(matches!(dearmor_signature(&without_begin), Err(ArmorError::MissingBegin)));
+
+        // A KEM is not a signature algorithm
+        let kem = armored.replace("ML-DSA-65", "ML-KEM-768");
+        assert!(matches!(dearmor_signature(&kem), Err(ArmorError::UnknownAlgorithm(name)) if name == "ML-KEM-768"));
+    }
+}
@@ -1,55 +1,784 @@
+use crate::error::QuantovaError;
+use crate::keygen::{self, KeygenError};
+use crate::keys::key_fingerprint;
+use crate::secret::ProtectedSecretKey;
+use argon2::{Argon2, Params, Version};
 use oqs::sig::{self, Sig, Signature};
-use std::fs::File;
-use std::io::{self, Write};
-use std::process;
+use rand::random;
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use zeroize::Zeroizing;
+
+/// Fixed message signed when checking that a secret key belongs to a public key.
+const KEYPAIR_TEST_VECTOR: &[u8] = b"Quantova key pair validation test vector";
+
+/// Appended to every challenge before signing, so a challenge response cannot be mistaken
+/// for a signature made for any other purpose.
+const CHALLENGE_CONTEXT: &[u8] = b"Quantova authentication challenge v1";
+pub const CHALLENGE_LEN: usize = 32;
+/// How long an issued challenge stays valid, and how far a response's timestamp may be from
+/// the verifier's clock, by default.
+pub const DEFAULT_CHALLENGE_TTL: Duration = Duration::from_secs(60);
+
+/// Prepended to a session token's claims before signing, so a token signature cannot be
+/// mistaken for a signature made for any other purpose.
+const TOKEN_CONTEXT: &[u8] = b"Quantova session token v1";
+/// How long a session token is valid after it is issued, by default.
+pub const DEFAULT_TOKEN_LIFETIME: Duration = Duration::from_secs(300);
+
+/// File names of a saved key pair inside its directory.
+const PUBLIC_KEY_FILE: &str = "public.key";
+const SECRET_KEY_FILE: &str = "secret.key";
+
+/// A passphrase-encrypted secret key file is a header followed by the AES-256-GCM
+/// ciphertext: magic || version || KDF id || Argon2 m_cost, t_cost, p_cost (u32 each) ||
+/// algorithm name length (u8) || algorithm name || salt || nonce. The whole header is
+/// authenticated as associated data, so none of it can be changed without the tag failing.
+const KEY_FILE_MAGIC: &[u8; 4] = b"QKEY";
+const KEY_FILE_VERSION: u8 = 1;
+/// The only KDF a version 1 key file may name: Argon2id, version 0x13.
+const KEY_FILE_KDF_ARGON2ID: u8 = 1;
+const KEY_FILE_SALT_LEN: usize = 16;
+const KEY_FILE_AAD: &[u8] = b"Quantova encrypted secret key v1";
+/// Upper bounds on the Argon2 costs a key file may ask for, so a crafted file cannot make
+/// loading exhaust memory or time: 1 GiB, 16 passes, 16 lanes.
+const KEY_FILE_MAX_M_COST: u32 = 1 << 20;
+const KEY_FILE_MAX_T_COST: u32 = 16;
+const KEY_FILE_MAX_P_COST: u32 = 16;
+
+/// Signs a fixed test vector with `secret_key` and verifies it against `public_key`,
+/// returning whether the two keys actually correspond.
+pub fn validate_keypair(sig: &Sig, public_key: &sig::PublicKey, secret_key: &sig::SecretKey) -> bool {
+    match sig.sign(KEYPAIR_TEST_VECTOR, secret_key) {
+        Ok(signature) => sig.verify(KEYPAIR_TEST_VECTOR, &signature, public_key).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Compares a received public key against a pinned one. Keys of different lengths are never
+/// equal; equal-length keys are compared in constant time, so timing does not reveal where
+/// the first differing byte is.
+pub fn public_keys_equal(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && ring::constant_time::verify_slices_are_equal(a, b).is_ok()
+}
+
+fn challenge_message(challenge: &[u8; CHALLENGE_LEN], nonce: u64, timestamp: u64) -> Vec<u8> {
+    [challenge.as_slice(), &nonce.to_be_bytes(), &timestamp.to_be_bytes(), CHALLENGE_CONTEXT].concat()
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Nanoseconds since the Unix epoch, saturating at `u64::MAX`.
+fn unix_time_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX))
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    Keygen(KeygenError),
+    Backend(oqs::Error),
+    Io(io::Error),
+    /// The encrypted key file's tag did not verify: wrong passphrase or modified file.
+    BadPassphrase,
+    /// The key file is too short, has a bad header, or decrypts to something that is not a
+    /// secret key.
+    MalformedKeyFile,
+    /// The key file has a format version or KDF this build does not know.
+    UnsupportedKeyFile { version: u8, kdf: u8 },
+    /// The key file holds a secret key for a different signature algorithm.
+    WrongKeyAlgorithm { expected: String, found: String },
+    /// The verifier was not given a key to sign session tokens with.
+    NoTokenKey,
+    /// The session token's signature does not verify under the verifier's token key.
+    InvalidToken,
+    TokenExpired { expires_at: u64, now: u64 },
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::Keygen(e) => write!(f, "key generation failed: {}", e),
+            AuthError::Backend(e) => write!(f, "signature backend error: {}", e),
+            AuthError::Io(e) => write!(f, "key file error: {}", e),
+            AuthError::BadPassphrase => write!(f, "wrong passphrase or modified key file"),
+            AuthError::MalformedKeyFile => write!(f, "malformed encrypted key file"),
+            AuthError::UnsupportedKeyFile { version, kdf } => {
+                write!(f, "unsupported key file version {} with KDF {}", version, kdf)
+            }
+            AuthError::WrongKeyAlgorithm { expected, found } => {
+                write!(f, "key file holds a {} key, expected {}", found, expected)
+            }
+            AuthError::NoTokenKey => write!(f, "verifier has no token signing key"),
+            AuthError::InvalidToken => write!(f, "session token signature is invalid"),
+            AuthError::TokenExpired { expires_at, now } => {
+                write!(f, "session token expired at {}, now {}", expires_at, now)
+            }
+        }
+    }
+}
+
+impl From<KeygenError> for AuthError {
+    fn from(e: KeygenError) -> Self {
+        AuthError::Keygen(e)
+    }
+}
+
+impl From<oqs::Error> for AuthError {
+    fn from(e: oqs::Error) -> Self {
+        AuthError::Backend(e)
+    }
+}
+
+impl From<io::Error> for AuthError {
+    fn from(e: io::Error) -> Self {
+        AuthError::Io(e)
+    }
+}
+
+/// Creates or replaces `path` readable by the owner only (mode 0600 on Unix). An existing
+/// file is narrowed to 0600 before anything is written to it.
+pub(crate) fn write_private_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents)
+}
+
+/// Derives the AES-256-GCM key for an encrypted key file from the passphrase with Argon2id.
+fn passphrase_key(passphrase: &[u8], salt: &[u8], params: Params) -> Result<LessSafeKey, argon2::Error> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, params).hash_password_into(passphrase, salt, key.as_mut_slice())?;
+    Ok(LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key.as_slice()).expect("AES-256-GCM takes 32-byte keys.")))
+}
+
+/// The header of an encrypted key file, everything before the ciphertext.
+struct KeyFileHeader {
+    params: Params,
+    algorithm: String,
+    salt: [u8; KEY_FILE_SALT_LEN],
+    nonce: [u8; aead::NONCE_LEN],
+}
+
+impl KeyFileHeader {
+    fn encode(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(19 + self.algorithm.len() + KEY_FILE_SALT_LEN + aead::NONCE_LEN);
+        header.extend_from_slice(KEY_FILE_MAGIC);
+        header.push(KEY_FILE_VERSION);
+        header.push(KEY_FILE_KDF_ARGON2ID);
+        header.extend_from_slice(&self.params.m_cost().to_be_bytes());
+        header.extend_from_slice(&self.params.t_cost().to_be_bytes());
+        header.extend_from_slice(&self.params.p_cost().to_be_bytes());
+        header.push(self.algorithm.len() as u8);
+        header.extend_from_slice(self.algorithm.as_bytes());
+        header.extend_from_slice(&self.salt);
+        header.extend_from_slice(&self.nonce);
+        header
+    }
+
+    /// Splits a key file into its header, the raw header bytes (the AEAD associated data)
+    /// and the ciphertext.
+    fn decode(file: &[u8]) -> Result<(Self, &[u8], &[u8]), AuthError> {
+        fn take<'a>(rest: &mut &'a [u8], len: usize) -> Result<&'a [u8], AuthError> {
+            if rest.len() < len {
+                return Err(AuthError::MalformedKeyFile);
+            }
+            let (head, tail) = rest.split_at(len);
+            *rest = tail;
+            Ok(head)
+        }
+        let take_u32 = |rest: &mut &[u8]| -> Result<u32, AuthError> {
+            Ok(u32::from_be_bytes(take(rest, 4)?.try_into().expect("Took 4 bytes.")))
+        };
+
+        let mut rest = file;
+        if take(&mut rest, KEY_FILE_MAGIC.len())? != KEY_FILE_MAGIC {
+            return Err(AuthError::MalformedKeyFile);
+        }
+        let (version, kdf) = (take(&mut rest, 1)?[0], take(&mut rest, 1)?[0]);
+        if version != KEY_FILE_VERSION || kdf != KEY_FILE_KDF_ARGON2ID {
+            return Err(AuthError::UnsupportedKeyFile { version, kdf });
+        }
+        let (m_cost, t_cost, p_cost) = (take_u32(&mut rest)?, take_u32(&mut rest)?, take_u32(&mut rest)?);
+        if m_cost > KEY_FILE_MAX_M_COST || t_cost > KEY_FILE_MAX_T_COST || p_cost > KEY_FILE_MAX_P_COST {
+            return Err(AuthError::MalformedKeyFile);
+        }
+        let params = Params::new(m_cost, t_cost, p_cost, None).map_err(|_| AuthError::MalformedKeyFile)?;
+        let algorithm_len = take(&mut rest, 1)?[0] as usize;
+        let algorithm = String::from_utf8(take(&mut rest, algorithm_len)?.to_vec()).map_err(|_| AuthError::MalformedKeyFile)?;
+        let salt = take(&mut rest, KEY_FILE_SALT_LEN)?.try_into().expect("Took the salt length.");
+        let nonce = take(&mut rest, aead::NONCE_LEN)?.try_into().expect("Took the nonce length.");
+        if rest.len() < AES_256_GCM.tag_len() {
+            return Err(AuthError::MalformedKeyFile);
+        }
+        let header_len = file.len() - rest.len();
+        Ok((Self { params, algorithm, salt, nonce }, &file[..header_len], rest))
+    }
+}
+
+/// The associated data sealing an encrypted key file: a fixed context, then the header.
+fn key_file_aad(header: &[u8]) -> Vec<u8> {
+    [KEY_FILE_AAD, header].concat()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The timestamp is further from the verifier's clock than the freshness window.
+    Stale { timestamp: u64, now: u64 },
+    /// The nonce is not above the last one accepted, so the response is a replay or out of
+    /// order.
+    NonceNotIncreasing { nonce: u64, last: u64 },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Stale { timestamp, now } => {
+                write!(f, "timestamp {} is outside the freshness window at {}", timestamp, now)
+            }
+            ReplayError::NonceNotIncreasing { nonce, last } => {
+                write!(f, "nonce {} is not above the last accepted nonce {}", nonce, last)
+            }
+        }
+    }
+}
+
+/// Rejects responses whose timestamp is outside a freshness window or whose nonce is not
+/// above the last accepted one.
+///
+/// A [`Prover`]'s nonces only increase, so remembering the highest accepted nonce is enough
+/// to reject every replay, however old, in constant space.
+pub struct ReplayGuard {
+    window: Duration,
+    /// Highest nonce accepted so far.
+    last_nonce: Option<u64>,
+}
+
+impl ReplayGuard {
+    pub fn new(window: Duration) -> Self {
+        Self { window, last_nonce: None }
+    }
+
+    /// Checks a response's nonce and Unix timestamp without recording them.
+    pub fn check(&self, nonce: u64, timestamp: u64) -> Result<(), ReplayError> {
+        let now = unix_time();
+        if now.abs_diff(timestamp) > self.window.as_secs() {
+            return Err(ReplayError::Stale { timestamp, now });
+        }
+        if let Some(last) = self.last_nonce
+            && nonce <= last
+        {
+            return Err(ReplayError::NonceNotIncreasing { nonce, last });
+        }
+        Ok(())
+    }
+
+    /// Records the nonce of a response whose signature verified.
+    pub fn record(&mut self, nonce: u64) {
+        self.last_nonce = Some(self.last_nonce.map_or(nonce, |last| last.max(nonce)));
+    }
+}
+
+/// A prover's answer to a challenge. The nonce and timestamp are covered by the signature.
+#[derive(Debug, Clone)]
+pub struct ChallengeResponse {
+    pub nonce: u64,
+    pub timestamp: u64,
+    pub signature: Signature,
+}
+
+/// What a session token asserts: whose key authenticated, and when the token is valid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claims {
+    /// [`key_fingerprint`] of the authenticated public key.
+    pub subject: String,
+    pub issued_at: u64,
+    pub expires_at: u64,
+}
+
+impl Claims {
+    /// The bytes the verifier signs: context || subject length || subject || issued_at || expires_at.
+    fn message(&self) -> Vec<u8> {
+        [
+            TOKEN_CONTEXT,
+            &(self.subject.len() as u32).to_be_bytes(),
+            self.subject.as_bytes(),
+            &self.issued_at.to_be_bytes(),
+            &self.expires_at.to_be_bytes(),
+        ]
+        .concat()
+    }
+}
+
+/// Proof that a challenge response was accepted. Only [`Verifier::check_response`] creates
+/// one, and [`Verifier::issue_token`] consumes it, so every token follows a successful
+/// authentication and each authentication mints at most one token.
+#[derive(Debug)]
+pub struct Authenticated {
+    subject: String,
+}
+
+impl Authenticated {
+    /// [`key_fingerprint`] of the public key the response verified under.
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+}
+
+/// A bearer token minted after a successful challenge-response, so later requests can skip
+/// the post-quantum handshake until it expires.
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub claims: Claims,
+    pub signature: Signature,
+}
+
+/// The verifier's own key pair for signing session tokens.
+struct TokenKey {
+    sig: Sig,
+    public_key: sig::PublicKey,
+    secret_key: ProtectedSecretKey,
+    lifetime: Duration,
+}
+
+/// The verifying side of challenge-response authentication. It issues random challenges and
+/// accepts each one at most once within the freshness window, and a [`ReplayGuard`] rejects
+/// stale timestamps and nonces that do not increase, so a recorded response cannot be
+/// replayed.
+pub struct Verifier {
+    sig: Sig,
+    public_key: sig::PublicKey,
+    window: Duration,
+    outstanding: HashMap<[u8; CHALLENGE_LEN], Instant>,
+    replay_guard: ReplayGuard,
+    token_key: Option<TokenKey>,
+}
+
+impl Verifier {
+    pub fn new(algorithm: sig::Algorithm, public_key: sig::PublicKey, window: Duration) -> Result<Self, oqs::Error> {
+        Ok(Self {
+            sig: Sig::new(algorithm)?,
+            public_key,
+            window,
+            outstanding: HashMap::new(),
+            replay_guard: ReplayGuard::new(window),
+            token_key: None,
+        })
+    }
+
+    /// Gives the verifier its own `algorithm` key pair for signing session tokens that are
+    /// valid for `lifetime` after issue.
+    pub fn with_token_key(
+        mut self,
+        algorithm: sig::Algorithm,
+        public_key: sig::PublicKey,
+        secret_key: sig::SecretKey,
+        lifetime: Duration,
+    ) -> Result<Self, oqs::Error> {
+        self.token_key = Some(TokenKey {
+            sig: Sig::new(algorithm)?,
+            public_key,
+            secret_key: ProtectedSecretKey::new(secret_key),
+            lifetime,
+        });
+        Ok(self)
+    }
+
+    pub fn issue_challenge(&mut self) -> [u8; CHALLENGE_LEN] {
+        let challenge = random();
+        self.outstanding.insert(challenge, Instant::now());
+        challenge
+    }
+
+    /// Checks a response to `challenge`: the challenge must be outstanding and unexpired, the
+    /// nonce and timestamp must pass the replay guard, and the signature must verify under
+    /// the stored public key. The challenge is used up either way. An accepted response
+    /// gives the [`Authenticated`] proof needed to issue a session token; `Ok(None)` means
+    /// it was rejected.
+    pub fn check_response(
+        &mut self,
+        challenge: &[u8; CHALLENGE_LEN],
+        response: &ChallengeResponse,
+    ) -> Result<Option<Authenticated>, ReplayError> {
+        let Some(issued) = self.outstanding.remove(challenge) else {
+            return Ok(None);
+        };
+        if issued.elapsed() > self.window {
+            return Ok(None);
+        }
+        self.replay_guard.check(response.nonce, response.timestamp)?;
+        let message = challenge_message(challenge, response.nonce, response.timestamp);
+        if self.sig.verify(&message, &response.signature, &self.public_key).is_err() {
+            return Ok(None);
+        }
+        self.replay_guard.record(response.nonce);
+        Ok(Some(Authenticated { subject: key_fingerprint(self.public_key.as_ref()) }))
+    }
+
+    /// Mints a session token for the subject of an accepted challenge response.
+    pub fn issue_token(&self, proof: Authenticated) -> Result<Token, AuthError> {
+        self.issue_token_at(proof, unix_time())
+    }
+
+    /// Like [`Self::issue_token`], with an explicit Unix issue time.
+    fn issue_token_at(&self, proof: Authenticated, issued_at: u64) -> Result<Token, AuthError> {
+        let token_key = self.token_key.as_ref().ok_or(AuthError::NoTokenKey)?;
+        let claims = Claims {
+            subject: proof.subject,
+            issued_at,
+            expires_at: issued_at + token_key.lifetime.as_secs(),
+        };
+        let signature = token_key.sig.sign(&claims.message(), token_key.secret_key.expose())?;
+        Ok(Token { claims, signature })
+    }
+
+    /// Checks a token's signature under the verifier's token key, then its expiry, and
+    /// returns its claims.
+    pub fn validate_token(&self, token: &Token) -> Result<Claims, AuthError> {
+        let token_key = self.token_key.as_ref().ok_or(AuthError::NoTokenKey)?;
+        if token_key.sig.verify(&token.claims.message(), &token.signature, &token_key.public_key).is_err() {
+            return Err(AuthError::InvalidToken);
+        }
+        let now = unix_time();
+        if now >= token.claims.expires_at {
+            return Err(AuthError::TokenExpired { expires_at: token.claims.expires_at, now });
+        }
+        Ok(token.claims.clone())
+    }
+}
+
+/// The proving side of challenge-response authentication, holding the secret key.
+///
+/// Nonces come from a counter that only increases, and the verifier rejects any nonce not
+/// above the last one it accepted. The counter never falls behind the clock in nanoseconds,
+/// so a restarted prover carries on above its old nonces without saved state, provided the
+/// clock does not go backwards.
+pub struct Prover {
+    sig: Sig,
+    secret_key: ProtectedSecretKey,
+    next_nonce: u64,
+}
+
+impl Prover {
+    pub fn new(algorithm: sig::Algorithm, secret_key: sig::SecretKey) -> Result<Self, oqs::Error> {
+        Ok(Self { sig: Sig::new(algorithm)?, secret_key: ProtectedSecretKey::new(secret_key), next_nonce: 0 })
+    }
+
+    /// Signs `challenge || nonce || timestamp || context` with the current time.
+    pub fn respond(&mut self, challenge: &[u8; CHALLENGE_LEN]) -> Result<ChallengeResponse, oqs::Error> {
+        self.respond_at(challenge, unix_time())
+    }
+
+    /// Like [`Self::respond`], with an explicit Unix timestamp.
+    pub fn respond_at(&mut self, challenge: &[u8; CHALLENGE_LEN], timestamp: u64) -> Result<ChallengeResponse, oqs::Error> {
+        let nonce = self.next_nonce.max(unix_time_nanos());
+        self.next_nonce = nonce.saturating_add(1);
+        let signature = self.sig.sign(&challenge_message(challenge, nonce, timestamp), self.secret_key.expose())?;
+        Ok(ChallengeResponse { nonce, timestamp, signature })
+    }
+}
+
+/// Runs a challenge-response exchange, then tries a replay, a never-issued challenge, an
+/// expired challenge, an expired timestamp and a reused nonce, printing how each is handled.
+fn challenge_response_demo(auth: &QuantumSafeAuth) -> Result<(), oqs::Error> {
+    let algorithm = auth.algorithm;
+    let mut prover = Prover::new(algorithm, auth.secret_key.expose().clone())?;
+    let mut verifier = Verifier::new(algorithm, auth.public_key.clone(), DEFAULT_CHALLENGE_TTL)?;
+
+    let challenge = verifier.issue_challenge();
+    let response = prover.respond(&challenge)?;
+    println!("Challenge-response: {}", verdict(verifier.check_response(&challenge, &response)));
+    println!("Replayed response: {}", verdict(verifier.check_response(&challenge, &response)));
+    let unknown: [u8; CHALLENGE_LEN] = random();
+    println!("Never-issued challenge: {}", verdict(verifier.check_response(&unknown, &prover.respond(&unknown)?)));
+
+    let mut impatient = Verifier::new(algorithm, auth.public_key.clone(), Duration::ZERO)?;
+    let stale = impatient.issue_challenge();
+    let stale_response = prover.respond(&stale)?;
+    thread::sleep(Duration::from_millis(1));
+    println!("Expired challenge: {}", verdict(impatient.check_response(&stale, &stale_response)));
+
+    let challenge = verifier.issue_challenge();
+    let expired_at = unix_time() - 2 * DEFAULT_CHALLENGE_TTL.as_secs();
+    let expired = prover.respond_at(&challenge, expired_at)?;
+    println!("Expired timestamp: {}", verdict(verifier.check_response(&challenge, &expired)));
+    let challenge = verifier.issue_challenge();
+    println!("Reused nonce: {}", verdict(verifier.check_response(&challenge, &response)));
+    Ok(())
+}
+
+fn verdict(outcome: Result<Option<Authenticated>, ReplayError>) -> String {
+    match outcome {
+        Ok(Some(proof)) => format!("accepted ({})", &proof.subject()[..16]),
+        Ok(None) => "rejected".to_string(),
+        Err(e) => format!("rejected ({})", e),
+    }
+}
+
+/// Authenticates with challenge-response, issues a session token, and shows how the verifier
+/// handles that token, a tampered copy and an expired one.
+fn session_token_demo(auth: &QuantumSafeAuth) -> Result<(), AuthError> {
+    let token_sig = Sig::new(oqs::sig::Algorithm::Dilithium2)?;
+    let (token_public_key, token_secret_key) = keygen::generate_sig_keypair(&token_sig)?;
+    let mut verifier = Verifier::new(auth.algorithm, auth.public_key.clone(), DEFAULT_CHALLENGE_TTL)?.with_token_key(
+        oqs::sig::Algorithm::Dilithium2,
+        token_public_key,
+        token_secret_key,
+        DEFAULT_TOKEN_LIFETIME,
+    )?;
+    let mut prover = Prover::new(auth.algorithm, auth.secret_key.expose().clone())?;
+    let mut authenticate = || -> Result<Option<Authenticated>, oqs::Error> {
+        let challenge = verifier.issue_challenge();
+        let response = prover.respond(&challenge)?;
+        Ok(verifier.check_response(&challenge, &response).ok().flatten())
+    };
+    let (Some(proof), Some(second_proof)) = (authenticate()?, authenticate()?) else {
+        println!("Challenge-response failed; no session token issued.");
+        return Ok(());
+    };
+
+    let token = verifier.issue_token(proof)?;
+    match verifier.validate_token(&token) {
+        Ok(claims) => println!("Session token issued for {} until {}.", &claims.subject[..16], claims.expires_at),
+        Err(e) => println!("Session token rejected: {}", e),
+    }
+    let mut extended = token.clone();
+    extended.claims.expires_at += DEFAULT_TOKEN_LIFETIME.as_secs();
+    if let Err(e) = verifier.validate_token(&extended) {
+        println!("Token with an extended expiry rejected: {}", e);
+    }
+    let expired = verifier.issue_token_at(second_proof, unix_time() - 2 * DEFAULT_TOKEN_LIFETIME.as_secs())?;
+    if let Err(e) = verifier.validate_token(&expired) {
+        println!("Expired token rejected: {}", e);
+    }
+    Ok(())
+}
 
 struct QuantumSafeAuth {
+    algorithm: sig::Algorithm,
+    /// Created once and reused by every sign and verify call.
+    sig: Sig,
     public_key: sig::PublicKey,
-    secret_key: sig::SecretKey,
+    secret_key: ProtectedSecretKey,
 }
 
 impl QuantumSafeAuth {
-    fn new() -> Self {
-        let sig = Sig::new(oqs::sig::Algorithm::Dilithium2).expect("Failed to create signature scheme.");
-        let (public_key, secret_key) = sig.keypair().expect("Key pair generation failed.");
-        println!(" Quantum-safe key pair generated.
+    fn new() -> Result<Self, KeygenError> {
+        Self::new_with(oqs::sig::Algorithm::Dilithium2)
+    }
+
+    /// Generates a key pair for `algorithm`, which every later sign and verify call uses.
+    fn new_with(algorithm: sig::Algorithm) -> Result<Self, KeygenError> {
+        let sig = Sig::new(algorithm)?;
+        let (public_key, secret_key) = keygen::generate_sig_keypair(&sig)?;
+        let secret_key = ProtectedSecretKey::new(secret_key);
+        println!(" Quantum-safe {:?} key pair generated.
         Public Key: {:?}
-         Secret Key: {:?}", public_key, secret_key);
-        Self {
+         Secret Key: {:?}", algorithm, public_key, secret_key);
+        Ok(Self {
+            algorithm,
+            sig,
             public_key,
             secret_key,
+        })
+    }
+
+    /// Rebuilds a key pair from raw bytes, optionally checking that the keys match. Gives
+    /// `Ok(None)` when the bytes do not form a key pair for `algorithm`.
+    fn from_bytes(
+        algorithm: sig::Algorithm,
+        public_key: &[u8],
+        secret_key: &[u8],
+        validate: bool,
+    ) -> Result<Option<Self>, KeygenError> {
+        let sig = Sig::new(algorithm)?;
+        let (Some(public_key), Some(secret_key)) = (sig.public_key_from_bytes(public_key), sig.secret_key_from_bytes(secret_key)) else {
+            return Ok(None);
+        };
+        let (public_key, secret_key) = (public_key.to_owned(), secret_key.to_owned());
+        if validate && !validate_keypair(&sig, &public_key, &secret_key) {
+            return Ok(None);
         }
+        Ok(Some(Self {
+            algorithm,
+            sig,
+            public_key,
+            secret_key: ProtectedSecretKey::new(secret_key),
+        }))
     }
 
-    fn sign_message(&self, message: &[u8]) -> Signature {
-        let sig = Sig::new(oqs::sig::Algorithm::Dilithium2).expect("Failed to create signature scheme.");
-        sig.sign(message, &self.secret_key).expect("Signing failed.")
+    fn sign_message(&self, message: &[u8]) -> Result<Signature, QuantovaError> {
+        self.sig
+            .sign(message, self.secret_key.expose())
+            .map_err(|e| QuantovaError::from(e).with_algorithm(self.algorithm.name()))
     }
 
     fn verify_signature(&self, message: &[u8], signature: &Signature) -> bool {
-        let sig = Sig::new(oqs::sig::Algorithm::Dilithium2).expect("Failed to create signature scheme.");
-        sig.verify(message, signature, &self.public_key).is_ok()
+        self.sig.verify(message, signature, &self.public_key).is_ok()
     }
 
-    fn save_to_file(&self, filename: &str) -> io::Result<()> {
-        let mut file = File::create(filename)?;
+    fn save_to_file(&self, path: &Path) -> io::Result<()> {
+        let mut file = File::create(path)?;
         file.write_all(self.public_key.as_ref())?;
         Ok(())
     }
 
-    fn terminate(&self) {
-        println!("Terminating the process.");
-        process::exit(0);
+    fn save_hex_to_file(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, hex::encode(&self.public_key))
+    }
+
+    /// Saves the public key and the secret key to `dir`. The secret key file is created
+    /// readable by the owner only (mode 0600 on Unix).
+    fn save_keypair(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(PUBLIC_KEY_FILE), self.public_key.as_ref())?;
+        write_private_file(&dir.join(SECRET_KEY_FILE), self.secret_key.expose().as_ref())
+    }
+
+    /// Encrypts the secret key under `passphrase` and saves it to `path` as a header (see
+    /// [`KEY_FILE_MAGIC`]) followed by the ciphertext. The key is derived with Argon2id from
+    /// a random salt, and the file is created readable by the owner only.
+    fn save_secret_key_encrypted(&self, path: &Path, passphrase: &[u8]) -> io::Result<()> {
+        let header = KeyFileHeader {
+            params: Params::default(),
+            algorithm: self.algorithm.name().to_string(),
+            salt: random(),
+            nonce: random(),
+        };
+        let encoded = header.encode();
+        let mut ciphertext = Zeroizing::new(self.secret_key.expose().as_ref().to_vec());
+        passphrase_key(passphrase, &header.salt, header.params)
+            .expect("Default Argon2id parameters accept a 16-byte salt and 32-byte output.")
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(header.nonce),
+                Aad::from(key_file_aad(&encoded)),
+                &mut *ciphertext,
+            )
+            .expect("AEAD sealing only fails for oversized inputs");
+        write_private_file(path, &[encoded.as_slice(), &ciphertext].concat())
+    }
+
+    /// Loads an `algorithm` secret key saved by [`Self::save_secret_key_encrypted`]. A wrong
+    /// passphrase, or any change to the file, gives [`AuthError::BadPassphrase`].
+    fn load_secret_key_encrypted(
+        path: &Path,
+        algorithm: sig::Algorithm,
+        passphrase: &[u8],
+    ) -> Result<ProtectedSecretKey, AuthError> {
+        let contents = fs::read(path)?;
+        let (header, encoded, ciphertext) = KeyFileHeader::decode(&contents)?;
+        if header.algorithm != algorithm.name() {
+            return Err(AuthError::WrongKeyAlgorithm { expected: algorithm.name().to_string(), found: header.algorithm });
+        }
+        let mut plaintext = Zeroizing::new(ciphertext.to_vec());
+        let secret_key = passphrase_key(passphrase, &header.salt, header.params)
+            .map_err(|_| AuthError::MalformedKeyFile)?
+            .open_in_place(Nonce::assume_unique_for_key(header.nonce), Aad::from(key_file_aad(encoded)), &mut plaintext)
+            .map_err(|_| AuthError::BadPassphrase)?;
+        let sig = Sig::new(algorithm)?;
+        let secret_key = sig.secret_key_from_bytes(secret_key).ok_or(AuthError::MalformedKeyFile)?.to_owned();
+        Ok(ProtectedSecretKey::new(secret_key))
+    }
+
+    /// Loads a key pair saved by [`Self::save_keypair`], checking that the keys have the right
+    /// sizes and belong together.
+    fn load(dir: &Path, algorithm: sig::Algorithm) -> io::Result<Self> {
+        let public_key = fs::read(dir.join(PUBLIC_KEY_FILE))?;
+        let secret_key = Zeroizing::new(fs::read(dir.join(SECRET_KEY_FILE))?);
+        Self::from_bytes(algorithm, &public_key, &secret_key, true)
+            .map_err(|e| io::Error::other(e.to_string()))?
+            .ok_or_else(|| {
+                io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("{} does not hold a valid {:?} key pair", dir.display(), algorithm),
+                )
+            })
     }
 }
 
-pub fn authentication() {
-    let auth = QuantumSafeAuth::new();
+/// Saves the secret key encrypted, loads it back with the right passphrase and signs with it,
+/// then tries a wrong passphrase.
+fn encrypted_key_demo(auth: &QuantumSafeAuth, path: &Path, message: &[u8]) -> Result<(), AuthError> {
+    auth.save_secret_key_encrypted(path, b"correct horse battery staple")?;
+    let loaded = QuantumSafeAuth::load_secret_key_encrypted(path, auth.algorithm, b"correct horse battery staple")?;
+    let signature = auth.sig.sign(message, loaded.expose())?;
+    println!(
+        "Encrypted secret key reloaded; its signature {}.",
+        if auth.verify_signature(message, &signature) { "verifies" } else { "does not verify!" }
+    );
+    if let Err(e) = QuantumSafeAuth::load_secret_key_encrypted(path, auth.algorithm, b"correct horse battery stapler") {
+        println!("Wrong passphrase rejected: {}", e);
+    }
+    Ok(())
+}
+
+/// Stands up auth with Falcon-512 and with Dilithium3, signs and verifies end to end with
+/// each, and shows whether either accepts the other's signature.
+fn algorithm_selection_demo(message: &[u8]) -> Result<(), QuantovaError> {
+    let falcon = QuantumSafeAuth::new_with(oqs::sig::Algorithm::Falcon512)?;
+    let dilithium = QuantumSafeAuth::new_with(oqs::sig::Algorithm::Dilithium3)?;
+    for (auth, other) in [(&falcon, &dilithium), (&dilithium, &falcon)] {
+        let signature = auth.sign_message(message)?;
+        println!(
+            "{:?}: {}-byte signature {}, {} by {:?}.",
+            auth.algorithm,
+            signature.as_ref().len(),
+            if auth.verify_signature(message, &signature) { "verified" } else { "failed to verify" },
+            if other.verify_signature(message, &signature) { "accepted!" } else { "rejected" },
+            other.algorithm
+        );
+    }
+    Ok(())
+}
+
+/// Times repeated verification with the cached `Sig` against creating a `Sig` per call.
+fn sig_reuse_demo(auth: &QuantumSafeAuth, message: &[u8], signature: &Signature) {
+    const ROUNDS: usize = 200;
+    let start = Instant::now();
+    let reused = (0..ROUNDS).filter(|_| auth.verify_signature(message, signature)).count();
+    let reused_time = start.elapsed();
+    let start = Instant::now();
+    let recreated = (0..ROUNDS)
+        .filter(|_| {
+            Sig::new(auth.algorithm).is_ok_and(|sig| sig.verify(message, signature, &auth.public_key).is_ok())
+        })
+        .count();
+    let recreated_time = start.elapsed();
+    println!(
+        "{} verifications: {:.2} ms reusing the cached Sig, {:.2} ms creating one per call ({} and {} valid).",
+        ROUNDS,
+        reused_time.as_secs_f64() * 1000.0,
+        recreated_time.as_secs_f64() * 1000.0,
+        reused,
+        recreated
+    );
+}
+
+pub fn authentication() -> Result<(), QuantovaError> {
+    let auth = QuantumSafeAuth::new()?;
+    // Everything the demo writes goes under one scratch directory, removed at the end
+    let demo_dir = std::env::temp_dir().join(format!("quantova-auth-demo-{}", std::process::id()));
+    fs::create_dir_all(&demo_dir)?;
 
     let message = b"Quantum-safe authentication message";
     println!("Message: {:?}", String::from_utf8_lossy(message));
 
-    let signature = auth.sign_message(message);
+    let signature = auth.sign_message(message)?;
     println!("Signature generated.");
 
     if auth.verify_signature(message, &signature) {
@@ -58,10 +787,302 @@ pub fn authentication() {
         println!("Signature verification failed!");
     }
 
-    match auth.save_to_file("public_key.bin") {
+    challenge_response_demo(&auth)?;
+    session_token_demo(&auth)?;
+    algorithm_selection_demo(message)?;
+    sig_reuse_demo(&auth, message, &signature);
+
+    let reloaded = QuantumSafeAuth::from_bytes(auth.algorithm, auth.public_key.as_ref(), auth.secret_key.expose().as_ref(), true)?;
+    match reloaded {
+        Some(_) => println!("Key pair reloaded and validated."),
+        None => println!("Key pair validation failed!"),
+    }
+
+    // Save the key pair, load it as a restarted process would, and cross-check signatures
+    let keypair_dir = demo_dir.join("keypair");
+    auth.save_keypair(&keypair_dir)?;
+    let loaded = QuantumSafeAuth::load(&keypair_dir, auth.algorithm)?;
+    let reloaded_signature = loaded.sign_message(message)?;
+    let round_trip = loaded.verify_signature(message, &signature) && auth.verify_signature(message, &reloaded_signature);
+    println!(
+        "Key pair saved and loaded: {}",
+        if round_trip { "signatures verify both ways." } else { "signature check failed!" }
+    );
+    encrypted_key_demo(&auth, &keypair_dir.join("secret.key.enc"), message)?;
+
+    let public_key_bin = demo_dir.join("public_key.bin");
+    let public_key_hex = demo_dir.join("public_key.hex");
+    match auth.save_to_file(&public_key_bin) {
         Ok(_) => println!("Public key saved to file."),
         Err(e) => eprintln!("Failed to save public key: {}", e),
     }
 
-    auth.terminate();
+    if let Err(e) = auth.save_hex_to_file(&public_key_hex) {
+        eprintln!("Failed to save hex public key: {}", e);
+    }
+
+    let _ = fs::remove_dir_all(&demo_dir);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_algorithms_sign_and_verify_end_to_end() {
+        let message = b"algorithm selection";
+        let falcon = QuantumSafeAuth::new_with(sig::Algorithm::Falcon512).unwrap();
+        let dilithium = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium3).unwrap();
+        for (auth, other) in [(&falcon, &dilithium), (&dilithium, &falcon)] {
+            let signature = auth.sign_message(message).unwrap();
+            assert!(signature.as_ref().len() <= auth.sig.length_signature());
+            assert!(auth.verify_signature(message, &signature));
+            assert!(!auth.verify_signature(b"algorithm selection?", &signature));
+            assert!(!other.verify_signature(message, &signature));
+
+            let rebuilt = QuantumSafeAuth::from_bytes(auth.algorithm, auth.public_key.as_ref(), auth.secret_key.expose().as_ref(), true)
+                .unwrap()
+                .unwrap();
+            assert!(auth.verify_signature(message, &rebuilt.sign_message(message).unwrap()));
+        }
+        // Key bytes of one algorithm do not load as the other
+        assert!(
+            QuantumSafeAuth::from_bytes(dilithium.algorithm, falcon.public_key.as_ref(), falcon.secret_key.expose().as_ref(), false)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    fn scratch_dir(test: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("quantova-auth-test-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn encrypted_secret_key_round_trip_and_wrong_passphrase() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let dir = scratch_dir("encrypted");
+        let path = dir.join("secret.key.enc");
+        auth.save_secret_key_encrypted(&path, b"correct horse battery staple").unwrap();
+
+        let loaded = QuantumSafeAuth::load_secret_key_encrypted(&path, auth.algorithm, b"correct horse battery staple").unwrap();
+        assert_eq!(loaded.expose().as_ref(), auth.secret_key.expose().as_ref());
+        let signature = auth.sig.sign(b"round trip", loaded.expose()).unwrap();
+        assert!(auth.verify_signature(b"round trip", &signature));
+        assert!(matches!(
+            QuantumSafeAuth::load_secret_key_encrypted(&path, auth.algorithm, b"correct horse battery stapler"),
+            Err(AuthError::BadPassphrase)
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn encrypted_key_file_header_is_authenticated() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let dir = scratch_dir("header");
+        let path = dir.join("secret.key.enc");
+        auth.save_secret_key_encrypted(&path, b"passphrase").unwrap();
+        let file = fs::read(&path).unwrap();
+        assert_eq!(&file[..4], KEY_FILE_MAGIC);
+        let load = |bytes: &[u8]| {
+            fs::write(&path, bytes).unwrap();
+            QuantumSafeAuth::load_secret_key_encrypted(&path, auth.algorithm, b"passphrase")
+        };
+
+        // Changing the Argon2 cost in the header breaks the tag (t_cost is bytes 10..14)
+        let mut altered = file.clone();
+        altered[13] ^= 0x01;
+        assert!(matches!(load(&altered), Err(AuthError::BadPassphrase)));
+        let mut future = file.clone();
+        future[4] = KEY_FILE_VERSION + 1;
+        assert!(matches!(load(&future), Err(AuthError::UnsupportedKeyFile { .. })));
+        let mut greedy = file.clone();
+        greedy[6..10].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(load(&greedy), Err(AuthError::MalformedKeyFile)));
+        assert!(matches!(load(&file[..20]), Err(AuthError::MalformedKeyFile)));
+        assert!(load(&file).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn token_verifier(auth: &QuantumSafeAuth) -> Verifier {
+        let token_sig = Sig::new(sig::Algorithm::Dilithium2).unwrap();
+        let (public_key, secret_key) = keygen::generate_sig_keypair(&token_sig).unwrap();
+        Verifier::new(auth.algorithm, auth.public_key.clone(), DEFAULT_CHALLENGE_TTL)
+            .unwrap()
+            .with_token_key(sig::Algorithm::Dilithium2, public_key, secret_key, DEFAULT_TOKEN_LIFETIME)
+            .unwrap()
+    }
+
+    fn authenticate(verifier: &mut Verifier, prover: &mut Prover) -> Authenticated {
+        let challenge = verifier.issue_challenge();
+        let response = prover.respond(&challenge).unwrap();
+        verifier.check_response(&challenge, &response).unwrap().expect("response is accepted")
+    }
+
+    #[test]
+    fn session_tokens_follow_authentication_and_expire() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let mut verifier = token_verifier(&auth);
+        let mut prover = Prover::new(auth.algorithm, auth.secret_key.expose().clone()).unwrap();
+
+        let proof = authenticate(&mut verifier, &mut prover);
+        assert_eq!(proof.subject(), key_fingerprint(auth.public_key.as_ref()));
+        let token = verifier.issue_token(proof).unwrap();
+        assert_eq!(verifier.validate_token(&token).unwrap().subject, key_fingerprint(auth.public_key.as_ref()));
+
+        let mut extended = token.clone();
+        extended.claims.expires_at += DEFAULT_TOKEN_LIFETIME.as_secs();
+        assert!(matches!(verifier.validate_token(&extended), Err(AuthError::InvalidToken)));
+
+        let issued_at = unix_time() - 2 * DEFAULT_TOKEN_LIFETIME.as_secs();
+        let proof = authenticate(&mut verifier, &mut prover);
+        let expired = verifier.issue_token_at(proof, issued_at).unwrap();
+        assert!(matches!(verifier.validate_token(&expired), Err(AuthError::TokenExpired { .. })));
+    }
+
+    #[test]
+    fn rejected_responses_give_no_proof() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let impostor = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let mut verifier = token_verifier(&auth);
+        let mut prover = Prover::new(impostor.algorithm, impostor.secret_key.expose().clone()).unwrap();
+        let challenge = verifier.issue_challenge();
+        let response = prover.respond(&challenge).unwrap();
+        assert!(verifier.check_response(&challenge, &response).unwrap().is_none());
+    }
+
+    #[test]
+    fn expired_timestamps_and_duplicate_nonces_are_rejected() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let mut verifier = Verifier::new(auth.algorithm, auth.public_key.clone(), DEFAULT_CHALLENGE_TTL).unwrap();
+        let mut prover = Prover::new(auth.algorithm, auth.secret_key.expose().clone()).unwrap();
+
+        let challenge = verifier.issue_challenge();
+        let expired_at = unix_time() - 2 * DEFAULT_CHALLENGE_TTL.as_secs();
+        let expired = prover.respond_at(&challenge, expired_at).unwrap();
+        assert!(matches!(verifier.check_response(&challenge, &expired), Err(ReplayError::Stale { timestamp, .. }) if timestamp == expired_at));
+
+        let challenge = verifier.issue_challenge();
+        let response = prover.respond(&challenge).unwrap();
+        assert!(verifier.check_response(&challenge, &response).unwrap().is_some());
+        let challenge = verifier.issue_challenge();
+        assert_eq!(
+            verifier.check_response(&challenge, &response).unwrap_err(),
+            ReplayError::NonceNotIncreasing { nonce: response.nonce, last: response.nonce }
+        );
+    }
+
+    #[test]
+    fn restarted_prover_is_accepted() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let mut verifier = Verifier::new(auth.algorithm, auth.public_key.clone(), DEFAULT_CHALLENGE_TTL).unwrap();
+        for _ in 0..3 {
+            // A new Prover each time, as after a process restart with no saved state
+            let mut prover = Prover::new(auth.algorithm, auth.secret_key.expose().clone()).unwrap();
+            let challenge = verifier.issue_challenge();
+            let response = prover.respond(&challenge).unwrap();
+            assert!(verifier.check_response(&challenge, &response).unwrap().is_some());
+        }
+    }
+
+    #[test]
+    fn replay_guard_requires_increasing_nonces() {
+        let mut guard = ReplayGuard::new(DEFAULT_CHALLENGE_TTL);
+        let now = unix_time();
+        assert!(guard.check(5, now).is_ok());
+        guard.record(5);
+        assert_eq!(guard.check(5, now), Err(ReplayError::NonceNotIncreasing { nonce: 5, last: 5 }));
+        assert_eq!(guard.check(4, now), Err(ReplayError::NonceNotIncreasing { nonce: 4, last: 5 }));
+        assert!(guard.check(6, now).is_ok());
+        // Recording an older nonce never lowers the bar
+        guard.record(3);
+        assert_eq!(guard.check(5, now), Err(ReplayError::NonceNotIncreasing { nonce: 5, last: 5 }));
+    }
+
+    #[test]
+    fn prover_nonces_increase_across_responses_and_restarts() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let challenge = [0u8; CHALLENGE_LEN];
+        let mut prover = Prover::new(auth.algorithm, auth.secret_key.expose().clone()).unwrap();
+        let first = prover.respond(&challenge).unwrap().nonce;
+        let second = prover.respond(&challenge).unwrap().nonce;
+        let mut restarted = Prover::new(auth.algorithm, auth.secret_key.expose().clone()).unwrap();
+        let third = restarted.respond(&challenge).unwrap().nonce;
+        assert!(first < second && second < third, "{} {} {}", first, second, third);
+    }
+
+    #[test]
+    fn replayed_unknown_and_stale_challenges_are_rejected() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let mut verifier = Verifier::new(auth.algorithm, auth.public_key.clone(), DEFAULT_CHALLENGE_TTL).unwrap();
+        let mut prover = Prover::new(auth.algorithm, auth.secret_key.expose().clone()).unwrap();
+
+        let challenge = verifier.issue_challenge();
+        let response = prover.respond(&challenge).unwrap();
+        assert!(verifier.check_response(&challenge, &response).unwrap().is_some());
+        // The challenge was used up by the first answer
+        assert!(verifier.check_response(&challenge, &response).unwrap().is_none());
+
+        let unknown: [u8; CHALLENGE_LEN] = random();
+        assert!(verifier.check_response(&unknown, &prover.respond(&unknown).unwrap()).unwrap().is_none());
+
+        let mut impatient = Verifier::new(auth.algorithm, auth.public_key.clone(), Duration::ZERO).unwrap();
+        let stale = impatient.issue_challenge();
+        let stale_response = prover.respond(&stale).unwrap();
+        thread::sleep(Duration::from_millis(1));
+        assert!(impatient.check_response(&stale, &stale_response).unwrap().is_none());
+    }
+
+    #[test]
+    fn saved_key_pair_loads_and_cross_verifies() {
+        let auth = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        let dir = scratch_dir("keypair");
+        auth.save_keypair(&dir).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.join(SECRET_KEY_FILE)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let message = b"signed before the restart";
+        let signature = auth.sign_message(message).unwrap();
+        let loaded = QuantumSafeAuth::load(&dir, auth.algorithm).unwrap();
+        assert!(loaded.verify_signature(message, &signature));
+        assert!(auth.verify_signature(message, &loaded.sign_message(message).unwrap()));
+
+        // A secret key from another key pair is refused
+        let other = QuantumSafeAuth::new_with(sig::Algorithm::Dilithium2).unwrap();
+        fs::write(dir.join(SECRET_KEY_FILE), other.secret_key.expose().as_ref()).unwrap();
+        assert_eq!(QuantumSafeAuth::load(&dir, auth.algorithm).err().map(|e| e.kind()), Some(ErrorKind::InvalidData));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_are_narrowed_to_the_owner() {
+        let dir = scratch_dir("private-file");
+        let path = dir.join("secret.key");
+        fs::write(&path, b"old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        write_private_file(&path, b"new secret").unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(fs::read(&path).unwrap(), b"new secret");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn public_keys_equal_needs_equal_length_and_bytes() {
+        let pinned = [0x5a; 32];
+        assert!(public_keys_equal(&pinned, &[0x5a; 32]));
+        let mut changed = pinned;
+        changed[31] ^= 0x01;
+        assert!(!public_keys_equal(&pinned, &changed));
+        assert!(!public_keys_equal(&pinned, &pinned[..31]));
+        assert!(!public_keys_equal(&pinned, &[pinned.as_slice(), &[0x5a]].concat()));
+        assert!(public_keys_equal(&[], &[]));
+    }
 }
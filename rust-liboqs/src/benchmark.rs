@@ -0,0 +1,100 @@
+use oqs::sig::{Algorithm, Sig};
+use std::time::{Duration, Instant};
+
+const BENCH_MESSAGE: &[u8] = b"Quantova signature benchmark message";
+
+/// Every Dilithium, Falcon and SPHINCS+ variant the toolkit knows how to benchmark.
+const SIG_ALGORITHMS: [Algorithm; 17] = [
+    Algorithm::Dilithium2,
+    Algorithm::Dilithium3,
+    Algorithm::Dilithium5,
+    Algorithm::Falcon512,
+    Algorithm::Falcon1024,
+    Algorithm::SphincsSha2128fSimple,
+    Algorithm::SphincsSha2128sSimple,
+    Algorithm::SphincsSha2192fSimple,
+    Algorithm::SphincsSha2192sSimple,
+    Algorithm::SphincsSha2256fSimple,
+    Algorithm::SphincsSha2256sSimple,
+    Algorithm::SphincsShake128fSimple,
+    Algorithm::SphincsShake128sSimple,
+    Algorithm::SphincsShake192fSimple,
+    Algorithm::SphincsShake192sSimple,
+    Algorithm::SphincsShake256fSimple,
+    Algorithm::SphincsShake256sSimple,
+];
+
+pub struct SigBenchRow {
+    pub algorithm: Algorithm,
+    pub keygen: Duration,
+    pub sign: Duration,
+    pub verify: Duration,
+    pub public_key_len: usize,
+    pub secret_key_len: usize,
+    pub signature_len: usize,
+}
+
+fn bench_sig(algorithm: Algorithm) -> Result<SigBenchRow, oqs::Error> {
+    let sig = Sig::new(algorithm)?;
+
+    let start = Instant::now();
+    let (public_key, secret_key) = sig.keypair()?;
+    let keygen = start.elapsed();
+
+    let start = Instant::now();
+    let signature = sig.sign(BENCH_MESSAGE, &secret_key)?;
+    let sign = start.elapsed();
+
+    let start = Instant::now();
+    sig.verify(BENCH_MESSAGE, &signature, &public_key)?;
+    let verify = start.elapsed();
+
+    Ok(SigBenchRow {
+        algorithm,
+        keygen,
+        sign,
+        verify,
+        public_key_len: public_key.as_ref().len(),
+        secret_key_len: secret_key.as_ref().len(),
+        signature_len: signature.as_ref().len(),
+    })
+}
+
+/// Runs keygen/sign/verify on a fixed message for every signature algorithm enabled in
+/// the linked liboqs build. Algorithms that are disabled or fail are skipped.
+pub fn benchmark_all_sigs() -> Vec<SigBenchRow> {
+    SIG_ALGORITHMS
+        .iter()
+        .filter_map(|&algorithm| match bench_sig(algorithm) {
+            Ok(row) => Some(row),
+            Err(e) => {
+                println!(" Skipping {:?}: {}", algorithm, e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn print_sig_bench_table(rows: &[SigBenchRow]) {
+    println!(
+        "\n{:<24} {:>11} {:>11} {:>11} {:>8} {:>8} {:>8}",
+        "Algorithm", "Keygen(ms)", "Sign(ms)", "Verify(ms)", "PK(B)", "SK(B)", "Sig(B)"
+    );
+    println!("{}", "-".repeat(87));
+    for row in rows {
+        println!(
+            "{:<24} {:>11.3} {:>11.3} {:>11.3} {:>8} {:>8} {:>8}",
+            format!("{:?}", row.algorithm),
+            millis(row.keygen),
+            millis(row.sign),
+            millis(row.verify),
+            row.public_key_len,
+            row.secret_key_len,
+            row.signature_len
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shared flag that asks long-running batch operations to stop early, e.g. on shutdown.
+///
+/// Batch operations check it between items, so an item already in progress completes.
+#[derive(Debug, Default)]
+pub struct CancellationToken {
+    cancelled: AtomicBool,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Error from a batch operation that processes items of type `T` and fails with `E`.
+#[derive(Debug)]
+pub enum BatchError<T, E> {
+    /// The token was cancelled; holds the items completed before that.
+    Cancelled { completed: Vec<T> },
+    Failed(E),
+}
+
+impl<T, E: fmt::Display> fmt::Display for BatchError<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Cancelled { completed } => write!(f, "cancelled after {} items", completed.len()),
+            BatchError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// Classical signature schemes for the classical half of a hybrid signature.
+//
+// Ed25519 is the default; ECDSA P-256 is available for deployments that mandate NIST curves.
+// Both are provided by ring.
+
+use crate::keygen::{self, KeygenError};
+use ring::rand::SystemRandom;
+use ring::signature::{
+    self, EcdsaKeyPair, Ed25519KeyPair, KeyPair, ECDSA_P256_SHA256_FIXED, ECDSA_P256_SHA256_FIXED_SIGNING, ED25519,
+};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum ClassicalError {
+    Keygen(KeygenError),
+    /// ring failed to produce a signature; for ECDSA this means the RNG failed.
+    Signing,
+}
+
+impl fmt::Display for ClassicalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassicalError::Keygen(e) => write!(f, "{}", e),
+            ClassicalError::Signing => write!(f, "classical signing failed"),
+        }
+    }
+}
+
+impl From<KeygenError> for ClassicalError {
+    fn from(e: KeygenError) -> Self {
+        ClassicalError::Keygen(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassicalScheme {
+    Ed25519,
+    EcdsaP256,
+}
+
+impl ClassicalScheme {
+    pub const ALL: [ClassicalScheme; 2] = [ClassicalScheme::Ed25519, ClassicalScheme::EcdsaP256];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ClassicalScheme::Ed25519 => "Ed25519",
+            ClassicalScheme::EcdsaP256 => "ECDSA P-256",
+        }
+    }
+
+    /// Generates a fresh key pair for this scheme.
+    pub fn generate(self) -> Result<Box<dyn ClassicalSigner>, ClassicalError> {
+        Ok(match self {
+            ClassicalScheme::Ed25519 => Box::new(Ed25519Signer::generate()?),
+            ClassicalScheme::EcdsaP256 => Box::new(EcdsaP256Signer::generate()?),
+        })
+    }
+
+    /// Verifies `signature` over `data` under `public_key`, for a receiver that holds only
+    /// the public key.
+    pub fn verify(self, data: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+        let algorithm: &dyn signature::VerificationAlgorithm = match self {
+            ClassicalScheme::Ed25519 => &ED25519,
+            ClassicalScheme::EcdsaP256 => &ECDSA_P256_SHA256_FIXED,
+        };
+        signature::UnparsedPublicKey::new(algorithm, public_key)
+            .verify(data, signature)
+            .is_ok()
+    }
+}
+
+/// A classical key pair that can sign the classical half of a hybrid signature.
+pub trait ClassicalSigner {
+    fn scheme(&self) -> ClassicalScheme;
+
+    fn public_key(&self) -> &[u8];
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, ClassicalError>;
+
+    /// Verifies `signature` over `data` under this key pair's public key.
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        self.scheme().verify(data, signature, self.public_key())
+    }
+}
+
+pub struct Ed25519Signer {
+    key_pair: Ed25519KeyPair,
+}
+
+impl Ed25519Signer {
+    pub fn generate() -> Result<Self, ClassicalError> {
+        let seed = keygen::random_seed()?;
+        let key_pair = Ed25519KeyPair::from_seed_unchecked(&seed).expect("Any 32-byte seed is an Ed25519 key.");
+        Ok(Self { key_pair })
+    }
+}
+
+impl ClassicalSigner for Ed25519Signer {
+    fn scheme(&self) -> ClassicalScheme {
+        ClassicalScheme::Ed25519
+    }
+
+    fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, ClassicalError> {
+        Ok(self.key_pair.sign(data).as_ref().to_vec())
+    }
+}
+
+/// ECDSA over P-256 with SHA-256, producing fixed-length 64-byte `r || s` signatures.
+pub struct EcdsaP256Signer {
+    key_pair: EcdsaKeyPair,
+    rng: SystemRandom,
+}
+
+impl EcdsaP256Signer {
+    pub fn generate() -> Result<Self, ClassicalError> {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &rng)
+            .map_err(|_| KeygenError::RngUnavailable)?;
+        let key_pair = EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8.as_ref())
+            .expect("ring accepts the PKCS#8 document it just generated.");
+        Ok(Self { key_pair, rng })
+    }
+}
+
+impl ClassicalSigner for EcdsaP256Signer {
+    fn scheme(&self) -> ClassicalScheme {
+        ClassicalScheme::EcdsaP256
+    }
+
+    fn public_key(&self) -> &[u8] {
+        self.key_pair.public_key().as_ref()
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, ClassicalError> {
+        let signature = self.key_pair.sign(&self.rng, data).map_err(|_| ClassicalError::Signing)?;
+        Ok(signature.as_ref().to_vec())
+    }
+}
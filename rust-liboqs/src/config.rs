@@ -0,0 +1,184 @@
+use crate::algorithm::AlgorithmId;
+use crate::shamir::MAX_TOTAL_SHARES;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+/// Default location of the toolkit configuration file.
+pub const DEFAULT_CONFIG_PATH: &str = "quantova.toml";
+
+/// AEAD ciphers the toolkit knows how to use. `auto` picks the faster one on this CPU.
+const SUPPORTED_AEADS: [&str; 3] = ["aes-256-gcm", "chacha20-poly1305", "auto"];
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    InvalidField { field: &'static str, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "failed to read config: {}", e),
+            ConfigError::Parse(e) => write!(f, "failed to parse config: {}", e),
+            ConfigError::InvalidField { field, reason } => write!(f, "invalid config field `{}`: {}", field, reason),
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Parse(e)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThresholdConfig {
+    pub t: usize,
+    pub n: usize,
+}
+
+impl Default for ThresholdConfig {
+    fn default() -> Self {
+        Self { t: 3, n: 5 }
+    }
+}
+
+/// Algorithms and parameters shared by every toolkit module, read from `quantova.toml`.
+///
+/// Every field is optional; missing fields fall back to the toolkit defaults.
+///
+/// ```toml
+/// signature = "Dilithium3"
+/// kem = "ML-KEM-768"
+/// aead = "aes-256-gcm"
+///
+/// [threshold]
+/// t = 3
+/// n = 5
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub signature: String,
+    pub kem: String,
+    pub threshold: ThresholdConfig,
+    pub aead: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            signature: "Dilithium2".to_string(),
+            kem: "ML-KEM-768".to_string(),
+            threshold: ThresholdConfig::default(),
+            aead: "aes-256-gcm".to_string(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads and validates a config file. A missing file yields the defaults.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let config: Config = toml::from_str(&contents)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |field, reason: String| Err(ConfigError::InvalidField { field, reason });
+
+        if AlgorithmId::from_name(&self.signature).and_then(AlgorithmId::to_sig).is_none() {
+            return invalid("signature", format!("{:?} is not a known signature algorithm", self.signature));
+        }
+        match AlgorithmId::from_name(&self.kem) {
+            Some(id) if id.to_sig().is_none() => {}
+            _ => return invalid("kem", format!("{:?} is not a known KEM", self.kem)),
+        }
+        let ThresholdConfig { t, n } = self.threshold;
+        if t == 0 || t > n || n > MAX_TOTAL_SHARES {
+            return invalid(
+                "threshold",
+                format!("need 1 <= t <= n <= {}, got t = {} and n = {}", MAX_TOTAL_SHARES, t, n),
+            );
+        }
+        if !SUPPORTED_AEADS.contains(&self.aead.to_ascii_lowercase().as_str()) {
+            return invalid("aead", format!("{:?} is not one of {}", self.aead, SUPPORTED_AEADS.join(", ")));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> Result<Config, ConfigError> {
+        let config: Config = toml::from_str(toml)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn invalid_field(toml: &str) -> &'static str {
+        match parse(toml) {
+            Err(ConfigError::InvalidField { field, .. }) => field,
+            other => panic!("expected an invalid field, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn missing_fields_and_sections_fall_back_to_the_defaults() {
+        let path = std::env::temp_dir().join(format!("quantova-config-test-{}-partial.toml", std::process::id()));
+        fs::write(&path, "kem = \"ML-KEM-1024\"\n\n[threshold]\nn = 7\n").unwrap();
+        let config = Config::load(&path);
+        let _ = fs::remove_file(&path);
+        let config = config.unwrap();
+        assert_eq!(config.kem, "ML-KEM-1024");
+        assert_eq!(config.signature, Config::default().signature);
+        assert_eq!(config.aead, Config::default().aead);
+        assert_eq!((config.threshold.t, config.threshold.n), (3, 7));
+
+        let empty = parse("").unwrap();
+        assert_eq!((empty.threshold.t, empty.threshold.n), (3, 5));
+    }
+
+    #[test]
+    fn each_invalid_field_is_named() {
+        assert_eq!(invalid_field("signature = \"ML-KEM-768\""), "signature");
+        assert_eq!(invalid_field("signature = \"RSA-2048\""), "signature");
+        assert_eq!(invalid_field("kem = \"Dilithium2\""), "kem");
+        assert_eq!(invalid_field("aead = \"aes-128-cbc\""), "aead");
+        assert_eq!(invalid_field("[threshold]\nt = 0"), "threshold");
+        assert_eq!(invalid_field("[threshold]\nt = 6\nn = 5"), "threshold");
+        assert_eq!(invalid_field(&format!("[threshold]\nn = {}", MAX_TOTAL_SHARES + 1)), "threshold");
+        assert!(parse(&format!("[threshold]\nt = 2\nn = {}", MAX_TOTAL_SHARES)).is_ok());
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(matches!(parse("signatures = \"Dilithium3\""), Err(ConfigError::Parse(_))));
+        assert!(matches!(parse("[threshold]\nm = 5"), Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn a_missing_file_yields_the_defaults() {
+        let path = std::env::temp_dir().join(format!("quantova-config-test-{}-missing.toml", std::process::id()));
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.signature, Config::default().signature);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::algorithm::AlgorithmId;
+use ciborium::value::Value;
+use oqs::sig::{self, Algorithm, Sig};
+use std::fmt;
+
+/// CBOR tag identifying a COSE_Sign1 structure (RFC 9052).
+const COSE_SIGN1_TAG: u64 = 18;
+/// Protected header label carrying the algorithm identifier.
+const HEADER_ALG: i64 = 1;
+/// Unprotected header label carrying the signer's key identifier.
+const HEADER_KID: i64 = 4;
+
+/// Returns the COSE algorithm identifier used for a liboqs signature algorithm.
+/// See [`AlgorithmId::cose_id`] for how the code points are assigned.
+pub fn cose_algorithm_id(algorithm: Algorithm) -> Option<i64> {
+    AlgorithmId::from_sig(algorithm)?.cose_id()
+}
+
+#[derive(Debug)]
+pub enum CoseError {
+    Malformed(&'static str),
+    UnsupportedAlgorithm,
+    AlgorithmMismatch { expected: i64, found: i64 },
+    InvalidSignature,
+}
+
+impl fmt::Display for CoseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoseError::Malformed(reason) => write!(f, "malformed COSE_Sign1: {}", reason),
+            CoseError::UnsupportedAlgorithm => write!(f, "algorithm has no COSE identifier"),
+            CoseError::AlgorithmMismatch { expected, found } => {
+                write!(f, "COSE algorithm {} does not match expected {}", found, expected)
+            }
+            CoseError::InvalidSignature => write!(f, "COSE_Sign1 signature is invalid"),
+        }
+    }
+}
+
+fn encode(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    ciborium::ser::into_writer(value, &mut out).expect("Writing CBOR to a Vec cannot fail.");
+    out
+}
+
+fn protected_header(alg_id: i64) -> Vec<u8> {
+    encode(&Value::Map(vec![(Value::Integer(HEADER_ALG.into()), Value::Integer(alg_id.into()))]))
+}
+
+fn sig_structure(protected: &[u8], payload: &[u8]) -> Vec<u8> {
+    encode(&Value::Array(vec![
+        Value::Text("Signature1".to_string()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(Vec::new()),
+        Value::Bytes(payload.to_vec()),
+    ]))
+}
+
+/// Returns the `Sig_structure` bytes that must be signed to produce a COSE_Sign1 signature.
+pub fn cose_to_be_signed(payload: &[u8], alg_id: i64) -> Vec<u8> {
+    sig_structure(&protected_header(alg_id), payload)
+}
+
+/// Builds a tagged COSE_Sign1 structure from a payload and a signature over
+/// [`cose_to_be_signed`] for the same payload and algorithm. `key_id` (see
+/// [`crate::keys::key_id`]) is placed in the unprotected `kid` header.
+pub fn to_cose_sign1(payload: &[u8], signature: &[u8], alg_id: i64, key_id: &[u8]) -> Vec<u8> {
+    encode(&Value::Tag(
+        COSE_SIGN1_TAG,
+        Box::new(Value::Array(vec![
+            Value::Bytes(protected_header(alg_id)),
+            Value::Map(vec![(Value::Integer(HEADER_KID.into()), Value::Bytes(key_id.to_vec()))]),
+            Value::Bytes(payload.to_vec()),
+            Value::Bytes(signature.to_vec()),
+        ])),
+    ))
+}
+
+fn header_alg(protected: &[u8]) -> Result<i64, CoseError> {
+    let header: Value = ciborium::de::from_reader(protected).map_err(|_| CoseError::Malformed("protected header"))?;
+    let entries = header.into_map().map_err(|_| CoseError::Malformed("protected header is not a map"))?;
+    entries
+        .into_iter()
+        .find(|(label, _)| label.as_integer() == Some(HEADER_ALG.into()))
+        .and_then(|(_, alg)| alg.as_integer())
+        .and_then(|alg| i64::try_from(alg).ok())
+        .ok_or(CoseError::Malformed("missing algorithm header"))
+}
+
+/// Splits a COSE_Sign1 structure, tagged or not, into its four elements.
+fn sign1_fields(cose: &[u8]) -> Result<[Value; 4], CoseError> {
+    let value: Value = ciborium::de::from_reader(cose).map_err(|_| CoseError::Malformed("invalid CBOR"))?;
+    let value = match value {
+        Value::Tag(COSE_SIGN1_TAG, inner) => *inner,
+        Value::Tag(..) => return Err(CoseError::Malformed("unexpected CBOR tag")),
+        untagged => untagged,
+    };
+    let fields = value.into_array().map_err(|_| CoseError::Malformed("not an array"))?;
+    fields.try_into().map_err(|_| CoseError::Malformed("expected four elements"))
+}
+
+/// Returns the `kid` header of a COSE_Sign1 structure, if present, so the receiver can look
+/// up the signer's public key before verifying. The header is unprotected: a wrong `kid`
+/// only selects a key that then fails verification.
+pub fn cose_key_id(cose: &[u8]) -> Result<Option<Vec<u8>>, CoseError> {
+    let [_, unprotected, _, _] = sign1_fields(cose)?;
+    let entries = unprotected.into_map().map_err(|_| CoseError::Malformed("unprotected header is not a map"))?;
+    Ok(entries
+        .into_iter()
+        .find(|(label, _)| label.as_integer() == Some(HEADER_KID.into()))
+        .and_then(|(_, kid)| kid.into_bytes().ok()))
+}
+
+/// Parses a COSE_Sign1 structure, checks that its algorithm matches `sig` and verifies the
+/// signature against `public_key`. Returns the payload on success.
+pub fn verify_cose_sign1(cose: &[u8], sig: &Sig, public_key: &sig::PublicKey) -> Result<Vec<u8>, CoseError> {
+    let [protected, _unprotected, payload, signature] = sign1_fields(cose)?;
+    let protected = protected.into_bytes().map_err(|_| CoseError::Malformed("protected header is not a bstr"))?;
+    let payload = payload.into_bytes().map_err(|_| CoseError::Malformed("detached payloads are not supported"))?;
+    let signature = signature.into_bytes().map_err(|_| CoseError::Malformed("signature is not a bstr"))?;
+
+    let expected = cose_algorithm_id(sig.algorithm()).ok_or(CoseError::UnsupportedAlgorithm)?;
+    let found = header_alg(&protected)?;
+    if found != expected {
+        return Err(CoseError::AlgorithmMismatch { expected, found });
+    }
+
+    let signature = sig.signature_from_bytes(&signature).ok_or(CoseError::InvalidSignature)?;
+    sig.verify(&sig_structure(&protected, &payload), signature, public_key)
+        .map_err(|_| CoseError::InvalidSignature)?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Signs `payload` as a tagged COSE_Sign1 with a fresh `algorithm` key pair.
+    fn signed(algorithm: Algorithm, payload: &[u8]) -> (Sig, sig::PublicKey, Vec<u8>) {
+        let sig = Sig::new(algorithm).unwrap();
+        let (public_key, secret_key) = sig.keypair().unwrap();
+        let alg_id = cose_algorithm_id(algorithm).unwrap();
+        let signature = sig.sign(&cose_to_be_signed(payload, alg_id), &secret_key).unwrap();
+        let cose = to_cose_sign1(payload, signature.as_ref(), alg_id, b"kid-1");
+        (sig, public_key, cose)
+    }
+
+    /// Returns the elements of a tagged COSE_Sign1, for rewriting one of them.
+    fn fields(cose: &[u8]) -> Vec<Value> {
+        let value: Value = ciborium::de::from_reader(cose).unwrap();
+        value.into_tag().unwrap().1.into_array().unwrap()
+    }
+
+    fn tagged(fields: Vec<Value>) -> Vec<u8> {
+        encode(&Value::Tag(COSE_SIGN1_TAG, Box::new(Value::Array(fields))))
+    }
+
+    #[test]
+    fn sign1_round_trips() {
+        let (sig, public_key, cose) = signed(Algorithm::Dilithium2, b"payload");
+        assert_eq!(verify_cose_sign1(&cose, &sig, &public_key).unwrap(), b"payload");
+        assert_eq!(cose_key_id(&cose).unwrap().as_deref(), Some(&b"kid-1"[..]));
+    }
+
+    #[test]
+    fn another_algorithm_is_reported_as_a_mismatch() {
+        let (_, _, cose) = signed(Algorithm::Dilithium2, b"payload");
+        let other = Sig::new(Algorithm::Falcon512).unwrap();
+        let (other_public_key, _) = other.keypair().unwrap();
+        assert!(matches!(
+            verify_cose_sign1(&cose, &other, &other_public_key),
+            Err(CoseError::AlgorithmMismatch { expected: -65540, found: -65537 })
+        ));
+    }
+
+    #[test]
+    fn a_modified_payload_or_protected_header_fails_verification() {
+        let (sig, public_key, cose) = signed(Algorithm::Dilithium2, b"payload");
+
+        let mut payload_changed = fields(&cose);
+        payload_changed[2] = Value::Bytes(b"Payload".to_vec());
+        assert!(matches!(verify_cose_sign1(&tagged(payload_changed), &sig, &public_key), Err(CoseError::InvalidSignature)));
+
+        // Same algorithm, but the protected bytes are no longer the ones that were signed
+        let mut header_changed = fields(&cose);
+        let extended = Value::Map(vec![
+            (Value::Integer(HEADER_ALG.into()), Value::Integer((-65537).into())),
+            (Value::Integer(3.into()), Value::Text("text/plain".to_string())),
+        ]);
+        header_changed[0] = Value::Bytes(encode(&extended));
+        assert!(matches!(verify_cose_sign1(&tagged(header_changed), &sig, &public_key), Err(CoseError::InvalidSignature)));
+    }
+
+    #[test]
+    fn untagged_sign1_is_accepted_but_other_shapes_are_not() {
+        let (sig, public_key, cose) = signed(Algorithm::Dilithium2, b"payload");
+        let untagged = encode(&Value::Array(fields(&cose)));
+        assert_eq!(verify_cose_sign1(&untagged, &sig, &public_key).unwrap(), b"payload");
+
+        let wrong_tag = encode(&Value::Tag(98, Box::new(Value::Array(fields(&cose)))));
+        assert!(matches!(verify_cose_sign1(&wrong_tag, &sig, &public_key), Err(CoseError::Malformed("unexpected CBOR tag"))));
+
+        let not_an_array = encode(&Value::Tag(COSE_SIGN1_TAG, Box::new(Value::Bytes(b"payload".to_vec()))));
+        assert!(matches!(verify_cose_sign1(&not_an_array, &sig, &public_key), Err(CoseError::Malformed("not an array"))));
+
+        let short = tagged(fields(&cose)[..3].to_vec());
+        assert!(matches!(verify_cose_sign1(&short, &sig, &public_key), Err(CoseError::Malformed("expected four elements"))));
+
+        assert!(matches!(verify_cose_sign1(b"\xff\x00", &sig, &public_key), Err(CoseError::Malformed("invalid CBOR"))));
+    }
+}
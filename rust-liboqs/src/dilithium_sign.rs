@@ -0,0 +1,302 @@
+// Plain Dilithium3 signing, with the armor and COSE_Sign1 encodings. This is the round-3
+// Dilithium3 submission as liboqs ships it, not the standardized ML-DSA-65 (FIPS 204): the
+// two differ in key and signature encodings and are not interoperable.
+//
+// This module used to be called "Post-Quantum Schnorr", but it has always wrapped liboqs's
+// Dilithium3 rather than a Schnorr construction of its own. Dilithium is itself a
+// Fiat-Shamir-with-aborts lattice signature, which is where the Schnorr comparison came from.
+
+use crate::algorithm::AlgorithmId;
+use crate::armor;
+use crate::cancellation::{BatchError, CancellationToken};
+use crate::cose;
+use crate::keygen::{self, KeygenError};
+use crate::keys::{key_id, KEY_ID_LEN};
+use crate::secret::ProtectedSecretKey;
+use oqs::sig::{Algorithm, Sig, Signature, PublicKey};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum DilithiumSignError {
+    Keygen(KeygenError),
+    Backend(oqs::Error),
+    /// A detached signature buffer whose length is not a Dilithium3 signature's.
+    MalformedSignature { expected: usize, actual: usize },
+}
+
+impl fmt::Display for DilithiumSignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DilithiumSignError::Keygen(e) => write!(f, "{}", e),
+            DilithiumSignError::Backend(e) => write!(f, "signature backend error: {}", e),
+            DilithiumSignError::MalformedSignature { expected, actual } => {
+                write!(f, "detached signature is {} bytes, expected {}", actual, expected)
+            }
+        }
+    }
+}
+
+impl From<KeygenError> for DilithiumSignError {
+    fn from(e: KeygenError) -> Self {
+        DilithiumSignError::Keygen(e)
+    }
+}
+
+impl From<oqs::Error> for DilithiumSignError {
+    fn from(e: oqs::Error) -> Self {
+        DilithiumSignError::Backend(e)
+    }
+}
+
+/// A Dilithium3 key pair with the `Sig` instance that every operation reuses.
+struct DilithiumSigner {
+    sig: Sig,
+    public_key: PublicKey,
+    secret_key: ProtectedSecretKey,
+}
+
+impl DilithiumSigner {
+    fn new() -> Result<Self, DilithiumSignError> {
+        let sig = Sig::new(Algorithm::Dilithium3)?;
+        let (public_key, secret_key) = keygen::generate_sig_keypair(&sig)?;
+        let secret_key = ProtectedSecretKey::new(secret_key);
+        println!(" Dilithium3 Key Pair Generated!");
+        println!("Public Key: {:?}", public_key);
+        println!("Secret Key: {:?}", secret_key);
+        Ok(Self { sig, public_key, secret_key })
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, DilithiumSignError> {
+        let signature = self.sig.sign(message, self.secret_key.expose())?;
+        println!("Signature created for message: {:?}", String::from_utf8_lossy(message));
+        println!("Signature: {:?}", signature);
+        Ok(signature)
+    }
+
+    fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        let result = self.sig.verify(message, signature, &self.public_key).is_ok();
+        if result {
+            println!("✅ Signature verification successful!");
+        } else {
+            println!("❌ Signature verification failed!");
+        }
+        result
+    }
+
+    /// Signs `message` and returns the raw signature bytes, to be stored next to the message.
+    fn sign_detached(&self, message: &[u8]) -> Result<Vec<u8>, DilithiumSignError> {
+        Ok(self.sig.sign(message, self.secret_key.expose())?.into_vec())
+    }
+
+    /// Verifies raw signature bytes from [`Self::sign_detached`] against `message` and
+    /// `public_key`. A buffer of the wrong length is an error rather than an invalid signature.
+    fn verify_detached(message: &[u8], signature: &[u8], public_key: &PublicKey) -> Result<bool, DilithiumSignError> {
+        let sig = Sig::new(Algorithm::Dilithium3)?;
+        // signature_from_bytes only rejects overlong buffers; Dilithium signatures have a fixed size
+        let malformed = DilithiumSignError::MalformedSignature { expected: sig.length_signature(), actual: signature.len() };
+        if signature.len() != sig.length_signature() {
+            return Err(malformed);
+        }
+        let signature = sig.signature_from_bytes(signature).ok_or(malformed)?;
+        Ok(sig.verify(message, signature, public_key).is_ok())
+    }
+
+    /// Signs every message in order, stopping at the first backend error.
+    ///
+    /// `cancel` is checked before each message. Once it is set, signing stops and
+    /// [`BatchError::Cancelled`] returns the signatures completed so far.
+    fn sign_batch(
+        &self,
+        messages: &[&[u8]],
+        cancel: &CancellationToken,
+    ) -> Result<Vec<Signature>, BatchError<Signature, DilithiumSignError>> {
+        let mut signatures = Vec::with_capacity(messages.len());
+        for message in messages {
+            if cancel.is_cancelled() {
+                return Err(BatchError::Cancelled { completed: signatures });
+            }
+            let signature = self.sig.sign(message, self.secret_key.expose()).map_err(|e| BatchError::Failed(e.into()))?;
+            signatures.push(signature);
+        }
+        Ok(signatures)
+    }
+
+    /// Verifies each `(message, signature)` pair, returning one result per pair in order.
+    /// `cancel` is checked before each pair, as in [`Self::sign_batch`].
+    fn verify_batch(
+        &self,
+        pairs: &[(&[u8], &Signature)],
+        cancel: &CancellationToken,
+    ) -> Result<Vec<bool>, BatchError<bool, Infallible>> {
+        let mut results = Vec::with_capacity(pairs.len());
+        for &(message, signature) in pairs {
+            if cancel.is_cancelled() {
+                return Err(BatchError::Cancelled { completed: results });
+            }
+            results.push(self.sig.verify(message, signature, &self.public_key).is_ok());
+        }
+        Ok(results)
+    }
+}
+
+pub fn dilithium_sign() -> Result<(), DilithiumSignError> {
+    let message = b"Dilithium3 Signature Example";
+    println!("📝 Message: {}",
+        String::from_utf8_lossy(message));
+
+    let signer = DilithiumSigner::new()?;
+
+    // Sign the message
+    let signature = signer.sign(message)?;
+
+    // Verify the signature
+    println!("🔍 Verifying Signature...");
+    signer.verify(message, &signature);
+
+    // Sign again and compare with the first signature
+    let resigned = signer.sign(message)?;
+    signer.verify(message, &resigned);
+    println!(
+        "🔁 Re-signing produced {} signature (deterministic: {})",
+        if resigned.as_ref() == signature.as_ref() { "an identical" } else { "a different" },
+        AlgorithmId::Dilithium3.is_deterministic()
+    );
+
+    // Sign a batch with one scheme instance, then verify it with one message altered
+    let batch: [&[u8]; 4] = [b"transfer 1", b"transfer 2", b"transfer 3", b"transfer 4"];
+    let cancel = CancellationToken::new();
+    match signer.sign_batch(&batch, &cancel) {
+        Ok(batch_signatures) => {
+            let mut pairs: Vec<(&[u8], &Signature)> = batch.iter().copied().zip(&batch_signatures).collect();
+            if let Ok(results) = signer.verify_batch(&pairs, &cancel) {
+                println!("📚 Batch of {} signed and verified: {:?}", batch.len(), results);
+            }
+            pairs[2].0 = b"transfer 3000";
+            if let Ok(results) = signer.verify_batch(&pairs, &cancel) {
+                println!("📚 Same batch with the third message altered: {:?}", results);
+            }
+        }
+        Err(e) => println!("❌ Batch signing failed: {}", e),
+    }
+
+    // Detached signature bytes verify on their own; a truncated buffer is an error
+    let detached = signer.sign_detached(message)?;
+    let detached_valid = DilithiumSigner::verify_detached(message, &detached, &signer.public_key)?;
+    println!(
+        "{} Detached signature ({} bytes) verified",
+        if detached_valid { "✅" } else { "❌" },
+        detached.len()
+    );
+    match DilithiumSigner::verify_detached(message, &detached[..detached.len() - 1], &signer.public_key) {
+        Err(e) => println!("✅ Truncated detached signature rejected: {}", e),
+        Ok(valid) => println!("❌ Truncated detached signature accepted as {}", valid),
+    }
+
+    // Armor the signature for pasting into text channels, then read it back
+    let armored = armor::armor_signature(signature.as_ref(), AlgorithmId::Dilithium3);
+    println!("📜 Armored signature: {} lines, starting {:?}", armored.lines().count(), armored.lines().nth(1).unwrap_or(""));
+    match armor::dearmor_signature(&armored) {
+        Ok((algorithm, bytes)) => {
+            let valid = algorithm == AlgorithmId::Dilithium3 && bytes == signature.as_ref();
+            println!("{} Dearmored {} signature, {} bytes", if valid { "✅" } else { "❌" }, algorithm.liboqs_name(), bytes.len());
+        }
+        Err(e) => println!("❌ Failed to dearmor signature: {}", e),
+    }
+
+    // Wrap the message in a COSE_Sign1 structure and verify it again
+    println!("📦 Encoding message as COSE_Sign1...");
+    // Resolve the algorithm through its OID, as a peer reading a key or header would
+    let oid = AlgorithmId::Dilithium3.to_oid();
+    let algorithm = AlgorithmId::from_oid(oid).and_then(AlgorithmId::to_sig).expect("Dilithium3's OID names Dilithium3.");
+    println!("Algorithm: {} (OID {})", AlgorithmId::Dilithium3.liboqs_name(), oid);
+    let sig = Sig::new(algorithm)?;
+    let alg_id = cose::cose_algorithm_id(algorithm).expect("Dilithium3 has a COSE algorithm ID.");
+    let cose_signature = sig.sign(&cose::cose_to_be_signed(message, alg_id), signer.secret_key.expose())?;
+    let signer_kid = key_id(signer.public_key.as_ref());
+    let cose_sign1 = cose::to_cose_sign1(message, cose_signature.as_ref(), alg_id, &signer_kid);
+    println!("COSE_Sign1 structure: {} bytes, kid {}", cose_sign1.len(), hex::encode(signer_kid));
+    // The receiver indexes known signers by KID and picks the key named in the header
+    let decoy = keygen::generate_sig_keypair(&sig)?.0;
+    let key_store: HashMap<[u8; KEY_ID_LEN], &PublicKey> =
+        [&decoy, &signer.public_key].into_iter().map(|public_key| (key_id(public_key.as_ref()), public_key)).collect();
+    let signer_key = cose::cose_key_id(&cose_sign1)
+        .ok()
+        .flatten()
+        .and_then(|kid| <[u8; KEY_ID_LEN]>::try_from(kid).ok())
+        .and_then(|kid| key_store.get(&kid).copied());
+    let Some(signer_key) = signer_key else {
+        println!("❌ COSE_Sign1 kid does not name a known signer");
+        return Ok(());
+    };
+    match cose::verify_cose_sign1(&cose_sign1, &sig, signer_key) {
+        Ok(_) => println!("✅ COSE_Sign1 verification successful!"),
+        Err(e) => println!("❌ COSE_Sign1 verification failed: {}", e),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_verifies_and_flags_a_tampered_entry() {
+        let signer = DilithiumSigner::new().unwrap();
+        let batch: [&[u8]; 3] = [b"transfer 1", b"transfer 2", b"transfer 3"];
+        let cancel = CancellationToken::new();
+        let signatures = signer.sign_batch(&batch, &cancel).unwrap();
+        let mut pairs: Vec<(&[u8], &Signature)> = batch.iter().copied().zip(&signatures).collect();
+        assert_eq!(signer.verify_batch(&pairs, &cancel).unwrap(), [true, true, true]);
+
+        pairs[1].0 = b"transfer 2000";
+        assert_eq!(signer.verify_batch(&pairs, &cancel).unwrap(), [true, false, true]);
+        // Every signature is valid, just for a different message
+        let shifted: Vec<(&[u8], &Signature)> = batch.iter().copied().zip(signatures.iter().cycle().skip(1)).collect();
+        assert_eq!(signer.verify_batch(&shifted, &cancel).unwrap(), [false, false, false]);
+    }
+
+    #[test]
+    fn a_cancelled_batch_returns_what_it_completed() {
+        let signer = DilithiumSigner::new().unwrap();
+        let messages: Vec<&[u8]> = vec![b"transfer"; 20_000];
+        let cancel = CancellationToken::new();
+        let outcome = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                cancel.cancel();
+            });
+            signer.sign_batch(&messages, &cancel)
+        });
+        let Err(BatchError::Cancelled { completed }) = outcome else {
+            panic!("the batch was not cancelled");
+        };
+        assert!(completed.len() < messages.len());
+        let pairs: Vec<(&[u8], &Signature)> = messages.iter().copied().zip(&completed).collect();
+        assert!(signer.verify_batch(&pairs, &CancellationToken::new()).unwrap().into_iter().all(|valid| valid));
+
+        // Verification stops the same way, before the first pair here
+        assert!(matches!(
+            signer.verify_batch(&pairs, &cancel),
+            Err(BatchError::Cancelled { completed }) if completed.is_empty()
+        ));
+    }
+
+    #[test]
+    fn detached_signature_of_the_wrong_length_is_an_error() {
+        let signer = DilithiumSigner::new().unwrap();
+        let mut signature = signer.sign_detached(b"detached").unwrap();
+        let expected = signature.len();
+        assert!(DilithiumSigner::verify_detached(b"detached", &signature, &signer.public_key).unwrap());
+        assert!(!DilithiumSigner::verify_detached(b"attached", &signature, &signer.public_key).unwrap());
+
+        for actual in [0, expected - 1, expected + 1] {
+            signature.resize(actual, 0);
+            assert!(matches!(
+                DilithiumSigner::verify_detached(b"detached", &signature, &signer.public_key),
+                Err(DilithiumSignError::MalformedSignature { expected: e, actual: a }) if e == expected && a == actual
+            ));
+        }
+    }
+}
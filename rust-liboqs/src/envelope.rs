@@ -0,0 +1,372 @@
+use crate::keys::{key_id, KEY_ID_LEN};
+use oqs::kem::{self, Kem};
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, CHACHA20_POLY1305};
+use ring::hkdf::{self, HKDF_SHA256};
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// HKDF info string binding the derived AEAD key to this construction.
+const ENVELOPE_KEY_INFO: &[u8] = b"Quantova KEM envelope v1";
+
+/// Envelope format written by [`seal`]; [`open`] reads this version and no later one.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum EnvelopeError {
+    Backend(oqs::Error),
+    /// The KEM ciphertext does not have the size the algorithm expects.
+    MalformedCiphertext,
+    /// The AEAD tag did not verify: wrong recipient key or modified envelope.
+    Decryption,
+    /// The envelope was written in a format newer (or other) than this reader understands.
+    UnsupportedVersion { got: u8, max_supported: u8 },
+    /// The envelope was sealed for a different purpose than the caller accepts.
+    PurposeMismatch { expected: String, found: String },
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::Backend(e) => write!(f, "KEM backend error: {}", e),
+            EnvelopeError::MalformedCiphertext => write!(f, "malformed KEM ciphertext"),
+            EnvelopeError::Decryption => write!(f, "decryption failed: wrong key or modified envelope"),
+            EnvelopeError::UnsupportedVersion { got, max_supported } => write!(
+                f,
+                "unsupported envelope version {} (this build reads up to version {})",
+                got, max_supported
+            ),
+            EnvelopeError::PurposeMismatch { expected, found } => {
+                write!(f, "envelope was sealed for {:?}, expected {:?}", found, expected)
+            }
+        }
+    }
+}
+
+impl From<oqs::Error> for EnvelopeError {
+    fn from(e: oqs::Error) -> Self {
+        EnvelopeError::Backend(e)
+    }
+}
+
+/// Size of the buffer encrypted by [`fastest_aead`] when timing each cipher.
+const BENCH_BUFFER_LEN: usize = 16 * 1024;
+/// Timed rounds per cipher; the best round counts, which filters out scheduler noise.
+const BENCH_ROUNDS: usize = 8;
+
+/// AEAD cipher protecting an envelope's payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aead {
+    Aes256Gcm,
+    ChaCha20Poly1305,
+    /// Whichever of the two is faster on this CPU, see [`fastest_aead`].
+    Auto,
+}
+
+impl Aead {
+    /// Parses the config spelling, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "aes-256-gcm" => Some(Aead::Aes256Gcm),
+            "chacha20-poly1305" => Some(Aead::ChaCha20Poly1305),
+            "auto" => Some(Aead::Auto),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Aead::Aes256Gcm => "aes-256-gcm",
+            Aead::ChaCha20Poly1305 => "chacha20-poly1305",
+            Aead::Auto => "auto",
+        }
+    }
+
+    /// Resolves [`Aead::Auto`] to a concrete cipher; the others are returned unchanged.
+    pub fn resolve(self) -> Self {
+        match self {
+            Aead::Auto => fastest_aead(),
+            concrete => concrete,
+        }
+    }
+
+    fn algorithm(self) -> &'static aead::Algorithm {
+        match self.resolve() {
+            Aead::ChaCha20Poly1305 => &CHACHA20_POLY1305,
+            _ => &AES_256_GCM,
+        }
+    }
+}
+
+/// Best time to seal a [`BENCH_BUFFER_LEN`]-byte buffer with `algorithm`.
+fn time_aead(algorithm: &'static aead::Algorithm) -> Duration {
+    let key = LessSafeKey::new(UnboundKey::new(algorithm, &[0x42; 32]).expect("Both ciphers take 32-byte keys."));
+    let mut buffer = vec![0u8; BENCH_BUFFER_LEN];
+    (0..BENCH_ROUNDS)
+        .map(|_| {
+            buffer.truncate(BENCH_BUFFER_LEN);
+            let start = Instant::now();
+            key.seal_in_place_append_tag(nonce(), Aad::empty(), &mut buffer)
+                .expect("AEAD sealing only fails for oversized inputs");
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Micro-benchmarks AES-256-GCM and ChaCha20-Poly1305 over a small buffer and returns the
+/// faster one. AES-GCM wins on CPUs with AES instructions, ChaCha20-Poly1305 elsewhere.
+///
+/// The benchmark runs once; later calls return the cached choice for the process lifetime.
+pub fn fastest_aead() -> Aead {
+    static FASTEST: OnceLock<Aead> = OnceLock::new();
+    *FASTEST.get_or_init(|| {
+        if time_aead(&CHACHA20_POLY1305) < time_aead(&AES_256_GCM) {
+            Aead::ChaCha20Poly1305
+        } else {
+            Aead::Aes256Gcm
+        }
+    })
+}
+
+/// A message encrypted to a recipient's KEM public key.
+#[derive(Debug, Clone)]
+pub struct Envelope {
+    /// Format version, [`ENVELOPE_VERSION`] for envelopes made by this build. It is
+    /// authenticated along with the KEM ciphertext, so it cannot be rewritten in transit.
+    pub version: u8,
+    pub kem: kem::Algorithm,
+    /// What the sender meant this message to be, e.g. `"config-update"` or `"command"`. It is
+    /// authenticated as associated data, so use [`open_expecting`] to refuse a message sealed
+    /// for another purpose under the same recipient key.
+    pub purpose: String,
+    /// [`key_id`] of the recipient's public key, so a recipient holding several keys knows
+    /// which one opens this envelope. It is authenticated, so a rewritten ID fails to open.
+    pub recipient_key_id: [u8; KEY_ID_LEN],
+    /// Cipher used for `ciphertext`; never [`Aead::Auto`].
+    pub aead: Aead,
+    /// KEM ciphertext encapsulating the key for this envelope.
+    pub encapsulated_key: Vec<u8>,
+    /// AEAD ciphertext followed by the tag.
+    pub ciphertext: Vec<u8>,
+}
+
+/// Derives the AEAD key from the KEM shared secret.
+fn aead_key(shared_secret: &kem::SharedSecret, cipher: Aead) -> LessSafeKey {
+    let prk = hkdf::Salt::new(HKDF_SHA256, &[]).extract(shared_secret.as_ref());
+    let info = [ENVELOPE_KEY_INFO];
+    let okm = prk
+        .expand(&info, cipher.algorithm())
+        .expect("32-byte AEAD keys are a valid HKDF-SHA256 output length");
+    LessSafeKey::new(UnboundKey::from(okm))
+}
+
+impl Envelope {
+    /// Bytes of encrypted data the envelope carries: the KEM ciphertext plus the AEAD
+    /// ciphertext and tag. The small header fields are not counted.
+    pub fn encrypted_len(&self) -> usize {
+        self.encapsulated_key.len() + self.ciphertext.len()
+    }
+}
+
+/// Predicts [`Envelope::encrypted_len`] of sealing a `plaintext_len`-byte message with
+/// `algorithm` and `cipher`, without encapsulating or encrypting anything: the KEM
+/// ciphertext size plus the plaintext plus the AEAD tag. [`seal`] neither pads nor
+/// compresses, so the estimate is exact.
+pub fn estimated_ciphertext_size(plaintext_len: usize, algorithm: kem::Algorithm, cipher: Aead) -> Result<usize, EnvelopeError> {
+    let kem = Kem::new(algorithm)?;
+    Ok(kem.length_ciphertext() + plaintext_len + cipher.algorithm().tag_len())
+}
+
+/// Associated data for a version 1 envelope: the version byte, the recipient's key ID, the
+/// purpose prefixed by its 4-byte big-endian length, and the KEM ciphertext.
+fn associated_data(version: u8, recipient_key_id: &[u8; KEY_ID_LEN], purpose: &str, encapsulated_key: &[u8]) -> Vec<u8> {
+    let mut aad = vec![version];
+    aad.extend_from_slice(recipient_key_id);
+    aad.extend_from_slice(&(purpose.len() as u32).to_be_bytes());
+    aad.extend_from_slice(purpose.as_bytes());
+    aad.extend_from_slice(encapsulated_key);
+    aad
+}
+
+/// Every envelope encapsulates a fresh shared secret, so each AEAD key is used exactly once
+/// and a fixed nonce is safe.
+fn nonce() -> Nonce {
+    Nonce::assume_unique_for_key([0u8; aead::NONCE_LEN])
+}
+
+/// Encrypts `plaintext` to `recipient`: encapsulates a fresh shared secret, derives an
+/// AEAD key from it with HKDF-SHA256 and encrypts under that key. The format version, the
+/// recipient's key ID, `purpose` and the KEM ciphertext are authenticated as associated
+/// data. [`Aead::Auto`] picks the faster cipher on this host.
+pub fn seal(
+    plaintext: &[u8],
+    recipient: &kem::PublicKey,
+    algorithm: kem::Algorithm,
+    cipher: Aead,
+    purpose: &str,
+) -> Result<Envelope, EnvelopeError> {
+    let cipher = cipher.resolve();
+    let kem = Kem::new(algorithm)?;
+    let (encapsulated_key, shared_secret) = kem.encapsulate(recipient)?;
+    let recipient_key_id = key_id(recipient.as_ref());
+    let mut ciphertext = plaintext.to_vec();
+    aead_key(&shared_secret, cipher)
+        .seal_in_place_append_tag(
+            nonce(),
+            Aad::from(associated_data(ENVELOPE_VERSION, &recipient_key_id, purpose, encapsulated_key.as_ref())),
+            &mut ciphertext,
+        )
+        .expect("AEAD sealing only fails for oversized inputs");
+    Ok(Envelope {
+        version: ENVELOPE_VERSION,
+        kem: algorithm,
+        purpose: purpose.to_string(),
+        recipient_key_id,
+        aead: cipher,
+        encapsulated_key: encapsulated_key.into_vec(),
+        ciphertext,
+    })
+}
+
+/// Decrypts an envelope made by [`seal`] with the recipient's secret key. Envelopes of an
+/// unknown version are rejected with [`EnvelopeError::UnsupportedVersion`] before any of
+/// their fields are interpreted.
+pub fn open(envelope: &Envelope, recipient: &kem::SecretKey) -> Result<Vec<u8>, EnvelopeError> {
+    match envelope.version {
+        1 => open_v1(envelope, recipient),
+        got => Err(EnvelopeError::UnsupportedVersion { got, max_supported: ENVELOPE_VERSION }),
+    }
+}
+
+/// Like [`open`], but fails with [`EnvelopeError::PurposeMismatch`] unless the envelope was
+/// sealed for `expected_purpose`. Because the purpose is authenticated, relabeling an
+/// envelope to pass this check makes decryption fail instead.
+pub fn open_expecting(
+    envelope: &Envelope,
+    recipient: &kem::SecretKey,
+    expected_purpose: &str,
+) -> Result<Vec<u8>, EnvelopeError> {
+    if envelope.purpose != expected_purpose {
+        return Err(EnvelopeError::PurposeMismatch {
+            expected: expected_purpose.to_string(),
+            found: envelope.purpose.clone(),
+        });
+    }
+    open(envelope, recipient)
+}
+
+fn open_v1(envelope: &Envelope, recipient: &kem::SecretKey) -> Result<Vec<u8>, EnvelopeError> {
+    let kem = Kem::new(envelope.kem)?;
+    let encapsulated_key = kem
+        .ciphertext_from_bytes(&envelope.encapsulated_key)
+        .ok_or(EnvelopeError::MalformedCiphertext)?;
+    let shared_secret = kem.decapsulate(recipient, encapsulated_key)?;
+    let mut plaintext = envelope.ciphertext.clone();
+    let len = aead_key(&shared_secret, envelope.aead)
+        .open_in_place(
+            nonce(),
+            Aad::from(associated_data(
+                envelope.version,
+                &envelope.recipient_key_id,
+                &envelope.purpose,
+                &envelope.encapsulated_key,
+            )),
+            &mut plaintext,
+        )
+        .map_err(|_| EnvelopeError::Decryption)?
+        .len();
+    plaintext.truncate(len);
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEM: kem::Algorithm = kem::Algorithm::MlKem768;
+
+    fn recipient() -> (kem::PublicKey, kem::SecretKey) {
+        Kem::new(KEM).unwrap().keypair().unwrap()
+    }
+
+    #[test]
+    fn seal_open_round_trip() {
+        let (pk, sk) = recipient();
+        for cipher in [Aead::Aes256Gcm, Aead::ChaCha20Poly1305] {
+            let envelope = seal(b"hello", &pk, KEM, cipher, "test").unwrap();
+            assert_eq!(envelope.version, ENVELOPE_VERSION);
+            assert_eq!(open(&envelope, &sk).unwrap(), b"hello");
+        }
+    }
+
+    #[test]
+    fn v2_envelope_is_rejected_by_v1_reader() {
+        let (pk, sk) = recipient();
+        let mut envelope = seal(b"hello", &pk, KEM, Aead::Aes256Gcm, "test").unwrap();
+        envelope.version = 2;
+        assert!(matches!(
+            open(&envelope, &sk),
+            Err(EnvelopeError::UnsupportedVersion { got: 2, max_supported: ENVELOPE_VERSION })
+        ));
+    }
+
+    #[test]
+    fn open_expecting_accepts_matching_purpose() {
+        let (pk, sk) = recipient();
+        let envelope = seal(b"rotate keys", &pk, KEM, Aead::Aes256Gcm, "command").unwrap();
+        assert_eq!(open_expecting(&envelope, &sk, "command").unwrap(), b"rotate keys");
+    }
+
+    #[test]
+    fn open_expecting_rejects_other_purpose() {
+        let (pk, sk) = recipient();
+        let envelope = seal(b"max_conn = 10", &pk, KEM, Aead::Aes256Gcm, "config-update").unwrap();
+        assert!(matches!(
+            open_expecting(&envelope, &sk, "command"),
+            Err(EnvelopeError::PurposeMismatch { expected, found }) if expected == "command" && found == "config-update"
+        ));
+    }
+
+    #[test]
+    fn relabeled_purpose_fails_authentication() {
+        let (pk, sk) = recipient();
+        let mut envelope = seal(b"max_conn = 10", &pk, KEM, Aead::Aes256Gcm, "config-update").unwrap();
+        envelope.purpose = "command".to_string();
+        assert!(matches!(open_expecting(&envelope, &sk, "command"), Err(EnvelopeError::Decryption)));
+    }
+
+    #[test]
+    fn rewritten_recipient_key_id_fails_authentication() {
+        let (pk, sk) = recipient();
+        let mut envelope = seal(b"hello", &pk, KEM, Aead::Aes256Gcm, "test").unwrap();
+        assert_eq!(envelope.recipient_key_id, key_id(pk.as_ref()));
+        envelope.recipient_key_id[0] ^= 0x01;
+        assert!(matches!(open(&envelope, &sk), Err(EnvelopeError::Decryption)));
+    }
+
+    #[test]
+    fn estimated_size_matches_sealed_output() {
+        let (pk, _) = recipient();
+        for cipher in [Aead::Aes256Gcm, Aead::ChaCha20Poly1305, Aead::Auto] {
+            for len in [0, 1, 15, 16, 1000, 64 * 1024] {
+                let envelope = seal(&vec![0x5a; len], &pk, KEM, cipher, "test").unwrap();
+                let estimate = estimated_ciphertext_size(len, KEM, cipher).unwrap();
+                assert_eq!(estimate, envelope.encrypted_len(), "{:?}, {} bytes", cipher, len);
+            }
+        }
+    }
+
+    #[test]
+    fn fastest_aead_is_concrete_stable_and_round_trips() {
+        let fastest = fastest_aead();
+        assert!(matches!(fastest, Aead::Aes256Gcm | Aead::ChaCha20Poly1305));
+        assert_eq!(fastest_aead(), fastest);
+        assert_eq!(Aead::Auto.resolve(), fastest);
+
+        let (pk, sk) = recipient();
+        let envelope = seal(b"auto", &pk, KEM, Aead::Auto, "test").unwrap();
+        assert_eq!(envelope.aead, fastest);
+        assert_eq!(open(&envelope, &sk).unwrap(), b"auto");
+    }
+}
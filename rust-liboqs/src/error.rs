@@ -0,0 +1,348 @@
+use crate::armor::ArmorError;
+use crate::authentication::AuthError;
+use crate::config::ConfigError;
+use crate::dilithium_sign::DilithiumSignError;
+use crate::envelope::EnvelopeError;
+use crate::hybrid_keys::{HybridError, HybridKemError, KeyLoadError};
+use crate::keygen::KeygenError;
+use crate::log_signer::LogError;
+use crate::manifest::ManifestError;
+use crate::multisig::MultiSigError;
+use crate::password_channel::ChannelError;
+use crate::selftest::SelfTestError;
+use crate::threshold::ThresholdError;
+use crate::vss::VssError;
+use ring::error::Unspecified;
+use std::fmt;
+use std::io;
+
+/// One error type for the whole toolkit, so `?` works across modules.
+///
+/// Each module keeps its own error enum; this wraps them and adds the algorithm that was in
+/// use where it is known (see [`QuantovaError::with_algorithm`]). Errors from the pqcrypto
+/// examples' key and ciphertext types convert too, so code mixing both backends can use `?`.
+#[derive(Debug)]
+pub enum QuantovaError {
+    Backend { algorithm: Option<&'static str>, source: oqs::Error },
+    /// A pqcrypto key, ciphertext or signature had the wrong length.
+    Pqcrypto(pqcrypto_traits::Error),
+    Keygen { algorithm: Option<&'static str>, source: KeygenError },
+    /// An AEAD seal or open failed; ring does not say why.
+    Aead { algorithm: Option<&'static str> },
+    Io(io::Error),
+    Config(ConfigError),
+    Manifest(ManifestError),
+    Log(LogError),
+    Threshold(ThresholdError),
+    Auth(AuthError),
+    Hybrid(HybridError),
+    HybridKem(HybridKemError),
+    KeyLoad(KeyLoadError),
+    Envelope(EnvelopeError),
+    Channel(ChannelError),
+    Vss(VssError),
+    Armor(ArmorError),
+    MultiSig(MultiSigError),
+    SelfTest(SelfTestError),
+    DilithiumSign(DilithiumSignError),
+}
+
+impl QuantovaError {
+    /// Records the algorithm a backend, key generation or AEAD error came from.
+    pub fn with_algorithm(self, name: &'static str) -> Self {
+        match self {
+            QuantovaError::Backend { source, .. } => QuantovaError::Backend { algorithm: Some(name), source },
+            QuantovaError::Keygen { source, .. } => QuantovaError::Keygen { algorithm: Some(name), source },
+            QuantovaError::Aead { .. } => QuantovaError::Aead { algorithm: Some(name) },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for QuantovaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuantovaError::Backend { algorithm: Some(name), source } => write!(f, "{}: liboqs error: {}", name, source),
+            QuantovaError::Backend { algorithm: None, source } => write!(f, "liboqs error: {}", source),
+            QuantovaError::Pqcrypto(e) => write!(f, "pqcrypto error: {}", e),
+            QuantovaError::Keygen { algorithm: Some(name), source } => write!(f, "{}: {}", name, source),
+            QuantovaError::Keygen { algorithm: None, source } => write!(f, "{}", source),
+            QuantovaError::Aead { algorithm: Some(name) } => write!(f, "{}: encryption or decryption failed", name),
+            QuantovaError::Aead { algorithm: None } => write!(f, "encryption or decryption failed"),
+            QuantovaError::Io(e) => write!(f, "I/O error: {}", e),
+            QuantovaError::Config(e) => write!(f, "{}", e),
+            QuantovaError::Manifest(e) => write!(f, "manifest: {}", e),
+            QuantovaError::Log(e) => write!(f, "signed log: {}", e),
+            QuantovaError::Threshold(e) => write!(f, "threshold: {}", e),
+            QuantovaError::Auth(e) => write!(f, "authentication: {}", e),
+            QuantovaError::Hybrid(e) => write!(f, "hybrid signature: {}", e),
+            QuantovaError::HybridKem(e) => write!(f, "hybrid key exchange: {}", e),
+            QuantovaError::KeyLoad(e) => write!(f, "{}", e),
+            QuantovaError::Envelope(e) => write!(f, "envelope: {}", e),
+            QuantovaError::Channel(e) => write!(f, "password channel: {}", e),
+            QuantovaError::Vss(e) => write!(f, "verifiable secret sharing: {}", e),
+            QuantovaError::Armor(e) => write!(f, "armor: {}", e),
+            QuantovaError::MultiSig(e) => write!(f, "multi-signature: {}", e),
+            QuantovaError::SelfTest(e) => write!(f, "{}", e),
+            QuantovaError::DilithiumSign(e) => write!(f, "Dilithium signing: {}", e),
+        }
+    }
+}
+
+impl From<oqs::Error> for QuantovaError {
+    fn from(e: oqs::Error) -> Self {
+        QuantovaError::Backend { algorithm: None, source: e }
+    }
+}
+
+impl From<pqcrypto_traits::Error> for QuantovaError {
+    fn from(e: pqcrypto_traits::Error) -> Self {
+        QuantovaError::Pqcrypto(e)
+    }
+}
+
+impl From<KeygenError> for QuantovaError {
+    fn from(e: KeygenError) -> Self {
+        QuantovaError::Keygen { algorithm: None, source: e }
+    }
+}
+
+impl From<Unspecified> for QuantovaError {
+    fn from(_: Unspecified) -> Self {
+        QuantovaError::Aead { algorithm: None }
+    }
+}
+
+impl From<io::Error> for QuantovaError {
+    fn from(e: io::Error) -> Self {
+        QuantovaError::Io(e)
+    }
+}
+
+impl From<ConfigError> for QuantovaError {
+    fn from(e: ConfigError) -> Self {
+        QuantovaError::Config(e)
+    }
+}
+
+impl From<ManifestError> for QuantovaError {
+    fn from(e: ManifestError) -> Self {
+        QuantovaError::Manifest(e)
+    }
+}
+
+impl From<LogError> for QuantovaError {
+    fn from(e: LogError) -> Self {
+        QuantovaError::Log(e)
+    }
+}
+
+impl From<ThresholdError> for QuantovaError {
+    fn from(e: ThresholdError) -> Self {
+        QuantovaError::Threshold(e)
+    }
+}
+
+impl From<AuthError> for QuantovaError {
+    fn from(e: AuthError) -> Self {
+        QuantovaError::Auth(e)
+    }
+}
+
+impl From<HybridError> for QuantovaError {
+    fn from(e: HybridError) -> Self {
+        QuantovaError::Hybrid(e)
+    }
+}
+
+impl From<HybridKemError> for QuantovaError {
+    fn from(e: HybridKemError) -> Self {
+        QuantovaError::HybridKem(e)
+    }
+}
+
+impl From<KeyLoadError> for QuantovaError {
+    fn from(e: KeyLoadError) -> Self {
+        QuantovaError::KeyLoad(e)
+    }
+}
+
+impl From<EnvelopeError> for QuantovaError {
+    fn from(e: EnvelopeError) -> Self {
+        QuantovaError::Envelope(e)
+    }
+}
+
+impl From<ChannelError> for QuantovaError {
+    fn from(e: ChannelError) -> Self {
+        QuantovaError::Channel(e)
+    }
+}
+
+impl From<VssError> for QuantovaError {
+    fn from(e: VssError) -> Self {
+        QuantovaError::Vss(e)
+    }
+}
+
+impl From<ArmorError> for QuantovaError {
+    fn from(e: ArmorError) -> Self {
+        QuantovaError::Armor(e)
+    }
+}
+
+impl From<MultiSigError> for QuantovaError {
+    fn from(e: MultiSigError) -> Self {
+        QuantovaError::MultiSig(e)
+    }
+}
+
+impl From<SelfTestError> for QuantovaError {
+    fn from(e: SelfTestError) -> Self {
+        QuantovaError::SelfTest(e)
+    }
+}
+
+impl From<DilithiumSignError> for QuantovaError {
+    fn from(e: DilithiumSignError) -> Self {
+        QuantovaError::DilithiumSign(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_sealed() -> Result<(), QuantovaError> {
+        Err(Unspecified)?
+    }
+
+    fn load_share() -> Result<(), QuantovaError> {
+        Err(VssError::InsufficientShares { required: 3, provided: 2 })?
+    }
+
+    #[test]
+    fn module_errors_convert_with_question_mark() {
+        let aead = open_sealed().unwrap_err().with_algorithm("AES-256-GCM");
+        assert!(matches!(aead, QuantovaError::Aead { algorithm: Some("AES-256-GCM") }));
+        assert_eq!(aead.to_string(), "AES-256-GCM: encryption or decryption failed");
+        assert!(matches!(load_share(), Err(QuantovaError::Vss(_))));
+        let backend = QuantovaError::from(oqs::Error::Error).with_algorithm("ML-KEM-768");
+        assert!(backend.to_string().starts_with("ML-KEM-768: liboqs error"));
+    }
+
+    #[test]
+    fn backend_errors_keep_their_source() {
+        assert!(matches!(QuantovaError::from(oqs::Error::Error), QuantovaError::Backend { algorithm: None, .. }));
+    }
+
+    #[test]
+    fn pqcrypto_length_errors_convert() {
+        let error = QuantovaError::from(pqcrypto_traits::Error::BadLength { name: "PublicKey", actual: 3, expected: 1184 });
+        assert!(matches!(error, QuantovaError::Pqcrypto(pqcrypto_traits::Error::BadLength { actual: 3, .. })));
+    }
+
+    #[test]
+    fn keygen_errors_convert() {
+        let error = QuantovaError::from(KeygenError::RngUnavailable);
+        assert!(matches!(error, QuantovaError::Keygen { algorithm: None, source: KeygenError::RngUnavailable }));
+    }
+
+    #[test]
+    fn aead_errors_convert() {
+        assert!(matches!(QuantovaError::from(Unspecified), QuantovaError::Aead { algorithm: None }));
+    }
+
+    #[test]
+    fn io_errors_convert() {
+        assert!(matches!(QuantovaError::from(io::Error::from(io::ErrorKind::NotFound)), QuantovaError::Io(_)));
+    }
+
+    #[test]
+    fn config_errors_convert() {
+        let error = QuantovaError::from(ConfigError::InvalidField { field: "kem", reason: String::new() });
+        assert!(matches!(error, QuantovaError::Config(ConfigError::InvalidField { field: "kem", .. })));
+    }
+
+    #[test]
+    fn manifest_errors_convert() {
+        let error = QuantovaError::from(ManifestError::Malformed);
+        assert!(matches!(error, QuantovaError::Manifest(ManifestError::Malformed)));
+    }
+
+    #[test]
+    fn log_errors_convert() {
+        let error = QuantovaError::from(LogError::Truncated { chunk: 3 });
+        assert!(matches!(error, QuantovaError::Log(LogError::Truncated { chunk: 3 })));
+    }
+
+    #[test]
+    fn threshold_errors_convert() {
+        let error = QuantovaError::from(ThresholdError::InvalidSignature);
+        assert!(matches!(error, QuantovaError::Threshold(ThresholdError::InvalidSignature)));
+    }
+
+    #[test]
+    fn auth_errors_convert() {
+        assert!(matches!(QuantovaError::from(AuthError::BadPassphrase), QuantovaError::Auth(AuthError::BadPassphrase)));
+    }
+
+    #[test]
+    fn hybrid_errors_convert() {
+        let error = QuantovaError::from(HybridError::SelfCheckFailed);
+        assert!(matches!(error, QuantovaError::Hybrid(HybridError::SelfCheckFailed)));
+    }
+
+    #[test]
+    fn hybrid_kem_errors_convert() {
+        let error = QuantovaError::from(HybridKemError::Agreement);
+        assert!(matches!(error, QuantovaError::HybridKem(HybridKemError::Agreement)));
+    }
+
+    #[test]
+    fn key_load_errors_convert() {
+        let error = QuantovaError::from(KeyLoadError::KeyMismatch);
+        assert!(matches!(error, QuantovaError::KeyLoad(KeyLoadError::KeyMismatch)));
+    }
+
+    #[test]
+    fn envelope_errors_convert() {
+        let error = QuantovaError::from(EnvelopeError::Decryption);
+        assert!(matches!(error, QuantovaError::Envelope(EnvelopeError::Decryption)));
+    }
+
+    #[test]
+    fn channel_errors_convert() {
+        let error = QuantovaError::from(ChannelError::ConfirmationFailed);
+        assert!(matches!(error, QuantovaError::Channel(ChannelError::ConfirmationFailed)));
+    }
+
+    #[test]
+    fn vss_errors_convert() {
+        let error = QuantovaError::from(VssError::InsufficientShares { required: 3, provided: 2 });
+        assert!(matches!(error, QuantovaError::Vss(VssError::InsufficientShares { required: 3, provided: 2 })));
+    }
+
+    #[test]
+    fn armor_errors_convert() {
+        let error = QuantovaError::from(ArmorError::MissingBegin);
+        assert!(matches!(error, QuantovaError::Armor(ArmorError::MissingBegin)));
+    }
+
+    #[test]
+    fn multisig_errors_convert() {
+        assert!(matches!(QuantovaError::from(MultiSigError::Empty), QuantovaError::MultiSig(MultiSigError::Empty)));
+    }
+
+    #[test]
+    fn self_test_errors_convert() {
+        let error = QuantovaError::from(SelfTestError::NothingEnabled);
+        assert!(matches!(error, QuantovaError::SelfTest(SelfTestError::NothingEnabled)));
+    }
+
+    #[test]
+    fn dilithium_sign_errors_convert() {
+        let error = QuantovaError::from(DilithiumSignError::MalformedSignature { expected: 3293, actual: 12 });
+        assert!(matches!(error, QuantovaError::DilithiumSign(DilithiumSignError::MalformedSignature { actual: 12, .. })));
+    }
+}
@@ -0,0 +1,86 @@
+// Arithmetic in GF(256), the field Shamir sharing in the threshold module works over.
+// Elements are bytes; the field is the AES one, reduced by x^8 + x^4 + x^3 + x + 1 (0x11b).
+
+/// Low byte of the reduction polynomial 0x11b; the x^8 term is the carry out of the byte.
+const REDUCTION: u8 = 0x1b;
+
+/// Addition, which is also subtraction: XOR.
+pub fn add(a: u8, b: u8) -> u8 {
+    a ^ b
+}
+
+/// Multiplication modulo 0x11b. Runs in constant time for every pair of inputs, so share
+/// and secret bytes do not leak through timing.
+pub fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (REDUCTION & carry);
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplicative inverse, computed as a^254. Zero has no inverse and maps to zero.
+pub fn inv(a: u8) -> u8 {
+    // 254 = 0b1111_1110: square seven times, multiplying in every power after the first.
+    let mut power = a;
+    let mut result = 1;
+    for _ in 0..7 {
+        power = mul(power, power);
+        result = mul(result, power);
+    }
+    result
+}
+
+/// Evaluates the polynomial with `coefficients` (constant term first) at `x`.
+pub fn eval_poly(coefficients: &[u8], x: u8) -> u8 {
+    coefficients.iter().rev().fold(0, |acc, &c| add(mul(acc, x), c))
+}
+
+/// Evaluates at `x` the lowest-degree polynomial through `points`, given as `(x, y)` pairs
+/// with distinct x coordinates.
+pub fn lagrange_interpolate(points: &[(u8, u8)], x: u8) -> u8 {
+    points.iter().enumerate().fold(0, |sum, (j, &(x_j, y_j))| {
+        let basis = points
+            .iter()
+            .enumerate()
+            .filter(|&(m, _)| m != j)
+            .fold(1, |acc, (_, &(x_m, _))| mul(acc, mul(add(x, x_m), inv(add(x_j, x_m)))));
+        add(sum, mul(y_j, basis))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_nonzero_element_has_an_inverse() {
+        for a in 1..=255u8 {
+            assert_eq!(mul(a, inv(a)), 1, "inverse of {:#04x}", a);
+        }
+        assert_eq!(inv(0), 0);
+    }
+
+    #[test]
+    fn mul_matches_the_aes_field() {
+        // FIPS 197, section 4.2: {57} x {83} = {c1}
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+        assert_eq!(mul(0x57, 0x13), 0xfe);
+        for a in 0..=255u8 {
+            assert_eq!(mul(a, 1), a);
+            assert_eq!(mul(a, 0), 0);
+            assert_eq!(add(a, a), 0);
+        }
+    }
+
+    #[test]
+    fn interpolation_recovers_the_constant_term() {
+        let coefficients = [0x2a, 0x17, 0xe3];
+        let points: Vec<(u8, u8)> = [1, 5, 200].iter().map(|&x| (x, eval_poly(&coefficients, x))).collect();
+        assert_eq!(lagrange_interpolate(&points, 0), 0x2a);
+        assert_eq!(lagrange_interpolate(&points, 9), eval_poly(&coefficients, 9));
+    }
+}
@@ -1,20 +1,718 @@
+use crate::algorithm::{self, AlgorithmId, AlgorithmPreferences, NegotiatedSuite};
+use crate::authentication;
+use crate::classical::{ClassicalError, ClassicalScheme};
+use crate::config::Config;
+use crate::envelope::Aead;
+use crate::error::QuantovaError;
+use crate::keygen::{self, KeygenError};
+use crate::keys::{self, key_id};
+use crate::manifest;
+use crate::password_channel;
+use crate::secret::ProtectedSecretKey;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use oqs::kem::{self, Kem};
+use oqs::sig::{self, Sig};
+use rand::random;
+use ring::aead::{self, Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
+use ring::agreement::{self, EphemeralPrivateKey, UnparsedPublicKey, X25519};
+use ring::hkdf::{self, HKDF_SHA256};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use zeroize::Zeroizing;
 
-use oqs::sig::{self, Algorithm, Sig};
-use ring::signature::{Ed25519KeyPair, KeyPair, Signature, ED25519};
-use ring::rand::{SystemRandom, SecureRandom};
-use hex;
+/// Length of the session key [`hybrid_kem`] derives.
+pub const HYBRID_KEY_LEN: usize = 32;
+/// HKDF info label binding the combined key to this construction.
+const HYBRID_KEM_INFO: &[u8] = b"Quantova-hybrid-kem-v1";
 
-fn sign_classically(data: &[u8], private_key: &Ed25519KeyPair) -> Signature {
-    private_key.sign(data)
+/// File names of a saved hybrid key pair inside its directory.
+const CLASSIC_SEED_FILE: &str = "classic_seed.bin";
+const PQC_SECRET_KEY_FILE: &str = "pqc_secret.key";
+const PQC_PUBLIC_KEY_FILE: &str = "pqc_public.key";
+const ED25519_SEED_LEN: usize = 32;
+const X25519_KEY_LEN: usize = 32;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    Truncated,
+    LengthMismatch { declared: usize, actual: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Truncated => write!(f, "hybrid encoding is truncated"),
+            ParseError::LengthMismatch { declared, actual } => {
+                write!(f, "hybrid encoding declares {} bytes but is {} bytes long", declared, actual)
+            }
+        }
+    }
+}
+
+/// Frames the classical and PQC halves as `u32 len || classical || u32 len || pqc`,
+/// with big-endian lengths, so either half can be recovered without out-of-band sizes.
+fn frame_hybrid(classic: &[u8], pqc: &[u8]) -> Vec<u8> {
+    let mut framed = Vec::with_capacity(8 + classic.len() + pqc.len());
+    for part in [classic, pqc] {
+        framed.extend_from_slice(&(part.len() as u32).to_be_bytes());
+        framed.extend_from_slice(part);
+    }
+    framed
+}
+
+fn read_length(bytes: &[u8], at: usize) -> Result<usize, ParseError> {
+    let end = at.checked_add(4).ok_or(ParseError::Truncated)?;
+    let header = bytes.get(at..end).ok_or(ParseError::Truncated)?;
+    Ok(u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize)
+}
+
+/// Inverse of [`frame_hybrid`]. Rejects input whose declared lengths don't sum to its size.
+fn unframe_hybrid(bytes: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ParseError> {
+    let classic_len = read_length(bytes, 0)?;
+    let pqc_len = read_length(bytes, 4usize.checked_add(classic_len).ok_or(ParseError::Truncated)?)?;
+    let declared = classic_len
+        .checked_add(pqc_len)
+        .and_then(|len| len.checked_add(8))
+        .ok_or(ParseError::Truncated)?;
+    if declared != bytes.len() {
+        return Err(ParseError::LengthMismatch { declared, actual: bytes.len() });
+    }
+
+    let classic = bytes[4..4 + classic_len].to_vec();
+    let pqc = bytes[8 + classic_len..].to_vec();
+    Ok((classic, pqc))
+}
+
+#[derive(Debug)]
+pub enum HybridError {
+    Classical(ClassicalError),
+    Backend(oqs::Error),
+    Keygen(KeygenError),
+    Parse(ParseError),
+    /// The freshly made signature failed to verify under its own key.
+    SelfCheckFailed,
+}
+
+impl fmt::Display for HybridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HybridError::Classical(e) => write!(f, "{}", e),
+            HybridError::Backend(e) => write!(f, "signature backend error: {}", e),
+            HybridError::Keygen(e) => write!(f, "{}", e),
+            HybridError::Parse(e) => write!(f, "{}", e),
+            HybridError::SelfCheckFailed => write!(f, "the new hybrid signature does not verify under its own keys"),
+        }
+    }
+}
+
+impl From<ClassicalError> for HybridError {
+    fn from(e: ClassicalError) -> Self {
+        HybridError::Classical(e)
+    }
+}
+
+impl From<oqs::Error> for HybridError {
+    fn from(e: oqs::Error) -> Self {
+        HybridError::Backend(e)
+    }
+}
+
+impl From<KeygenError> for HybridError {
+    fn from(e: KeygenError) -> Self {
+        HybridError::Keygen(e)
+    }
+}
+
+impl From<ParseError> for HybridError {
+    fn from(e: ParseError) -> Self {
+        HybridError::Parse(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum KeyLoadError {
+    Io { path: PathBuf, source: io::Error },
+    /// A key file does not have the length its algorithm requires.
+    WrongLength { path: PathBuf, expected: usize, actual: usize },
+    /// The stored PQC secret key does not match the stored public key.
+    KeyMismatch,
+    Backend(oqs::Error),
+}
+
+impl fmt::Display for KeyLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyLoadError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            KeyLoadError::WrongLength { path, expected, actual } => {
+                write!(f, "{}: key is {} bytes, expected {}", path.display(), actual, expected)
+            }
+            KeyLoadError::KeyMismatch => write!(f, "stored PQC secret key does not match the stored public key"),
+            KeyLoadError::Backend(e) => write!(f, "signature backend error: {}", e),
+        }
+    }
+}
+
+impl From<oqs::Error> for KeyLoadError {
+    fn from(e: oqs::Error) -> Self {
+        KeyLoadError::Backend(e)
+    }
+}
+
+#[derive(Debug)]
+pub enum HybridKemError {
+    Backend(oqs::Error),
+    Keygen(KeygenError),
+    /// X25519 key generation failed or the peer's X25519 public key was rejected.
+    Agreement,
+    /// The KEM ciphertext does not have the size the algorithm expects.
+    MalformedCiphertext,
+    /// The two sides of the exchange derived different keys.
+    KeyMismatch,
+    /// The AEAD tag did not verify: wrong recipient keys or a modified ciphertext or nonce.
+    Decryption,
+}
+
+impl fmt::Display for HybridKemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HybridKemError::Backend(e) => write!(f, "KEM backend error: {}", e),
+            HybridKemError::Keygen(e) => write!(f, "{}", e),
+            HybridKemError::Agreement => write!(f, "X25519 key agreement failed"),
+            HybridKemError::MalformedCiphertext => write!(f, "malformed KEM ciphertext"),
+            HybridKemError::KeyMismatch => write!(f, "the two sides derived different keys"),
+            HybridKemError::Decryption => write!(f, "decryption failed: wrong key or modified ciphertext"),
+        }
+    }
+}
+
+impl From<oqs::Error> for HybridKemError {
+    fn from(e: oqs::Error) -> Self {
+        HybridKemError::Backend(e)
+    }
+}
+
+impl From<KeygenError> for HybridKemError {
+    fn from(e: KeygenError) -> Self {
+        HybridKemError::Keygen(e)
+    }
+}
+
+/// Concatenates a classical and a PQC public key into one length-prefixed hybrid key.
+pub fn combine_hybrid_public_key(classic: &[u8], pqc: &[u8]) -> Vec<u8> {
+    frame_hybrid(classic, pqc)
+}
+
+/// Splits a combined hybrid public key back into its classical and PQC components.
+pub fn split_hybrid_public_key(bytes: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ParseError> {
+    unframe_hybrid(bytes)
+}
+
+/// Frames a classical and a PQC signature into one length-prefixed hybrid signature.
+pub fn combine_hybrid_signature(classic: &[u8], pqc: &[u8]) -> Vec<u8> {
+    frame_hybrid(classic, pqc)
+}
+
+/// Splits a hybrid signature back into its classical and PQC signatures.
+pub fn parse_hybrid_signature(bytes: &[u8]) -> Result<(Vec<u8>, Vec<u8>), ParseError> {
+    unframe_hybrid(bytes)
+}
+
+/// Parses a hybrid signature and checks both halves. The signature is valid only if the
+/// classical and the PQC signature both verify; a malformed encoding is an error.
+pub fn verify_hybrid(
+    classical: ClassicalScheme,
+    sig: &Sig,
+    data: &[u8],
+    hybrid_signature: &[u8],
+    classic_public_key: &[u8],
+    pqc_public_key: &sig::PublicKey,
+) -> Result<bool, ParseError> {
+    let (classic, pqc) = parse_hybrid_signature(hybrid_signature)?;
+    let pqc_valid = sig
+        .signature_from_bytes(&pqc)
+        .is_some_and(|signature| sig.verify(data, signature, pqc_public_key).is_ok());
+    Ok(classical.verify(data, &classic, classic_public_key) && pqc_valid)
+}
+
+/// A hybrid signature together with the public keys needed to check it.
+#[derive(Debug, Clone)]
+pub struct HybridSignature {
+    pub classical: ClassicalScheme,
+    pub algorithm: sig::Algorithm,
+    pub classic_public_key: Vec<u8>,
+    pub pqc_public_key: sig::PublicKey,
+    /// Length-prefixed classical and PQC signatures, see [`combine_hybrid_signature`].
+    pub signature: Vec<u8>,
+}
+
+impl HybridSignature {
+    /// The combined public key, see [`combine_hybrid_public_key`].
+    pub fn public_key(&self) -> Vec<u8> {
+        combine_hybrid_public_key(&self.classic_public_key, self.pqc_public_key.as_ref())
+    }
+
+    /// Checks both halves of the signature over `data`; see [`verify_hybrid`].
+    pub fn verify(&self, data: &[u8]) -> Result<bool, HybridError> {
+        let sig = Sig::new(self.algorithm)?;
+        Ok(verify_hybrid(
+            self.classical,
+            &sig,
+            data,
+            &self.signature,
+            &self.classic_public_key,
+            &self.pqc_public_key,
+        )?)
+    }
+}
+
+/// Signs `data` with fresh Ed25519 and Dilithium2 key pairs.
+pub fn generate_hybrid_signature(data: &[u8]) -> Result<HybridSignature, HybridError> {
+    generate_hybrid_signature_with(data, ClassicalScheme::Ed25519, sig::Algorithm::Dilithium2)
+}
+
+/// Signs `data` with fresh `classical` and `algorithm` key pairs. The secret keys are
+/// dropped once both halves are made, so the result can only be verified.
+pub fn generate_hybrid_signature_with(
+    data: &[u8],
+    classical: ClassicalScheme,
+    algorithm: sig::Algorithm,
+) -> Result<HybridSignature, HybridError> {
+    let signer = classical.generate()?;
+    let classic_signature = signer.sign(data)?;
+    // ECDSA signing is randomized; check the output before it leaves
+    if !signer.verify(data, &classic_signature) {
+        return Err(HybridError::SelfCheckFailed);
+    }
+
+    let sig = Sig::new(algorithm)?;
+    let (pqc_public_key, pqc_secret_key) = keygen::generate_sig_keypair(&sig)?;
+    let pqc_signature = sig.sign(data, &pqc_secret_key)?;
+
+    Ok(HybridSignature {
+        classical,
+        algorithm,
+        classic_public_key: signer.public_key().to_vec(),
+        pqc_public_key,
+        signature: combine_hybrid_signature(&classic_signature, pqc_signature.as_ref()),
+    })
+}
+
+/// The responder's first message: an ephemeral X25519 public key and a PQ KEM public key.
+pub struct HybridOffer {
+    pub kem: kem::Algorithm,
+    pub x25519_public_key: Vec<u8>,
+    pub kem_public_key: kem::PublicKey,
+}
+
+/// The initiator's reply: its own ephemeral X25519 public key and the KEM ciphertext.
+pub struct HybridReply {
+    pub x25519_public_key: Vec<u8>,
+    pub kem_ciphertext: Vec<u8>,
+}
+
+/// The responder's secret state between sending the offer and receiving the reply.
+pub struct HybridResponder {
+    x25519_private_key: EphemeralPrivateKey,
+    kem: Kem,
+    kem_secret_key: kem::SecretKey,
+    x25519_public_key: Vec<u8>,
+    kem_public_key: kem::PublicKey,
+}
+
+/// Combines an X25519 and a PQ KEM shared secret into one 32-byte key with HKDF-SHA256. An
+/// attacker has to break both to learn it. Both public keys go into the HKDF salt and a fixed
+/// label into the `info`, so the key is bound to this construction and to the keys used;
+/// changing either public key changes the key.
+pub fn combine_secrets(
+    classic_ss: &[u8],
+    pqc_ss: &[u8],
+    classic_pk: &[u8],
+    pqc_pk: &[u8],
+) -> Zeroizing<[u8; HYBRID_KEY_LEN]> {
+    // The X25519 secret is always 32 bytes, so the concatenation is unambiguous.
+    let ikm = Zeroizing::new([classic_ss, pqc_ss].concat());
+    let prk = hkdf::Salt::new(HKDF_SHA256, &frame_hybrid(classic_pk, pqc_pk)).extract(&ikm);
+    let mut key = Zeroizing::new([0u8; HYBRID_KEY_LEN]);
+    prk.expand(&[HYBRID_KEM_INFO], HKDF_SHA256)
+        .and_then(|okm| okm.fill(key.as_mut_slice()))
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+/// Derives the exchange's session key. Both ephemeral X25519 public keys stand in for the
+/// classical public key, as X25519 secrets do not otherwise commit to them. The KEM
+/// ciphertext needs no binding: an IND-CCA KEM gives a different secret for any other
+/// ciphertext.
+fn exchange_key(
+    x25519_secret: &[u8],
+    kem_secret: &[u8],
+    responder_x25519: &[u8],
+    initiator_x25519: &[u8],
+    kem_public_key: &kem::PublicKey,
+) -> Zeroizing<[u8; HYBRID_KEY_LEN]> {
+    let x25519_public_keys = [responder_x25519, initiator_x25519].concat();
+    combine_secrets(x25519_secret, kem_secret, &x25519_public_keys, kem_public_key.as_ref())
+}
+
+fn x25519_keypair() -> Result<(EphemeralPrivateKey, Vec<u8>), HybridKemError> {
+    let private_key = EphemeralPrivateKey::generate(&X25519, &SystemRandom::new()).map_err(|_| HybridKemError::Agreement)?;
+    let public_key = private_key.compute_public_key().map_err(|_| HybridKemError::Agreement)?;
+    Ok((private_key, public_key.as_ref().to_vec()))
+}
+
+impl HybridResponder {
+    /// Generates the responder's ephemeral keys and returns the offer to send.
+    pub fn new(algorithm: kem::Algorithm) -> Result<(Self, HybridOffer), HybridKemError> {
+        let (x25519_private_key, x25519_public_key) = x25519_keypair()?;
+        let kem = Kem::new(algorithm)?;
+        let (kem_public_key, kem_secret_key) = keygen::generate_kem_keypair(&kem)?;
+        let responder = Self {
+            x25519_private_key,
+            kem,
+            kem_secret_key,
+            x25519_public_key: x25519_public_key.clone(),
+            kem_public_key: kem_public_key.clone(),
+        };
+        let offer = HybridOffer { kem: algorithm, x25519_public_key, kem_public_key };
+        Ok((responder, offer))
+    }
+
+    /// Completes the exchange with the initiator's reply. The ephemeral X25519 key is
+    /// consumed, so a responder can finish only one exchange.
+    pub fn finish(self, reply: &HybridReply) -> Result<Zeroizing<[u8; HYBRID_KEY_LEN]>, HybridKemError> {
+        let ciphertext = self
+            .kem
+            .ciphertext_from_bytes(&reply.kem_ciphertext)
+            .ok_or(HybridKemError::MalformedCiphertext)?;
+        let kem_secret = self.kem.decapsulate(&self.kem_secret_key, ciphertext)?;
+        let peer = UnparsedPublicKey::new(&X25519, &reply.x25519_public_key);
+        agreement::agree_ephemeral(self.x25519_private_key, &peer, HybridKemError::Agreement, |x25519_secret| {
+            Ok(exchange_key(
+                x25519_secret,
+                kem_secret.as_ref(),
+                &self.x25519_public_key,
+                &reply.x25519_public_key,
+                &self.kem_public_key,
+            ))
+        })
+    }
+}
+
+/// Answers a responder's offer: agrees on an X25519 secret, encapsulates a KEM secret to the
+/// offered key, and returns the reply to send along with the initiator's session key.
+pub fn hybrid_kem_initiate(offer: &HybridOffer) -> Result<(HybridReply, Zeroizing<[u8; HYBRID_KEY_LEN]>), HybridKemError> {
+    let (x25519_private_key, x25519_public_key) = x25519_keypair()?;
+    let kem = Kem::new(offer.kem)?;
+    let (ciphertext, kem_secret) = kem.encapsulate(&offer.kem_public_key)?;
+    let reply = HybridReply { x25519_public_key, kem_ciphertext: ciphertext.into_vec() };
+    let peer = UnparsedPublicKey::new(&X25519, &offer.x25519_public_key);
+    let key = agreement::agree_ephemeral(x25519_private_key, &peer, HybridKemError::Agreement, |x25519_secret| {
+        Ok(exchange_key(
+            x25519_secret,
+            kem_secret.as_ref(),
+            &offer.x25519_public_key,
+            &reply.x25519_public_key,
+            &offer.kem_public_key,
+        ))
+    })?;
+    Ok((reply, key))
+}
+
+/// Runs a full X25519 + `algorithm` exchange between a responder and an initiator and
+/// returns the 32-byte key both derived.
+pub fn hybrid_kem(algorithm: kem::Algorithm) -> Result<Zeroizing<[u8; HYBRID_KEY_LEN]>, HybridKemError> {
+    let (responder, offer) = HybridResponder::new(algorithm)?;
+    let (reply, initiator_key) = hybrid_kem_initiate(&offer)?;
+    let responder_key = responder.finish(&reply)?;
+    if initiator_key != responder_key {
+        return Err(HybridKemError::KeyMismatch);
+    }
+    Ok(initiator_key)
 }
 
-fn verify_classically(data: &[u8], signature: &Signature, public_key: &[u8]) -> bool {
-    ring::signature::UnparsedPublicKey::new(&ED25519, public_key)
-        .verify(data, signature.as_ref())
-        .is_ok()
+/// A recipient's long-term keys for [`hybrid_seal`]: a static X25519 key and a PQ KEM key
+/// pair. ring only offers ephemeral X25519 keys, so the static key uses curve25519-dalek.
+pub struct HybridRecipientKeys {
+    pub kem: kem::Algorithm,
+    pub x25519_public_key: [u8; X25519_KEY_LEN],
+    pub kem_public_key: kem::PublicKey,
+    x25519_secret_key: Zeroizing<[u8; X25519_KEY_LEN]>,
+    kem_secret_key: kem::SecretKey,
 }
 
-pub fn hybrid_keys() {
+impl HybridRecipientKeys {
+    pub fn generate(algorithm: kem::Algorithm) -> Result<Self, HybridKemError> {
+        let x25519_secret_key = Zeroizing::new(keygen::random_seed()?);
+        let x25519_public_key = MontgomeryPoint::mul_base_clamped(*x25519_secret_key).to_bytes();
+        let (kem_public_key, kem_secret_key) = keygen::generate_kem_keypair(&Kem::new(algorithm)?)?;
+        Ok(Self { kem: algorithm, x25519_public_key, kem_public_key, x25519_secret_key, kem_secret_key })
+    }
+}
+
+/// A payload encrypted by [`hybrid_seal`].
+#[derive(Debug, Clone)]
+pub struct HybridCiphertext {
+    pub pq_ciphertext: Vec<u8>,
+    pub classical_ephemeral_public_key: [u8; X25519_KEY_LEN],
+    pub nonce: [u8; aead::NONCE_LEN],
+    /// AES-256-GCM ciphertext followed by the tag.
+    pub aead_ciphertext: Vec<u8>,
+}
+
+impl HybridCiphertext {
+    /// Both key establishment messages are authenticated as associated data.
+    fn aad(&self) -> Vec<u8> {
+        frame_hybrid(&self.classical_ephemeral_public_key, &self.pq_ciphertext)
+    }
+}
+
+/// X25519 between a secret scalar and a public key. An all-zero result means the public key
+/// has low order and contributes nothing, so it is rejected.
+fn x25519(
+    secret_key: &[u8; X25519_KEY_LEN],
+    public_key: &[u8; X25519_KEY_LEN],
+) -> Result<Zeroizing<[u8; X25519_KEY_LEN]>, HybridKemError> {
+    let shared = Zeroizing::new(MontgomeryPoint(*public_key).mul_clamped(*secret_key).to_bytes());
+    if shared.iter().all(|&byte| byte == 0) {
+        return Err(HybridKemError::Agreement);
+    }
+    Ok(shared)
+}
+
+/// AES-256-GCM key for a hybrid ciphertext, from both shared secrets bound to the
+/// ephemeral and recipient X25519 keys and the recipient's KEM key.
+fn payload_key(
+    x25519_secret: &[u8],
+    kem_secret: &[u8],
+    ephemeral_public_key: &[u8],
+    recipient_x25519: &[u8],
+    recipient_kem: &kem::PublicKey,
+) -> LessSafeKey {
+    let x25519_public_keys = [ephemeral_public_key, recipient_x25519].concat();
+    let key = combine_secrets(x25519_secret, kem_secret, &x25519_public_keys, recipient_kem.as_ref());
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key.as_slice()).expect("AES-256-GCM takes 32-byte keys."))
+}
+
+/// Encrypts `plaintext` to a recipient's X25519 and KEM public keys: agrees on a secret with
+/// a fresh ephemeral X25519 key, encapsulates another to the KEM key, combines them with
+/// [`combine_secrets`] and encrypts with AES-256-GCM under a random nonce.
+pub fn hybrid_seal(
+    plaintext: &[u8],
+    algorithm: kem::Algorithm,
+    recipient_x25519: &[u8; X25519_KEY_LEN],
+    recipient_kem: &kem::PublicKey,
+) -> Result<HybridCiphertext, HybridKemError> {
+    let ephemeral_secret_key = Zeroizing::new(keygen::random_seed()?);
+    let classical_ephemeral_public_key = MontgomeryPoint::mul_base_clamped(*ephemeral_secret_key).to_bytes();
+    let x25519_secret = x25519(&ephemeral_secret_key, recipient_x25519)?;
+    let (pq_ciphertext, kem_secret) = Kem::new(algorithm)?.encapsulate(recipient_kem)?;
+
+    let mut sealed = HybridCiphertext {
+        pq_ciphertext: pq_ciphertext.into_vec(),
+        classical_ephemeral_public_key,
+        nonce: random(),
+        aead_ciphertext: plaintext.to_vec(),
+    };
+    let key = payload_key(
+        x25519_secret.as_slice(),
+        kem_secret.as_ref(),
+        &classical_ephemeral_public_key,
+        recipient_x25519,
+        recipient_kem,
+    );
+    let aad = sealed.aad();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(sealed.nonce), Aad::from(aad), &mut sealed.aead_ciphertext)
+        .expect("AEAD sealing only fails for oversized inputs");
+    Ok(sealed)
+}
+
+/// Decrypts a [`hybrid_seal`] ciphertext with the recipient's keys. Any change to the
+/// ciphertext, nonce or key establishment messages fails with [`HybridKemError::Decryption`].
+pub fn hybrid_open(sealed: &HybridCiphertext, recipient: &HybridRecipientKeys) -> Result<Vec<u8>, HybridKemError> {
+    let kem = Kem::new(recipient.kem)?;
+    let pq_ciphertext = kem
+        .ciphertext_from_bytes(&sealed.pq_ciphertext)
+        .ok_or(HybridKemError::MalformedCiphertext)?;
+    let kem_secret = kem.decapsulate(&recipient.kem_secret_key, pq_ciphertext)?;
+    let x25519_secret = x25519(&recipient.x25519_secret_key, &sealed.classical_ephemeral_public_key)?;
+    let key = payload_key(
+        x25519_secret.as_slice(),
+        kem_secret.as_ref(),
+        &sealed.classical_ephemeral_public_key,
+        &recipient.x25519_public_key,
+        &recipient.kem_public_key,
+    );
+    let mut plaintext = sealed.aead_ciphertext.clone();
+    let len = key
+        .open_in_place(Nonce::assume_unique_for_key(sealed.nonce), Aad::from(sealed.aad()), &mut plaintext)
+        .map_err(|_| HybridKemError::Decryption)?
+        .len();
+    plaintext.truncate(len);
+    Ok(plaintext)
+}
+
+/// Encrypts a payload to fresh recipient keys, opens it, then tries a modified ciphertext
+/// and a modified nonce.
+fn hybrid_encryption_demo(algorithm: kem::Algorithm) -> Result<(), HybridKemError> {
+    let recipient = HybridRecipientKeys::generate(algorithm)?;
+    let payload = b"transfer 42 QTV to node-b";
+    let sealed = hybrid_seal(payload, algorithm, &recipient.x25519_public_key, &recipient.kem_public_key)?;
+    let opened = hybrid_open(&sealed, &recipient)?;
+    println!(
+        "   - Sealed {} bytes into {} bytes of AES-256-GCM, opened: {}",
+        payload.len(),
+        sealed.aead_ciphertext.len(),
+        if opened == payload { "✅ matches" } else { "❌ differs" }
+    );
+
+    let mut tampered_ciphertext = sealed.clone();
+    tampered_ciphertext.aead_ciphertext[0] ^= 0x01;
+    let mut tampered_nonce = sealed.clone();
+    tampered_nonce.nonce[0] ^= 0x01;
+    for (what, tampered) in [("ciphertext", tampered_ciphertext), ("nonce", tampered_nonce)] {
+        match hybrid_open(&tampered, &recipient) {
+            Ok(_) => println!("   - Modified {}: ❌ decrypted", what),
+            Err(e) => println!("   - Modified {}: ✅ refused ({})", what, e),
+        }
+    }
+    Ok(())
+}
+
+/// Runs the hybrid exchange, then again with a modified reply, and shows how the combined
+/// key moves when either public key changes.
+fn hybrid_kem_demo(algorithm: kem::Algorithm) -> Result<(), HybridKemError> {
+    let key = hybrid_kem(algorithm)?;
+    println!("   - Both sides derived session key {}", keys::fingerprint(key.as_slice()));
+
+    let (responder, offer) = HybridResponder::new(algorithm)?;
+    let (mut reply, initiator_key) = hybrid_kem_initiate(&offer)?;
+    reply.kem_ciphertext[0] ^= 0x01;
+    let tampered_key = responder.finish(&reply)?;
+    println!(
+        "   - Modified reply: responder key {} vs {}, {}",
+        keys::fingerprint(tampered_key.as_slice()),
+        keys::fingerprint(initiator_key.as_slice()),
+        if initiator_key != tampered_key { "✅ keys differ" } else { "❌ keys match" }
+    );
+
+    // Same shared secrets, one public key changed at a time
+    let (classic_ss, pqc_ss) = (random::<[u8; 32]>(), random::<[u8; 32]>());
+    let classic_pk = offer.x25519_public_key.clone();
+    let mut pqc_pk = offer.kem_public_key.as_ref().to_vec();
+    let base = combine_secrets(&classic_ss, &pqc_ss, &classic_pk, &pqc_pk);
+    let mut other_classic_pk = classic_pk.clone();
+    other_classic_pk[0] ^= 0x01;
+    let classic_changed = combine_secrets(&classic_ss, &pqc_ss, &other_classic_pk, &pqc_pk);
+    pqc_pk[0] ^= 0x01;
+    let pqc_changed = combine_secrets(&classic_ss, &pqc_ss, &classic_pk, &pqc_pk);
+    println!(
+        "   - Altered public keys: {} / classical {} / PQC {}",
+        keys::fingerprint(base.as_slice()),
+        keys::fingerprint(classic_changed.as_slice()),
+        keys::fingerprint(pqc_changed.as_slice())
+    );
+    Ok(())
+}
+
+/// A hybrid key pair loaded from disk by [`load_hybrid_keypair`].
+pub struct HybridKeypair {
+    pub classic: Ed25519KeyPair,
+    pub pqc_public_key: sig::PublicKey,
+    pub pqc_secret_key: ProtectedSecretKey,
+}
+
+/// Saves an Ed25519 seed and a PQC key pair to `dir` as three files, creating `dir` if needed.
+/// The seed and secret key are written unencrypted, but on Unix only the owner can read them.
+pub fn save_hybrid_keypair(
+    dir: &Path,
+    classic_seed: &[u8; ED25519_SEED_LEN],
+    pqc_sk: &sig::SecretKey,
+    pqc_pk: &sig::PublicKey,
+) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    authentication::write_private_file(&dir.join(CLASSIC_SEED_FILE), classic_seed)?;
+    authentication::write_private_file(&dir.join(PQC_SECRET_KEY_FILE), pqc_sk.as_ref())?;
+    fs::write(dir.join(PQC_PUBLIC_KEY_FILE), pqc_pk.as_ref())
+}
+
+fn read_key_file(path: PathBuf, expected: usize) -> Result<Zeroizing<Vec<u8>>, KeyLoadError> {
+    let bytes = Zeroizing::new(fs::read(&path).map_err(|source| KeyLoadError::Io { path: path.clone(), source })?);
+    if bytes.len() != expected {
+        return Err(KeyLoadError::WrongLength { path, expected, actual: bytes.len() });
+    }
+    Ok(bytes)
+}
+
+/// Loads a key pair saved by [`save_hybrid_keypair`] for the PQC `algorithm`. Missing or
+/// wrong-length files are reported with their path, and the PQC secret key must match the
+/// public key.
+pub fn load_hybrid_keypair(dir: &Path, algorithm: sig::Algorithm) -> Result<HybridKeypair, KeyLoadError> {
+    let seed = read_key_file(dir.join(CLASSIC_SEED_FILE), ED25519_SEED_LEN)?;
+    let classic = Ed25519KeyPair::from_seed_unchecked(&seed).expect("Any 32-byte seed is an Ed25519 key.");
+
+    let sig = Sig::new(algorithm)?;
+    let secret_key = read_key_file(dir.join(PQC_SECRET_KEY_FILE), sig.length_secret_key())?;
+    let public_key = read_key_file(dir.join(PQC_PUBLIC_KEY_FILE), sig.length_public_key())?;
+    let pqc_secret_key = sig
+        .secret_key_from_bytes(&secret_key)
+        .expect("Length was checked against the algorithm.")
+        .to_owned();
+    let pqc_public_key = sig
+        .public_key_from_bytes(&public_key)
+        .expect("Length was checked against the algorithm.")
+        .to_owned();
+    if !authentication::validate_keypair(&sig, &pqc_public_key, &pqc_secret_key) {
+        return Err(KeyLoadError::KeyMismatch);
+    }
+    Ok(HybridKeypair { classic, pqc_public_key, pqc_secret_key: ProtectedSecretKey::new(pqc_secret_key) })
+}
+
+/// Saves a fresh hybrid key pair, reloads it and signs with the reloaded keys, then shows
+/// that a truncated seed file is reported instead of loaded.
+fn persist_keypair_demo(sig: &Sig, data: &[u8]) -> Result<(), QuantovaError> {
+    let dir = &std::env::temp_dir().join(format!("quantova-hybrid-keypair-demo-{}", std::process::id()));
+    let seed = Zeroizing::new(keygen::random_seed()?);
+    let (pqc_public_key, pqc_secret_key) = keygen::generate_sig_keypair(sig)?;
+    save_hybrid_keypair(dir, &seed, &pqc_secret_key, &pqc_public_key)?;
+
+    let reloaded = load_hybrid_keypair(dir, sig.algorithm())?;
+    let classic_public_key = Ed25519KeyPair::from_seed_unchecked(seed.as_slice())
+        .expect("Any 32-byte seed is an Ed25519 key.")
+        .public_key()
+        .as_ref()
+        .to_vec();
+    let classic_signature = reloaded.classic.sign(data);
+    let pqc_signature = sig.sign(data, reloaded.pqc_secret_key.expose())?;
+    let hybrid_signature = combine_hybrid_signature(classic_signature.as_ref(), pqc_signature.as_ref());
+    let valid = verify_hybrid(ClassicalScheme::Ed25519, sig, data, &hybrid_signature, &classic_public_key, &pqc_public_key)
+        .map_err(HybridError::from)?;
+    println!(
+        "   - Reloaded key pair signs: {}, public key {}",
+        if valid { "✅ Valid" } else { "❌ Invalid" },
+        if reloaded.pqc_public_key == pqc_public_key { "unchanged" } else { "differs!" }
+    );
+
+    fs::write(dir.join(CLASSIC_SEED_FILE), &seed[..ED25519_SEED_LEN / 2])?;
+    match load_hybrid_keypair(dir, sig.algorithm()) {
+        Ok(_) => println!("   - ❌ Truncated seed loaded"),
+        Err(e) => println!("   - Truncated seed rejected: {}", e),
+    }
+
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+pub fn hybrid_keys(config: &Config) -> Result<(), QuantovaError> {
+    hybrid_keys_with(ClassicalScheme::Ed25519, config)
+}
+
+/// Runs the hybrid demo with `classical` as the classical half of the signature and the
+/// signature algorithm and KEM from the loaded config as the PQC half.
+pub fn hybrid_keys_with(classical: ClassicalScheme, config: &Config) -> Result<(), QuantovaError> {
     // Simulated data to sign
     let data = b"hybrid cryptography message!";
     println!("\n=============================");
@@ -22,48 +720,291 @@ pub fn hybrid_keys() {
     println!("=============================");
     println!(" Data to be signed: {}", String::from_utf8_lossy(data));
 
-    // Generate a classical Ed25519 key pair
-    let rng = SystemRandom::new();
-    let private_key_bytes = {
-        let mut key = [0u8; 32];
-        rng.fill(&mut key).unwrap();
-        key
+    // Agree on the PQC algorithms with the peer instead of hardcoding them; both sides
+    // put the configured algorithms first
+    let signature = AlgorithmId::from_name(&config.signature).expect("Config::load checks the signature name.");
+    let kem = AlgorithmId::from_name(&config.kem).expect("Config::load checks the KEM name.");
+    let client = AlgorithmPreferences {
+        signatures: vec![signature, AlgorithmId::MlDsa65, AlgorithmId::Dilithium2],
+        kems: vec![kem, AlgorithmId::MlKem1024, AlgorithmId::MlKem768],
+    };
+    let server = AlgorithmPreferences {
+        signatures: vec![signature, AlgorithmId::Dilithium2, AlgorithmId::Falcon512],
+        kems: vec![kem, AlgorithmId::MlKem768],
     };
-    let private_key = Ed25519KeyPair::from_seed_unchecked(&private_key_bytes).unwrap();
-    let classic_signature = sign_classically(data, &private_key);
-    let classic_public_key = private_key.public_key().as_ref().to_vec();
-    
-    println!("\n Classical Ed25519 Key Pair:");
-    println!("   - Public Key: {}", hex::encode(&classic_public_key));
-    println!("   - Signature : {}", hex::encode(classic_signature.as_ref()));
-
-    // Generate a PQC signature (Dilithium2)
-    let sig = Sig::new(Algorithm::Dilithium2).unwrap();
-    let (pqc_public_key, pqc_private_key) = sig.keypair().unwrap();
-    let pqc_signature = sig.sign(data, &pqc_private_key).unwrap();
-
-    println!("\n PQC Dilithium2 Key Pair:");
-    println!("   - Public Key: {}", hex::encode(&pqc_public_key));
-    println!("   - Signature : {}", hex::encode(&pqc_signature));
-
-    // Hybrid signature (simple concatenation for demonstration)
-    let hybrid_signature = [classic_signature.as_ref(), pqc_signature.as_ref()].concat();
+    let suite = algorithm::negotiate_suite(&client, &server).expect("Both sides list the configured algorithms.");
+    let pqc_name = suite.signature.liboqs_name();
+    println!("\n🤝 Negotiated {} signatures and {} key exchange", pqc_name, suite.kem.liboqs_name());
+    for id in [suite.signature, suite.kem] {
+        let info = algorithm::algorithm_info(id)?;
+        println!(
+            "   - {}: {:?} {:?}, NIST level {}, {}, public key {} bytes",
+            id.liboqs_name(),
+            info.family,
+            info.kind,
+            info.nist_level,
+            info.status,
+            info.public_key_len
+        );
+    }
+
+    // Sign with a fresh classical key pair and one for the negotiated PQC algorithm
+    let sig = Sig::new(suite.signature.to_sig().expect("Negotiated signatures are signature algorithms."))?;
+    let hybrid = generate_hybrid_signature_with(data, classical, sig.algorithm())?;
+
+    println!("\n Classical {} Public Key: {}", classical.name(), hex::encode(&hybrid.classic_public_key));
+    println!(" PQC {} Public Key: {}", pqc_name, hex::encode(&hybrid.pqc_public_key));
+
+    // Combined hybrid public key, split back into its components on the receiving side
+    let hybrid_public_key = hybrid.public_key();
+    println!("\n🔗 Hybrid Public Key: {} bytes", hybrid_public_key.len());
+    match split_hybrid_public_key(&hybrid_public_key) {
+        Ok((classic_part, pqc_part)) => {
+            println!("   - Classical part: {}", hex::encode(&classic_part));
+            println!("   - PQC part      : {} bytes", pqc_part.len());
+        }
+        Err(e) => println!("❌ Failed to split hybrid public key: {}", e),
+    }
+
+    // Hybrid signature, framed so the receiver can split it without knowing either length
     println!("\n🔗 Hybrid Signature:");
-    println!("   - Signature: {}", hex::encode(&hybrid_signature));
+    println!("   - Signature: {}", hex::encode(&hybrid.signature));
 
-    // Verification
-    let classic_valid = verify_classically(data, &classic_signature, &classic_public_key);
-    let pqc_valid = sig.verify(data, &pqc_signature, &pqc_public_key).is_ok();
+    // Verification, starting from the framed bytes alone
+    let (classic_part, pqc_part) = parse_hybrid_signature(&hybrid.signature).map_err(HybridError::from)?;
+    let classic_valid = classical.verify(data, &classic_part, &hybrid.classic_public_key);
+    let pqc_valid = sig
+        .signature_from_bytes(&pqc_part)
+        .is_some_and(|signature| sig.verify(data, signature, &hybrid.pqc_public_key).is_ok());
+    let hybrid_valid = hybrid.verify(data)?;
+    let truncated = &hybrid.signature[..hybrid.signature.len() - 1];
 
     println!("\n=============================");
     println!(" Verification Results:");
     println!("=============================");
-    println!(" Classic Ed25519 Signature Valid: {}", if classic_valid { "✅ Valid" } else { "❌ Invalid" });
-    println!(" PQC Dilithium2 Signature Valid: {}", if pqc_valid { "✅ Valid" } else { "❌ Invalid" });
+    println!(" Classic {} Signature Valid: {}", classical.name(), if classic_valid { "✅ Valid" } else { "❌ Invalid" });
+    println!(" PQC {} Signature Valid: {}", pqc_name, if pqc_valid { "✅ Valid" } else { "❌ Invalid" });
+    println!(" Hybrid Signature Valid: {}", if hybrid_valid { "✅ Valid" } else { "❌ Invalid" });
+    match parse_hybrid_signature(truncated) {
+        Ok(_) => println!(" Truncated Hybrid Signature: ❌ Accepted"),
+        Err(e) => println!(" Truncated Hybrid Signature: ✅ Rejected ({})", e),
+    }
 
-    if classic_valid && pqc_valid {
+    if hybrid_valid {
         println!("\n All signatures are valid! Hybrid signature is secure!");
     } else {
         println!("\n❌ Signature verification failed!");
     }
+
+    // The classical half is pluggable; each backend pairs with the same PQC algorithm
+    println!("\n Classical backends:");
+    let valid = generate_hybrid_signature(data)?.verify(data)?;
+    println!("   - Default (Ed25519 + Dilithium2): {}", if valid { "✅ Valid" } else { "❌ Invalid" });
+    for scheme in ClassicalScheme::ALL {
+        let valid = generate_hybrid_signature_with(data, scheme, sig.algorithm())?.verify(data)?;
+        println!("   - {} + {}: {}", scheme.name(), pqc_name, if valid { "✅ Valid" } else { "❌ Invalid" });
+    }
+
+    // Save the hybrid key pair, then sign again with the copy loaded from disk
+    println!("\n Persisting a hybrid key pair...");
+    persist_keypair_demo(&sig, data)?;
+
+    // Sign a directory and encrypt the manifest to the peer with the negotiated KEM
+    println!("\n Sealing a directory manifest with {} and {}...", pqc_name, suite.kem.liboqs_name());
+    seal_directory_demo(&suite, &sig)?;
+
+    // Derive a session key from the negotiated KEM and a shared password
+    println!("\n Password-authenticated channel over {}...", suite.kem.liboqs_name());
+    let kem_algorithm = suite.kem.to_kem().expect("Negotiated KEMs are KEM algorithms.");
+    password_channel::password_channel_demo(kem_algorithm)?;
+
+    // Establish a session key that stays secret unless both X25519 and the KEM are broken
+    println!("\n Hybrid key exchange: X25519 + {}...", suite.kem.liboqs_name());
+    hybrid_kem_demo(kem_algorithm)?;
+
+    // Encrypt a payload to the peer's long-term X25519 and KEM keys
+    println!("\n Hybrid encryption: X25519 + {} + AES-256-GCM...", suite.kem.liboqs_name());
+    hybrid_encryption_demo(kem_algorithm)?;
+    Ok(())
+}
+
+/// Signs a small directory in the clear and sealed, unseals it intact, then again after
+/// changing a file.
+fn seal_directory_demo(suite: &NegotiatedSuite, sig: &Sig) -> Result<(), QuantovaError> {
+    let (signer_public_key, signer_secret_key) = keygen::generate_sig_keypair(sig)?;
+    let dir = &std::env::temp_dir().join(format!("quantova-sealed-manifest-demo-{}", std::process::id()));
+    fs::create_dir_all(dir.join("nested"))?;
+    fs::write(dir.join("ledger.txt"), "block 1\n")?;
+    fs::write(dir.join("nested").join("peers.txt"), "node-a\nnode-b\n")?;
+
+    let plain = manifest::sign_directory(dir, &signer_secret_key, sig.algorithm())?;
+    let plain_diffs = manifest::verify_directory(&plain, &signer_public_key, dir)?;
+    println!(
+        "   - Signed {} files in the clear: {}",
+        plain.entries.len(),
+        if plain_diffs.is_empty() { "✅ tree matches" } else { "❌ tree differs" }
+    );
+
+    let kem_algorithm = suite.kem.to_kem().expect("Negotiated KEMs are KEM algorithms.");
+    let (recipient_public_key, recipient_secret_key) = keygen::generate_kem_keypair(&Kem::new(kem_algorithm)?)?;
+    let sealed =
+        manifest::seal_directory(dir, &recipient_public_key, kem_algorithm, Aead::Auto, &signer_secret_key, sig.algorithm())?;
+    println!(
+        "   - Sealed manifest: {} encrypted bytes, {} ciphertext (fastest on this CPU)",
+        sealed.envelope.encrypted_len(),
+        sealed.envelope.aead.name()
+    );
+    let recipient_kid = key_id(recipient_public_key.as_ref());
+    println!(
+        "   - Addressed to key ID {} ({})",
+        hex::encode(sealed.envelope.recipient_key_id),
+        if sealed.envelope.recipient_key_id == recipient_kid { "the recipient" } else { "❌ not the recipient" }
+    );
+
+    let intact = manifest::unseal_directory(&sealed, &recipient_public_key, &recipient_secret_key, &signer_public_key, dir)?;
+    println!("   - Unsealed and verified: {}", if intact.is_empty() { "✅ tree matches" } else { "❌ tree differs" });
+
+    // Drift the tree in all three ways at once.
+    fs::write(dir.join("ledger.txt"), "block 1\nblock 2\n")?;
+    fs::write(dir.join("nested").join("intruder.txt"), "not signed\n")?;
+    fs::remove_file(dir.join("nested").join("peers.txt"))?;
+    let diffs = manifest::unseal_directory(&sealed, &recipient_public_key, &recipient_secret_key, &signer_public_key, dir)?;
+    println!("   - After drifting the tree:");
+    for diff in &diffs {
+        println!("     {}", diff);
+    }
+
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = b"hybrid cryptography message!";
+
+    #[test]
+    fn hybrid_signature_verifies_only_its_own_data() {
+        let hybrid = generate_hybrid_signature(DATA).unwrap();
+        assert!(hybrid.verify(DATA).unwrap());
+        assert!(!hybrid.verify(b"another message").unwrap());
+
+        let mut forged = hybrid.clone();
+        let last = forged.signature.len() - 1;
+        forged.signature[last] ^= 0x01;
+        assert!(!forged.verify(DATA).unwrap());
+    }
+
+    #[test]
+    fn saved_keypair_reloads_and_a_truncated_seed_is_rejected() {
+        let dir = std::env::temp_dir().join(format!("quantova-hybrid-test-{}-reload", std::process::id()));
+        let sig = Sig::new(sig::Algorithm::Dilithium2).unwrap();
+        let seed = keygen::random_seed().unwrap();
+        let (public_key, secret_key) = keygen::generate_sig_keypair(&sig).unwrap();
+        save_hybrid_keypair(&dir, &seed, &secret_key, &public_key).unwrap();
+        #[cfg(unix)]
+        for file in [CLASSIC_SEED_FILE, PQC_SECRET_KEY_FILE] {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(dir.join(file)).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let reloaded = load_hybrid_keypair(&dir, sig.algorithm()).unwrap();
+        assert_eq!(reloaded.pqc_public_key, public_key);
+        let expected = Ed25519KeyPair::from_seed_unchecked(&seed).unwrap();
+        assert_eq!(reloaded.classic.public_key().as_ref(), expected.public_key().as_ref());
+
+        fs::write(dir.join(CLASSIC_SEED_FILE), &seed[..ED25519_SEED_LEN / 2]).unwrap();
+        assert!(matches!(
+            load_hybrid_keypair(&dir, sig.algorithm()),
+            Err(KeyLoadError::WrongLength { expected: ED25519_SEED_LEN, .. })
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sealed_payload_opens_and_refuses_tampering() {
+        let algorithm = kem::Algorithm::MlKem768;
+        let recipient = HybridRecipientKeys::generate(algorithm).unwrap();
+        let sealed = hybrid_seal(DATA, algorithm, &recipient.x25519_public_key, &recipient.kem_public_key).unwrap();
+        assert_eq!(hybrid_open(&sealed, &recipient).unwrap(), DATA);
+
+        let mut tampered = sealed.clone();
+        tampered.aead_ciphertext[0] ^= 0x01;
+        assert!(matches!(hybrid_open(&tampered, &recipient), Err(HybridKemError::Decryption)));
+        let mut tampered = sealed.clone();
+        tampered.nonce[0] ^= 0x01;
+        assert!(matches!(hybrid_open(&tampered, &recipient), Err(HybridKemError::Decryption)));
+    }
+
+    #[test]
+    fn a_modified_reply_gives_the_responder_a_different_key() {
+        let (responder, offer) = HybridResponder::new(kem::Algorithm::MlKem768).unwrap();
+        let (mut reply, initiator_key) = hybrid_kem_initiate(&offer).unwrap();
+        reply.kem_ciphertext[0] ^= 0x01;
+        assert_ne!(responder.finish(&reply).unwrap(), initiator_key);
+    }
+
+    #[test]
+    fn both_sides_derive_the_same_32_byte_key() {
+        for algorithm in [kem::Algorithm::MlKem512, kem::Algorithm::MlKem768, kem::Algorithm::MlKem1024] {
+            let (responder, offer) = HybridResponder::new(algorithm).unwrap();
+            let (reply, initiator_key) = hybrid_kem_initiate(&offer).unwrap();
+            let responder_key = responder.finish(&reply).unwrap();
+            assert_eq!(responder_key.len(), HYBRID_KEY_LEN);
+            assert_eq!(responder_key, initiator_key);
+            assert_ne!(*initiator_key, [0u8; HYBRID_KEY_LEN]);
+            // Each exchange uses fresh ephemeral keys
+            assert_ne!(hybrid_kem(algorithm).unwrap(), initiator_key);
+        }
+    }
+
+    #[test]
+    fn hybrid_signature_splits_back_and_truncation_is_an_error() {
+        let framed = combine_hybrid_signature(b"classic", b"post-quantum");
+        assert_eq!(parse_hybrid_signature(&framed).unwrap(), (b"classic".to_vec(), b"post-quantum".to_vec()));
+
+        assert_eq!(parse_hybrid_signature(&[]), Err(ParseError::Truncated));
+        assert_eq!(parse_hybrid_signature(&framed[..6]), Err(ParseError::Truncated));
+        assert_eq!(
+            parse_hybrid_signature(&framed[..framed.len() - 1]),
+            Err(ParseError::LengthMismatch { declared: framed.len(), actual: framed.len() - 1 })
+        );
+        // A length prefix larger than the buffer must not overflow or panic
+        assert_eq!(parse_hybrid_signature(&[0xff; 8]), Err(ParseError::Truncated));
+    }
+
+    #[test]
+    fn every_classical_backend_pairs_with_the_pqc_half() {
+        for scheme in ClassicalScheme::ALL {
+            let hybrid = generate_hybrid_signature_with(DATA, scheme, sig::Algorithm::Dilithium2).unwrap();
+            assert_eq!(hybrid.classical, scheme);
+            assert!(hybrid.verify(DATA).unwrap(), "{}", scheme.name());
+            assert!(!hybrid.verify(b"another message").unwrap(), "{}", scheme.name());
+
+            let (classic_part, _) = parse_hybrid_signature(&hybrid.signature).unwrap();
+            assert!(scheme.verify(DATA, &classic_part, &hybrid.classic_public_key));
+            // The classical half only verifies under the scheme that made it
+            for other in ClassicalScheme::ALL.into_iter().filter(|other| *other != scheme) {
+                assert!(!other.verify(DATA, &classic_part, &hybrid.classic_public_key));
+            }
+        }
+    }
+
+    #[test]
+    fn changing_either_public_key_changes_the_combined_key() {
+        let (classic_ss, pqc_ss) = ([1u8; 32], [2u8; 32]);
+        let (classic_pk, pqc_pk) = ([3u8; 32], vec![4u8; 1184]);
+        let base = combine_secrets(&classic_ss, &pqc_ss, &classic_pk, &pqc_pk);
+        assert_eq!(combine_secrets(&classic_ss, &pqc_ss, &classic_pk, &pqc_pk), base);
+
+        let mut other_classic_pk = classic_pk;
+        other_classic_pk[0] ^= 0x01;
+        let mut other_pqc_pk = pqc_pk.clone();
+        other_pqc_pk[0] ^= 0x01;
+        assert_ne!(combine_secrets(&classic_ss, &pqc_ss, &other_classic_pk, &pqc_pk), base);
+        assert_ne!(combine_secrets(&classic_ss, &pqc_ss, &classic_pk, &other_pqc_pk), base);
+        // Moving bytes between the two keys is not the same pair of keys either
+        assert_ne!(combine_secrets(&classic_ss, &pqc_ss, &classic_pk[..31], &[&classic_pk[31..], &pqc_pk[..]].concat()), base);
+    }
 }
@@ -0,0 +1,141 @@
+use oqs::sig::{self, Sig, Signature};
+use serde_json::Value;
+
+/// Formats a number the way ECMAScript's `Number.prototype.toString` does, as RFC 8785
+/// requires. Every JSON number is treated as an IEEE-754 double, so integers beyond 2^53
+/// lose precision exactly as they would in any other JCS implementation.
+fn canonical_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    // `{:e}` yields the shortest round-trip digits, e.g. "-1.2345e-7".
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').expect("Scientific notation always has an exponent.");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().expect("Exponent is always an integer.");
+
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+    let formatted = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat((-n) as usize), digits)
+    } else {
+        let fraction = if k > 1 { format!(".{}", &digits[1..]) } else { String::new() };
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        format!("{}{}e{}{}", &digits[..1], fraction, sign, (n - 1).abs())
+    };
+
+    if value < 0.0 { format!("-{}", formatted) } else { formatted }
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => {
+            // serde_json's escaping of strings already matches RFC 8785.
+            out.push_str(&serde_json::to_string(value).expect("Serializing a JSON value cannot fail."));
+        }
+        Value::Number(number) => {
+            out.push_str(&canonical_number(number.as_f64().expect("JSON numbers are finite.")));
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            // Members are ordered by the UTF-16 code units of their names.
+            let mut members: Vec<(&String, &Value)> = map.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, item)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(&Value::String(key.clone()), out);
+                out.push(':');
+                write_canonical(item, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Serializes `value` using the JSON Canonicalization Scheme (RFC 8785): sorted object
+/// members, no insignificant whitespace and ECMAScript number formatting.
+pub fn canonicalize(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+/// Signs the canonical form of a JSON document, so any semantically equal document
+/// (e.g. with reordered keys) verifies against the same signature.
+pub fn sign_json(value: &Value, sig: &Sig, secret_key: &sig::SecretKey) -> oqs::Result<Signature> {
+    sig.sign(canonicalize(value).as_bytes(), secret_key)
+}
+
+/// Re-canonicalizes a JSON document and verifies `signature` over it.
+pub fn verify_json(value: &Value, signature: &Signature, sig: &Sig, public_key: &sig::PublicKey) -> bool {
+    sig.verify(canonicalize(value).as_bytes(), signature, public_key).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oqs::sig::Algorithm;
+    use serde_json::json;
+
+    #[test]
+    fn numbers_follow_ecmascript_formatting() {
+        // Values from RFC 8785, appendix B
+        for (value, expected) in [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (4.5, "4.5"),
+            (0.002, "0.002"),
+            (1e-7, "1e-7"),
+            (1e21, "1e+21"),
+            (1e30, "1e+30"),
+            (1e-27, "1e-27"),
+            (-1.5e-7, "-1.5e-7"),
+            (9007199254740992.0, "9007199254740992"),
+            (295147905179352830000.0, "295147905179352830000"),
+            (333333333.3333333, "333333333.3333333"),
+        ] {
+            assert_eq!(canonical_number(value), expected, "{:e}", value);
+        }
+    }
+
+    #[test]
+    fn members_are_sorted_by_utf16_and_whitespace_dropped() {
+        let document: Value = serde_json::from_str(
+            r#"{ "\u20ac": "Euro", "\r": "CR", "\ufb33": "Hebrew", "1": "One", "\ud83d\ude00": "Smiley", "\u0080": "Control", "\u00f6": "Umlaut" }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            canonicalize(&document),
+            "{\"\\r\":\"CR\",\"1\":\"One\",\"\u{80}\":\"Control\",\"\u{f6}\":\"Umlaut\",\"\u{20ac}\":\"Euro\",\"\u{1f600}\":\"Smiley\",\"\u{fb33}\":\"Hebrew\"}"
+        );
+        assert_eq!(canonicalize(&json!({ "b": [1, { "d": null, "c": true }], "a": "x" })), r#"{"a":"x","b":[1,{"c":true,"d":null}]}"#);
+    }
+
+    #[test]
+    fn reordered_document_verifies_and_changed_one_does_not() {
+        let sig = Sig::new(Algorithm::Dilithium2).unwrap();
+        let (public_key, secret_key) = sig.keypair().unwrap();
+        let signature = sign_json(&json!({ "amount": 10, "to": "node-a" }), &sig, &secret_key).unwrap();
+        let reordered: Value = serde_json::from_str(r#"{ "to": "node-a", "amount": 1e1 }"#).unwrap();
+        assert!(verify_json(&reordered, &signature, &sig, &public_key));
+        assert!(!verify_json(&json!({ "amount": 11, "to": "node-a" }), &signature, &sig, &public_key));
+    }
+}
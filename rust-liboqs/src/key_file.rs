@@ -0,0 +1,281 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Size of the read buffer used while streaming a key file.
+const CHUNK_SIZE: usize = 4096;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEncoding {
+    Hex,
+    Base64,
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    Io(io::Error),
+    InvalidCharacter { offset: u64, byte: u8 },
+    InvalidPadding { offset: u64 },
+    Truncated { offset: u64 },
+    LengthMismatch { expected: usize, actual: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Io(e) => write!(f, "failed to read key file: {}", e),
+            DecodeError::InvalidCharacter { offset, byte } => {
+                write!(f, "invalid character {:?} at byte offset {}", *byte as char, offset)
+            }
+            DecodeError::InvalidPadding { offset } => write!(f, "invalid padding at byte offset {}", offset),
+            DecodeError::Truncated { offset } => write!(f, "encoded data ends mid-symbol at byte offset {}", offset),
+            DecodeError::LengthMismatch { expected, actual } => {
+                write!(f, "decoded key is {} bytes, expected {}", actual, expected)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(e: io::Error) -> Self {
+        DecodeError::Io(e)
+    }
+}
+
+/// Incremental hex/base64 decoder that consumes one input byte at a time.
+struct StreamDecoder {
+    encoding: KeyEncoding,
+    /// Required output length, or `None` to accept any length.
+    expected_len: Option<usize>,
+    out: Vec<u8>,
+    acc: u32,
+    bits: u32,
+    symbols: usize,
+    padding: usize,
+}
+
+impl StreamDecoder {
+    fn new(encoding: KeyEncoding, expected_len: Option<usize>) -> Self {
+        Self {
+            encoding,
+            expected_len,
+            out: Vec::with_capacity(expected_len.unwrap_or(0)),
+            acc: 0,
+            bits: 0,
+            symbols: 0,
+            padding: 0,
+        }
+    }
+
+    fn push(&mut self, byte: u8, offset: u64) -> Result<(), DecodeError> {
+        if byte.is_ascii_whitespace() {
+            return Ok(());
+        }
+
+        let (value, width) = match self.encoding {
+            KeyEncoding::Hex => ((byte as char).to_digit(16), 4),
+            KeyEncoding::Base64 => {
+                if byte == b'=' {
+                    self.padding += 1;
+                    if self.padding > 2 {
+                        return Err(DecodeError::InvalidPadding { offset });
+                    }
+                    return Ok(());
+                }
+                if self.padding > 0 {
+                    return Err(DecodeError::InvalidPadding { offset });
+                }
+                let value = BASE64_ALPHABET.iter().position(|&c| c == byte).map(|v| v as u32);
+                (value, 6)
+            }
+        };
+        let value = value.ok_or(DecodeError::InvalidCharacter { offset, byte })?;
+
+        self.symbols += 1;
+        self.acc = (self.acc << width) | value;
+        self.bits += width;
+        if self.bits >= 8 {
+            self.bits -= 8;
+            self.out.push((self.acc >> self.bits) as u8);
+            self.acc &= (1 << self.bits) - 1;
+            if let Some(expected) = self.expected_len
+                && self.out.len() > expected
+            {
+                return Err(DecodeError::LengthMismatch {
+                    expected,
+                    actual: self.out.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self, offset: u64) -> Result<Vec<u8>, DecodeError> {
+        match self.encoding {
+            KeyEncoding::Hex if self.bits != 0 => return Err(DecodeError::Truncated { offset }),
+            KeyEncoding::Base64 => {
+                let remainder = self.symbols % 4;
+                if remainder == 1 {
+                    return Err(DecodeError::Truncated { offset });
+                }
+                let padding_ok = self.padding == 0 || self.padding == (4 - remainder) % 4;
+                // Leftover bits must be zero, otherwise the encoding was not canonical.
+                if !padding_ok || self.acc != 0 {
+                    return Err(DecodeError::InvalidPadding { offset });
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(expected) = self.expected_len
+            && self.out.len() != expected
+        {
+            return Err(DecodeError::LengthMismatch {
+                expected,
+                actual: self.out.len(),
+            });
+        }
+        Ok(self.out)
+    }
+}
+
+/// Streams a hex or base64 encoded key file from disk, decoding it chunk by chunk and
+/// checking that the result is exactly `expected_len` bytes long.
+///
+/// Whitespace (including line breaks) is ignored. Errors report the byte offset in the file.
+pub fn decode_key_file(path: &Path, encoding: KeyEncoding, expected_len: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut file = File::open(path)?;
+    let mut decoder = StreamDecoder::new(encoding, Some(expected_len));
+    let mut buf = [0u8; CHUNK_SIZE];
+    let mut offset = 0u64;
+
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        for &byte in &buf[..read] {
+            decoder.push(byte, offset)?;
+            offset += 1;
+        }
+    }
+
+    decoder.finish(offset)
+}
+
+/// Encodes `bytes` as padded standard base64.
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(group >> (18 - 6 * i)) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decodes base64 text of any length with the same rules as [`decode_key_file`]. Errors
+/// report the byte offset in `text`.
+pub fn decode_base64(text: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut decoder = StreamDecoder::new(KeyEncoding::Base64, None);
+    for (offset, byte) in (0u64..).zip(text.bytes()) {
+        decoder.push(byte, offset)?;
+    }
+    decoder.finish(text.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scratch_file(test: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("quantova-key-file-test-{}-{}", std::process::id(), test));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn base64_round_trips_at_every_padding_length() {
+        for len in 0..=5usize {
+            let bytes: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(97).wrapping_add(13)).collect();
+            let encoded = encode_base64(&bytes);
+            assert_eq!(encoded.len(), len.div_ceil(3) * 4);
+            assert_eq!(decode_base64(&encoded).unwrap(), bytes, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn invalid_characters_report_their_offset() {
+        assert!(matches!(decode_base64("QU!D"), Err(DecodeError::InvalidCharacter { offset: 2, byte: b'!' })));
+        // Skipped whitespace still counts towards the offset
+        assert!(matches!(decode_base64("QU\n-D"), Err(DecodeError::InvalidCharacter { offset: 3, byte: b'-' })));
+
+        let path = scratch_file("invalid-hex", b"00ff\n0g");
+        let result = decode_key_file(&path, KeyEncoding::Hex, 3);
+        let _ = fs::remove_file(&path);
+        assert!(matches!(result, Err(DecodeError::InvalidCharacter { offset: 6, byte: b'g' })));
+    }
+
+    #[test]
+    fn bad_or_misplaced_padding_is_rejected() {
+        // Data after padding
+        assert!(matches!(decode_base64("QQ=A"), Err(DecodeError::InvalidPadding { offset: 3 })));
+        // Too many padding characters
+        assert!(matches!(decode_base64("QQ==="), Err(DecodeError::InvalidPadding { offset: 4 })));
+        // Too few, or padding where none is needed
+        assert!(matches!(decode_base64("QQ="), Err(DecodeError::InvalidPadding { offset: 3 })));
+        assert!(matches!(decode_base64("QUJD="), Err(DecodeError::InvalidPadding { offset: 5 })));
+    }
+
+    #[test]
+    fn truncated_input_is_rejected() {
+        assert!(matches!(decode_base64("QUJDR"), Err(DecodeError::Truncated { offset: 5 })));
+
+        let path = scratch_file("truncated-hex", b"00ff0");
+        let result = decode_key_file(&path, KeyEncoding::Hex, 3);
+        let _ = fs::remove_file(&path);
+        assert!(matches!(result, Err(DecodeError::Truncated { offset: 5 })));
+    }
+
+    #[test]
+    fn non_zero_trailing_bits_are_rejected() {
+        assert_eq!(decode_base64("QQ==").unwrap(), b"A");
+        // "QR==" decodes to the same byte but leaves non-zero bits over
+        assert!(matches!(decode_base64("QR=="), Err(DecodeError::InvalidPadding { offset: 4 })));
+        assert!(matches!(decode_base64("QUJ="), Err(DecodeError::InvalidPadding { offset: 4 })));
+    }
+
+    #[test]
+    fn embedded_whitespace_and_newlines_are_ignored() {
+        assert_eq!(decode_base64(" QUJD\r\nRA==\n").unwrap(), b"ABCD");
+
+        let path = scratch_file("wrapped-hex", b"00 ff\n\t7f\r\n");
+        let result = decode_key_file(&path, KeyEncoding::Hex, 3);
+        let _ = fs::remove_file(&path);
+        assert_eq!(result.unwrap(), [0x00, 0xff, 0x7f]);
+    }
+
+    #[test]
+    fn keys_of_the_wrong_length_are_rejected() {
+        let path = scratch_file("length-mismatch", b"QUJDRA==");
+        let short = decode_key_file(&path, KeyEncoding::Base64, 5);
+        let long = decode_key_file(&path, KeyEncoding::Base64, 3);
+        let exact = decode_key_file(&path, KeyEncoding::Base64, 4);
+        let _ = fs::remove_file(&path);
+        assert!(matches!(short, Err(DecodeError::LengthMismatch { expected: 5, actual: 4 })));
+        // A key that runs long is rejected as soon as it passes the expected length
+        assert!(matches!(long, Err(DecodeError::LengthMismatch { expected: 3, actual: 4 })));
+        assert_eq!(exact.unwrap(), b"ABCD");
+    }
+}
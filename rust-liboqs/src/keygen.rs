@@ -0,0 +1,128 @@
+use crate::cancellation::{BatchError, CancellationToken};
+use oqs::kem::{self, Kem};
+use oqs::sig::{Algorithm, PublicKey, SecretKey, Sig};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum KeygenError {
+    RngUnavailable,
+    Backend(oqs::Error),
+}
+
+impl fmt::Display for KeygenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeygenError::RngUnavailable => write!(
+                f,
+                "the system random number generator is unavailable. On a freshly booted container or VM \
+                 the kernel entropy pool may not be initialized yet; wait a moment and retry, and make sure \
+                 getrandom(2) or /dev/urandom is accessible inside the sandbox"
+            ),
+            KeygenError::Backend(e) => write!(f, "key generation failed: {}", e),
+        }
+    }
+}
+
+impl From<oqs::Error> for KeygenError {
+    fn from(e: oqs::Error) -> Self {
+        KeygenError::Backend(e)
+    }
+}
+
+/// Checks that the operating system RNG can produce bytes.
+fn probe_system_rng() -> Result<(), KeygenError> {
+    let mut probe = [0u8; 1];
+    SystemRandom::new().fill(&mut probe).map_err(|_| KeygenError::RngUnavailable)
+}
+
+/// Returns a random 32-byte seed from the system RNG, e.g. for an Ed25519 key.
+pub fn random_seed() -> Result<[u8; 32], KeygenError> {
+    let mut seed = [0u8; 32];
+    SystemRandom::new().fill(&mut seed).map_err(|_| KeygenError::RngUnavailable)?;
+    Ok(seed)
+}
+
+/// Generates a key pair for `sig`, reporting an unusable system RNG as
+/// [`KeygenError::RngUnavailable`].
+///
+/// liboqs draws randomness from the same OS source but exposes no error for it: its default
+/// RNG terminates the process when the source fails. The RNG is therefore probed first so
+/// that the failure can be reported instead of aborting.
+pub fn generate_sig_keypair(sig: &Sig) -> Result<(PublicKey, SecretKey), KeygenError> {
+    probe_system_rng()?;
+    Ok(sig.keypair()?)
+}
+
+/// Generates a key pair for `kem`, probing the system RNG first like [`generate_sig_keypair`].
+pub fn generate_kem_keypair(kem: &Kem) -> Result<(kem::PublicKey, kem::SecretKey), KeygenError> {
+    probe_system_rng()?;
+    Ok(kem.keypair()?)
+}
+
+pub type SigKeypair = (PublicKey, SecretKey);
+
+/// Generates `n` key pairs for `algorithm`, e.g. to provision many identities at once.
+///
+/// With the `parallel` feature the key pairs are generated on the rayon thread pool. liboqs
+/// draws every key from the OS RNG, which is thread-safe and needs no per-thread seeding, so
+/// the threads do not share any RNG state. Without the feature the key pairs are generated
+/// one after another.
+///
+/// `cancel` is checked before each key pair. Once it is set, generation stops and
+/// [`BatchError::Cancelled`] returns the key pairs completed so far.
+pub fn generate_keypairs(
+    n: usize,
+    algorithm: Algorithm,
+    cancel: &CancellationToken,
+) -> Result<Vec<SigKeypair>, BatchError<SigKeypair, KeygenError>> {
+    probe_system_rng().map_err(BatchError::Failed)?;
+    let sig = Sig::new(algorithm).map_err(|e| BatchError::Failed(e.into()))?;
+    let generate = |_| {
+        if cancel.is_cancelled() {
+            return Ok(None);
+        }
+        sig.keypair().map(Some).map_err(|e| BatchError::Failed(KeygenError::from(e)))
+    };
+
+    #[cfg(feature = "parallel")]
+    let results: Vec<Option<_>> = {
+        use rayon::prelude::*;
+        (0..n).into_par_iter().map(generate).collect::<Result<_, _>>()?
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    let results: Vec<Option<_>> = (0..n).map(generate).collect::<Result<_, _>>()?;
+
+    let keypairs: Vec<_> = results.into_iter().flatten().collect();
+    if keypairs.len() < n {
+        return Err(BatchError::Cancelled { completed: keypairs });
+    }
+    Ok(keypairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn generated_keypairs_are_all_distinct() {
+        let keypairs = generate_keypairs(32, Algorithm::Dilithium2, &CancellationToken::new()).unwrap();
+        assert_eq!(keypairs.len(), 32);
+        let public_keys: HashSet<&[u8]> = keypairs.iter().map(|(public_key, _)| public_key.as_ref()).collect();
+        let secret_keys: HashSet<&[u8]> = keypairs.iter().map(|(_, secret_key)| secret_key.as_ref()).collect();
+        assert_eq!(public_keys.len(), 32);
+        assert_eq!(secret_keys.len(), 32);
+    }
+
+    #[test]
+    fn a_cancelled_batch_generates_nothing() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        match generate_keypairs(4, Algorithm::Dilithium2, &cancel) {
+            Err(BatchError::Cancelled { completed }) => assert!(completed.is_empty()),
+            other => panic!("expected a cancelled batch, got {:?}", other.map(|keypairs| keypairs.len())),
+        }
+    }
+}
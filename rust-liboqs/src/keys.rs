@@ -0,0 +1,58 @@
+// Identifiers for keys and secret material, all derived from SHA-256: the full hex
+// fingerprint keys the replay guard, the 8-byte key ID goes in message headers, and the
+// short fingerprint stands in for secret bytes in logs.
+
+use ring::digest::{self, SHA256};
+
+/// Length of a [`key_id`] in bytes.
+pub const KEY_ID_LEN: usize = 8;
+
+/// Number of hex characters of the SHA-256 fingerprint shown in place of secret bytes.
+const FINGERPRINT_CHARS: usize = 16;
+
+/// Returns the hex SHA-256 fingerprint used to identify a signer's public key.
+pub fn key_fingerprint(public_key: &[u8]) -> String {
+    hex::encode(digest::digest(&SHA256, public_key))
+}
+
+/// Returns a short, stable key identifier (KID) for a public key: the first 8 bytes of its
+/// SHA-256. It goes in message headers so receivers can pick the right key from a key
+/// store; it is not a substitute for checking the key itself.
+pub fn key_id(public_key: &[u8]) -> [u8; KEY_ID_LEN] {
+    let mut id = [0u8; KEY_ID_LEN];
+    id.copy_from_slice(&digest::digest(&SHA256, public_key).as_ref()[..KEY_ID_LEN]);
+    id
+}
+
+/// Returns a short SHA-256 fingerprint that identifies secret material in logs without
+/// revealing it.
+pub fn fingerprint(secret: &[u8]) -> String {
+    let mut fingerprint = key_fingerprint(secret);
+    fingerprint.truncate(FINGERPRINT_CHARS);
+    fingerprint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oqs::sig::{Algorithm, Sig};
+    use std::collections::HashSet;
+
+    #[test]
+    fn distinct_keys_get_distinct_key_ids() {
+        let sig = Sig::new(Algorithm::Dilithium2).unwrap();
+        let public_keys: Vec<_> = (0..64).map(|_| sig.keypair().unwrap().0).collect();
+        let ids: HashSet<_> = public_keys.iter().map(|public_key| key_id(public_key.as_ref())).collect();
+        assert_eq!(ids.len(), public_keys.len());
+        // The ID is a pure function of the key
+        assert_eq!(key_id(public_keys[0].as_ref()), key_id(public_keys[0].clone().as_ref()));
+    }
+
+    #[test]
+    fn identifiers_are_prefixes_of_the_full_fingerprint() {
+        let full = key_fingerprint(b"public key");
+        assert_eq!(full.len(), 64);
+        assert_eq!(hex::encode(key_id(b"public key")), full[..2 * KEY_ID_LEN]);
+        assert_eq!(fingerprint(b"public key"), full[..FINGERPRINT_CHARS]);
+    }
+}
@@ -0,0 +1,5 @@
+// Library half of the toolkit: the pieces with no dependency on the interactive demos, so
+// benches and other crates can link against them. The binary re-exports them at its root.
+
+pub mod gf256;
+pub mod shamir;
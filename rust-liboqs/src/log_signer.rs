@@ -0,0 +1,278 @@
+use oqs::sig::{self, Algorithm, Sig};
+use ring::digest::{self, SHA256};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Domain separator seeding the rolling hash, so a log chain never collides with other hashes.
+const LOG_HASH_SEED: &[u8] = b"Quantova signed log v1";
+
+#[derive(Debug)]
+pub enum LogError {
+    Io(io::Error),
+    Backend(oqs::Error),
+    MalformedSidecar { line: usize },
+    /// A sidecar entry is missing, duplicated or out of order.
+    OutOfOrder { expected: u64, found: u64 },
+    /// The log ends before the bytes recorded for this chunk.
+    Truncated { chunk: u64 },
+    /// The log holds bytes after the last signed chunk.
+    UnsignedTail { bytes: usize },
+    InvalidSignature { chunk: u64 },
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogError::Io(e) => write!(f, "log I/O failed: {}", e),
+            LogError::Backend(e) => write!(f, "signature backend error: {}", e),
+            LogError::MalformedSidecar { line } => write!(f, "malformed signature sidecar at line {}", line),
+            LogError::OutOfOrder { expected, found } => {
+                write!(f, "expected chunk {} but the sidecar lists chunk {}", expected, found)
+            }
+            LogError::Truncated { chunk } => write!(f, "log is truncated inside chunk {}", chunk),
+            LogError::UnsignedTail { bytes } => write!(f, "log has {} bytes after the last signed chunk", bytes),
+            LogError::InvalidSignature { chunk } => write!(f, "signature for chunk {} is invalid", chunk),
+        }
+    }
+}
+
+impl From<io::Error> for LogError {
+    fn from(e: io::Error) -> Self {
+        LogError::Io(e)
+    }
+}
+
+impl From<oqs::Error> for LogError {
+    fn from(e: oqs::Error) -> Self {
+        LogError::Backend(e)
+    }
+}
+
+/// Path of the per-chunk signature sidecar kept next to `log_path`.
+pub fn sidecar_path(log_path: &Path) -> PathBuf {
+    let mut path = log_path.as_os_str().to_owned();
+    path.push(".sigs");
+    PathBuf::from(path)
+}
+
+fn initial_hash() -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(digest::digest(&SHA256, LOG_HASH_SEED).as_ref());
+    hash
+}
+
+/// Chains `chunk` onto the previous cumulative hash. Each step covers the previous hash, so
+/// removing or reordering a chunk changes every hash after it.
+fn next_hash(previous: &[u8; 32], index: u64, chunk: &[u8]) -> [u8; 32] {
+    let mut ctx = digest::Context::new(&SHA256);
+    ctx.update(previous);
+    ctx.update(&index.to_be_bytes());
+    ctx.update(&(chunk.len() as u64).to_be_bytes());
+    ctx.update(chunk);
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(ctx.finish().as_ref());
+    hash
+}
+
+/// The signed statement for a chunk: the cumulative hash followed by the chunk index.
+fn signed_statement(hash: &[u8; 32], index: u64) -> [u8; 40] {
+    let mut statement = [0u8; 40];
+    statement[..32].copy_from_slice(hash);
+    statement[32..].copy_from_slice(&index.to_be_bytes());
+    statement
+}
+
+struct SidecarEntry {
+    index: u64,
+    len: usize,
+    signature: Vec<u8>,
+}
+
+fn read_sidecar(path: &Path) -> Result<Vec<SidecarEntry>, LogError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let malformed = LogError::MalformedSidecar { line: number + 1 };
+        let mut fields = line.split(' ');
+        let (Some(index), Some(len), Some(signature), None) = (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(malformed);
+        };
+        let (Ok(index), Ok(len), Ok(signature)) = (index.parse(), len.parse(), hex::decode(signature)) else {
+            return Err(malformed);
+        };
+        entries.push(SidecarEntry { index, len, signature });
+    }
+    Ok(entries)
+}
+
+/// Replays the log against its sidecar, calling `check` with each chunk's cumulative hash,
+/// and returns the final hash and the number of chunks.
+fn replay(
+    log_path: &Path,
+    mut check: impl FnMut(u64, &[u8; 32], &[u8]) -> Result<(), LogError>,
+) -> Result<([u8; 32], u64), LogError> {
+    let log = match fs::read(log_path) {
+        Ok(log) => log,
+        Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let entries = read_sidecar(&sidecar_path(log_path))?;
+
+    let mut hash = initial_hash();
+    let mut offset = 0usize;
+    for (expected, entry) in (0u64..).zip(&entries) {
+        if entry.index != expected {
+            return Err(LogError::OutOfOrder { expected, found: entry.index });
+        }
+        let chunk = offset
+            .checked_add(entry.len)
+            .and_then(|end| log.get(offset..end))
+            .ok_or(LogError::Truncated { chunk: entry.index })?;
+        hash = next_hash(&hash, entry.index, chunk);
+        check(entry.index, &hash, &entry.signature)?;
+        offset += entry.len;
+    }
+    if offset != log.len() {
+        return Err(LogError::UnsignedTail { bytes: log.len() - offset });
+    }
+    Ok((hash, entries.len() as u64))
+}
+
+/// Appends chunks to a log and signs each one over the cumulative hash of the whole log,
+/// so an append costs one hash update and one signature instead of re-signing the file.
+///
+/// Signatures go to a sidecar file next to the log (see [`sidecar_path`]), one line per
+/// chunk holding the chunk index, its length and the hex signature.
+pub struct LogSigner<'a> {
+    sig: Sig,
+    secret_key: &'a sig::SecretKey,
+    log_path: PathBuf,
+    hash: [u8; 32],
+    next_index: u64,
+}
+
+impl<'a> LogSigner<'a> {
+    /// Opens the log at `log_path`, creating it if needed. An existing log is replayed to
+    /// restore the rolling hash, so signing continues where it left off. Existing
+    /// signatures are not checked; use [`verify_log`] for that.
+    pub fn open(log_path: &Path, algorithm: Algorithm, secret_key: &'a sig::SecretKey) -> Result<Self, LogError> {
+        let (hash, next_index) = replay(log_path, |_, _, _| Ok(()))?;
+        Ok(Self {
+            sig: Sig::new(algorithm)?,
+            secret_key,
+            log_path: log_path.to_path_buf(),
+            hash,
+            next_index,
+        })
+    }
+
+    /// Appends `chunk` to the log and its signature to the sidecar. Returns the chunk index.
+    pub fn append(&mut self, chunk: &[u8]) -> Result<u64, LogError> {
+        let index = self.next_index;
+        let hash = next_hash(&self.hash, index, chunk);
+        let signature = self.sig.sign(&signed_statement(&hash, index), self.secret_key)?;
+
+        OpenOptions::new().create(true).append(true).open(&self.log_path)?.write_all(chunk)?;
+        let mut sidecar = OpenOptions::new().create(true).append(true).open(sidecar_path(&self.log_path))?;
+        writeln!(sidecar, "{} {} {}", index, chunk.len(), hex::encode(signature.as_ref()))?;
+
+        self.hash = hash;
+        self.next_index += 1;
+        Ok(index)
+    }
+}
+
+/// Replays the log, recomputing the rolling hash, and verifies every chunk signature.
+/// Returns the number of verified chunks.
+///
+/// A removed, reordered or altered chunk breaks the hash chain for every later chunk.
+/// Dropping chunks from the very end of both files leaves a shorter but valid log, so
+/// compare the returned count against a trusted value when truncation matters.
+pub fn verify_log(log_path: &Path, algorithm: Algorithm, public_key: &sig::PublicKey) -> Result<u64, LogError> {
+    let sig = Sig::new(algorithm)?;
+    let (_, chunks) = replay(log_path, |index, hash, signature| {
+        let valid = sig
+            .signature_from_bytes(signature)
+            .is_some_and(|signature| sig.verify(&signed_statement(hash, index), signature, public_key).is_ok());
+        if valid { Ok(()) } else { Err(LogError::InvalidSignature { chunk: index }) }
+    })?;
+    Ok(chunks)
+}
+
+/// Removes a log and its sidecar, ignoring files that do not exist.
+pub fn remove_log(log_path: &Path) -> io::Result<()> {
+    for path in [log_path.to_path_buf(), sidecar_path(log_path)] {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALGORITHM: Algorithm = Algorithm::Dilithium2;
+
+    /// Signs three chunks, reopening the log before the last one, and returns its path with
+    /// the signer's public key.
+    fn signed_log(test: &str) -> (PathBuf, sig::PublicKey) {
+        let dir = std::env::temp_dir().join(format!("quantova-log-test-{}-{}", std::process::id(), test));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("events.log");
+        let (pk, sk) = Sig::new(ALGORITHM).unwrap().keypair().unwrap();
+        let mut signer = LogSigner::open(&path, ALGORITHM, &sk).unwrap();
+        signer.append(b"login alice\n").unwrap();
+        signer.append(b"rotate-key alice\n").unwrap();
+        let mut resumed = LogSigner::open(&path, ALGORITHM, &sk).unwrap();
+        assert_eq!(resumed.append(b"logout alice\n").unwrap(), 2);
+        (path, pk)
+    }
+
+    fn cleanup(path: &Path) {
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn a_resumed_log_verifies() {
+        let (path, public_key) = signed_log("resume");
+        assert_eq!(verify_log(&path, ALGORITHM, &public_key).unwrap(), 3);
+        remove_log(&path).unwrap();
+        assert!(!path.exists() && !sidecar_path(&path).exists());
+        cleanup(&path);
+    }
+
+    #[test]
+    fn modified_or_extended_logs_are_rejected() {
+        let (path, public_key) = signed_log("tamper");
+
+        let mut log = fs::read(&path).unwrap();
+        log[13] ^= 0x01;
+        fs::write(&path, &log).unwrap();
+        assert!(matches!(verify_log(&path, ALGORITHM, &public_key), Err(LogError::InvalidSignature { chunk: 1 })));
+
+        log[13] ^= 0x01;
+        fs::write(&path, &log).unwrap();
+        OpenOptions::new().append(true).open(&path).unwrap().write_all(b"unsigned\n").unwrap();
+        assert!(matches!(verify_log(&path, ALGORITHM, &public_key), Err(LogError::UnsignedTail { bytes: 9 })));
+        cleanup(&path);
+    }
+
+    #[test]
+    fn a_different_key_does_not_verify() {
+        let (path, _) = signed_log("wrong-key");
+        let (other, _) = Sig::new(ALGORITHM).unwrap().keypair().unwrap();
+        assert!(matches!(verify_log(&path, ALGORITHM, &other), Err(LogError::InvalidSignature { chunk: 0 })));
+        cleanup(&path);
+    }
+}
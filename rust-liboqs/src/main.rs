@@ -1,20 +1,155 @@
+mod algorithm;
+mod armor;
 mod authentication;
+mod benchmark;
+mod cancellation;
+mod classical;
+mod config;
+mod cose;
+mod dilithium_sign;
+mod envelope;
+mod error;
 mod hybrid_keys;
-mod schnorr;
+mod json_sign;
+mod key_file;
+mod keygen;
+mod keys;
+mod log_signer;
+mod manifest;
+mod multisig;
+mod password_channel;
+mod secret;
+mod selftest;
+mod sequence_guard;
+mod signing_tools;
 mod threshold;
+mod verify_cache;
+mod verify_cli;
+mod vss;
+mod wizard;
 
+use config::Config;
+use error::QuantovaError;
+use project::shamir;
+use std::fmt;
 use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+
+#[derive(Debug)]
+enum ToolkitError {
+    /// Every demo that failed, with the error it returned.
+    SmokeTestsFailed(Vec<(&'static str, QuantovaError)>),
+}
+
+impl fmt::Display for ToolkitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolkitError::SmokeTestsFailed(failures) => {
+                write!(f, "{} module(s) failed", failures.len())?;
+                for (name, e) in failures {
+                    write!(f, "\n   - {}: {}", name, e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A toolkit demo, with its module error converted so the smoke tests can collect it.
+type Demo<'a> = &'a dyn Fn() -> Result<(), QuantovaError>;
+
+/// Runs every toolkit demo in sequence, continuing past failures, prints a summary table and
+/// returns the errors of the demos that failed.
+fn run_all_smoke_tests(config: &Config) -> Result<(), ToolkitError> {
+    let demos: [(&'static str, Demo); 5] = [
+        ("Quantum-Safe Authentication", &authentication::authentication),
+        ("Dilithium3 Signatures", &|| Ok(dilithium_sign::dilithium_sign()?)),
+        ("Hybrid Cryptography", &|| hybrid_keys::hybrid_keys(config)),
+        ("Threshold Signatures", &|| Ok(threshold::threshold(&config.threshold)?)),
+        ("Signing Utilities", &signing_tools::signing_tools),
+    ];
+
+    let mut results = Vec::new();
+    for (name, demo) in demos {
+        println!("\n Running {}...", name);
+        results.push((name, demo()));
+    }
+
+    println!("\n==============================");
+    println!(" Smoke Test Summary");
+    println!("==============================");
+    for (name, result) in &results {
+        println!(" {:<34} {}", name, if result.is_ok() { "✅ PASS" } else { "❌ FAIL" });
+    }
+
+    let failed: Vec<(&'static str, QuantovaError)> =
+        results.into_iter().filter_map(|(name, result)| result.err().map(|e| (name, e))).collect();
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(ToolkitError::SmokeTestsFailed(failed))
+    }
+}
 
 fn main() {
+    let config = match Config::load(Path::new(config::DEFAULT_CONFIG_PATH)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("❌ {}: {}", config::DEFAULT_CONFIG_PATH, e);
+            process::exit(2);
+        }
+    };
+
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("verify") {
+        process::exit(verify_cli::run(&args[2..], &config));
+    }
+
+    // Once per interactive session; `verify` runs one algorithm and should start fast
+    let self_test = match selftest::run_self_test() {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            process::exit(2);
+        }
+    };
+
+    println!(
+        "Self-test: {} algorithms passed, {} not enabled",
+        self_test.count(|outcome| *outcome == selftest::SelfTestOutcome::Passed),
+        self_test.count(|outcome| *outcome == selftest::SelfTestOutcome::Skipped)
+    );
+
+    let aead = match envelope::Aead::from_name(&config.aead) {
+        Some(envelope::Aead::Auto) => format!("auto ({} on this CPU)", envelope::fastest_aead().name()),
+        _ => config.aead.clone(),
+    };
+    println!(
+        "Config: signature={}, kem={}, threshold={}-of-{}, aead={}",
+        config.signature, config.kem, config.threshold.t, config.threshold.n, aead
+    );
+    let envelope_overhead = algorithm::AlgorithmId::from_name(&config.kem)
+        .and_then(algorithm::AlgorithmId::to_kem)
+        .zip(envelope::Aead::from_name(&config.aead))
+        .and_then(|(kem, cipher)| envelope::estimated_ciphertext_size(0, kem, cipher).ok());
+    if let Some(overhead) = envelope_overhead {
+        println!("Envelope overhead: {} bytes on top of each message", overhead);
+    }
+
     loop {
         println!("\n==============================");
         println!(" Quantum Cryptography Toolkit");
         println!("==============================");
         println!("1. Quantum-Safe Authentication");
         println!("2. Hybrid Cryptography");
-        println!("3. Post-Quantum Schnorr Signatures");
+        println!("3. Dilithium3 Signatures");
         println!("4. Threshold Signatures");
-        println!("5. Exit");
+        println!("5. Benchmark Signature Algorithms");
+        println!("6. Key Generation Wizard");
+        println!("7. Signing Utilities");
+        println!("8. Run All (smoke test)");
+        println!("9. Exit");
         print!("\nSelect an option: ");
         io::stdout().flush().unwrap();
 
@@ -24,21 +159,48 @@ fn main() {
         match choice.trim() {
             "1" => {
                 println!("\n Running Quantum-Safe Authentication...");
-                authentication::authentication();
+                if let Err(e) = authentication::authentication() {
+                    println!("❌ Authentication demo failed: {}", e);
+                }
             }
             "2" => {
                 println!("\n Running Hybrid Cryptography...");
-                hybrid_keys::hybrid_keys();
+                if let Err(e) = hybrid_keys::hybrid_keys(&config) {
+                    println!("❌ Hybrid demo failed: {}", e);
+                }
             }
             "3" => {
-                println!("\n Running Post-Quantum Schnorr Signatures...");
-                schnorr::schnorr();
+                println!("\n Running Dilithium3 Signatures...");
+                if let Err(e) = dilithium_sign::dilithium_sign() {
+                    println!("❌ Dilithium3 demo failed: {}", e);
+                }
             }
             "4" => {
                 println!("\n Running Threshold Signatures...");
-                threshold::threshold();
+                if let Err(e) = threshold::threshold(&config.threshold) {
+                    println!("❌ Threshold demo failed: {}", e);
+                }
             }
             "5" => {
+                println!("\n Benchmarking enabled signature algorithms...");
+                let rows = benchmark::benchmark_all_sigs();
+                benchmark::print_sig_bench_table(&rows);
+            }
+            "6" => {
+                println!("\n Running Key Generation Wizard...");
+                wizard::wizard();
+            }
+            "7" => {
+                println!("\n Running Signing Utilities...");
+                if let Err(e) = signing_tools::signing_tools() {
+                    println!("❌ Signing utilities demo failed: {}", e);
+                }
+            }
+            "8" => match run_all_smoke_tests(&config) {
+                Ok(()) => println!("\n✅ All toolkit modules passed."),
+                Err(e) => println!("\n❌ Smoke test failed: {}", e),
+            },
+            "9" => {
                 println!("🚪 Exiting...");
                 break;
             }
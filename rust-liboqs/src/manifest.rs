@@ -0,0 +1,576 @@
+use crate::algorithm::AlgorithmId;
+use crate::envelope::{self, Aead, Envelope, EnvelopeError};
+use crate::json_sign;
+use crate::keys::{key_id, KEY_ID_LEN};
+use oqs::kem;
+use oqs::sig::{self, Algorithm, Sig};
+use ring::digest::{self, SHA256};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io { path: PathBuf, source: io::Error },
+    Backend(oqs::Error),
+    /// The signature algorithm has no [`AlgorithmId`], so it cannot be named in a sealed manifest.
+    UnsupportedAlgorithm(Algorithm),
+    Envelope(EnvelopeError),
+    /// A decrypted sealed manifest is not a valid manifest document.
+    Malformed,
+    /// The manifest signature does not verify, so its file list cannot be trusted.
+    InvalidSignature,
+    /// A sealed manifest was signed for another recipient's key and re-encrypted to this one.
+    RecipientMismatch,
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io { path, source } => write!(f, "cannot read {}: {}", path.display(), source),
+            ManifestError::Backend(e) => write!(f, "signature backend error: {}", e),
+            ManifestError::UnsupportedAlgorithm(algorithm) => {
+                write!(f, "{:?} cannot be used in a sealed manifest", algorithm)
+            }
+            ManifestError::Envelope(e) => write!(f, "sealed manifest: {}", e),
+            ManifestError::Malformed => write!(f, "sealed manifest does not contain a valid manifest"),
+            ManifestError::InvalidSignature => write!(f, "manifest signature is invalid"),
+            ManifestError::RecipientMismatch => write!(f, "manifest was signed for a different recipient"),
+        }
+    }
+}
+
+impl From<oqs::Error> for ManifestError {
+    fn from(e: oqs::Error) -> Self {
+        ManifestError::Backend(e)
+    }
+}
+
+impl From<EnvelopeError> for ManifestError {
+    fn from(e: EnvelopeError) -> Self {
+        ManifestError::Envelope(e)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub path: PathBuf,
+    pub sha256: [u8; 32],
+}
+
+/// A list of file hashes covered by a single signature.
+#[derive(Debug, Clone)]
+pub struct SignedManifest {
+    pub algorithm: Algorithm,
+    /// [`key_id`] of the KEM key a sealed manifest was signed for; `None` for plain manifests.
+    /// It is part of the signed payload, so a recipient cannot re-encrypt the manifest to
+    /// someone else and pass it off as addressed to them.
+    pub recipient_key_id: Option<[u8; KEY_ID_LEN]>,
+    pub entries: Vec<ManifestEntry>,
+    pub signature: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Modified,
+    Missing,
+}
+
+/// Outcome of [`verify_files`] for a manifest whose signature holds: the files that no
+/// longer match it.
+#[derive(Debug)]
+pub struct ManifestReport {
+    pub mismatches: Vec<(PathBuf, FileStatus)>,
+}
+
+impl ManifestReport {
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// One way a directory differs from its manifest, as reported by [`verify_directory`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestDiff {
+    /// On disk but not in the manifest.
+    Added(PathBuf),
+    /// In the manifest but no longer on disk.
+    Removed(PathBuf),
+    Modified {
+        path: PathBuf,
+        expected_hash: [u8; 32],
+        actual_hash: [u8; 32],
+    },
+}
+
+impl ManifestDiff {
+    pub fn path(&self) -> &Path {
+        match self {
+            ManifestDiff::Added(path) | ManifestDiff::Removed(path) | ManifestDiff::Modified { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for ManifestDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestDiff::Added(path) => write!(f, "added    {}", path.display()),
+            ManifestDiff::Removed(path) => write!(f, "removed  {}", path.display()),
+            ManifestDiff::Modified { path, expected_hash, actual_hash } => write!(
+                f,
+                "modified {} (expected {}, found {})",
+                path.display(),
+                hex::encode(expected_hash),
+                hex::encode(actual_hash)
+            ),
+        }
+    }
+}
+
+/// Shows files processed / total and the current file while hashing, when the `indicatif`
+/// feature is enabled and stderr is a terminal. Otherwise it does nothing, so scripted output
+/// stays clean.
+struct Progress {
+    #[cfg(feature = "indicatif")]
+    bar: Option<indicatif::ProgressBar>,
+}
+
+impl Progress {
+    #[cfg(feature = "indicatif")]
+    fn new(total: usize, action: &'static str) -> Self {
+        use std::io::IsTerminal;
+
+        let bar = io::stderr().is_terminal().then(|| {
+            let bar = indicatif::ProgressBar::new(total as u64);
+            bar.set_style(
+                indicatif::ProgressStyle::with_template("{prefix} [{bar:30}] {pos}/{len} {wide_msg}")
+                    .expect("Progress template is valid.")
+                    .progress_chars("=> "),
+            );
+            bar.set_prefix(action);
+            bar
+        });
+        Self { bar }
+    }
+
+    #[cfg(not(feature = "indicatif"))]
+    fn new(_total: usize, _action: &'static str) -> Self {
+        Self {}
+    }
+
+    fn start_file(&self, _path: &Path) {
+        #[cfg(feature = "indicatif")]
+        if let Some(bar) = &self.bar {
+            bar.set_message(_path.display().to_string());
+        }
+    }
+
+    fn finish_file(&self) {
+        #[cfg(feature = "indicatif")]
+        if let Some(bar) = &self.bar {
+            bar.inc(1);
+        }
+    }
+
+    fn finish(self) {
+        #[cfg(feature = "indicatif")]
+        if let Some(bar) = self.bar {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+fn hash_file(path: &Path) -> io::Result<[u8; 32]> {
+    let contents = fs::read(path)?;
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(digest::digest(&SHA256, &contents).as_ref());
+    Ok(hash)
+}
+
+/// Spells `path` with `/` separators whatever the host uses, so a manifest signed on one
+/// platform verifies on another.
+fn portable_path(path: &Path) -> String {
+    let mut portable = String::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(prefix) => portable.push_str(&prefix.as_os_str().to_string_lossy()),
+            Component::RootDir => portable.push('/'),
+            other => {
+                if !portable.is_empty() && !portable.ends_with('/') {
+                    portable.push('/');
+                }
+                portable.push_str(&other.as_os_str().to_string_lossy());
+            }
+        }
+    }
+    portable
+}
+
+fn algorithm_name(algorithm: Algorithm) -> Result<&'static str, ManifestError> {
+    AlgorithmId::from_sig(algorithm)
+        .map(AlgorithmId::liboqs_name)
+        .ok_or(ManifestError::UnsupportedAlgorithm(algorithm))
+}
+
+fn files_json(entries: &[ManifestEntry]) -> Vec<Value> {
+    entries
+        .iter()
+        .map(|entry| json!({ "path": portable_path(&entry.path), "sha256": hex::encode(entry.sha256) }))
+        .collect()
+}
+
+/// The bytes that are signed: the RFC 8785 form of the liboqs algorithm name, the recipient
+/// key ID of a sealed manifest and the (path, hash) list.
+fn manifest_payload(
+    algorithm: Algorithm,
+    recipient_key_id: Option<&[u8; KEY_ID_LEN]>,
+    entries: &[ManifestEntry],
+) -> Result<Vec<u8>, ManifestError> {
+    let mut document = json!({ "algorithm": algorithm_name(algorithm)?, "files": files_json(entries) });
+    if let Some(recipient_key_id) = recipient_key_id {
+        document["recipient"] = json!(hex::encode(recipient_key_id));
+    }
+    Ok(json_sign::canonicalize(&document).into_bytes())
+}
+
+/// Hashes `base.join(path)` for each path and signs the (path, hash) list, recording the
+/// paths as given, along with the recipient of a sealed manifest.
+fn sign_under(
+    base: &Path,
+    mut paths: Vec<PathBuf>,
+    recipient_key_id: Option<[u8; KEY_ID_LEN]>,
+    secret_key: &sig::SecretKey,
+    algorithm: Algorithm,
+) -> Result<SignedManifest, ManifestError> {
+    paths.sort();
+    paths.dedup();
+
+    let progress = Progress::new(paths.len(), "Signing");
+    let mut entries = Vec::with_capacity(paths.len());
+    for path in paths {
+        progress.start_file(&path);
+        let full_path = base.join(&path);
+        let sha256 = hash_file(&full_path).map_err(|source| ManifestError::Io { path: full_path, source })?;
+        progress.finish_file();
+        entries.push(ManifestEntry { path, sha256 });
+    }
+    progress.finish();
+
+    let sig = Sig::new(algorithm)?;
+    let signature = sig.sign(&manifest_payload(algorithm, recipient_key_id.as_ref(), &entries)?, secret_key)?;
+    Ok(SignedManifest {
+        algorithm,
+        recipient_key_id,
+        entries,
+        signature: signature.into_vec(),
+    })
+}
+
+/// Hashes each file in `paths` and signs the resulting (path, hash) list once.
+///
+/// Entries are sorted by path and duplicates are removed, so the same set of files always
+/// produces the same signed payload regardless of the order they were listed in.
+pub fn sign_files(
+    paths: &[PathBuf],
+    secret_key: &sig::SecretKey,
+    algorithm: Algorithm,
+) -> Result<SignedManifest, ManifestError> {
+    sign_under(Path::new(""), paths.to_vec(), None, secret_key, algorithm)
+}
+
+fn signature_valid(manifest: &SignedManifest, public_key: &sig::PublicKey) -> Result<bool, ManifestError> {
+    let sig = Sig::new(manifest.algorithm)?;
+    let payload = manifest_payload(manifest.algorithm, manifest.recipient_key_id.as_ref(), &manifest.entries)?;
+    Ok(sig
+        .signature_from_bytes(&manifest.signature)
+        .is_some_and(|signature| sig.verify(&payload, signature, public_key).is_ok()))
+}
+
+/// Checks the manifest signature, then re-hashes `base.join(path)` for every entry.
+fn verify_under(manifest: &SignedManifest, public_key: &sig::PublicKey, base: &Path) -> Result<ManifestReport, ManifestError> {
+    if !signature_valid(manifest, public_key)? {
+        return Err(ManifestError::InvalidSignature);
+    }
+
+    let progress = Progress::new(manifest.entries.len(), "Verifying");
+    let mut mismatches = Vec::new();
+    for entry in &manifest.entries {
+        progress.start_file(&entry.path);
+        let full_path = base.join(&entry.path);
+        let hashed = hash_file(&full_path);
+        progress.finish_file();
+        match hashed {
+            Ok(hash) if hash == entry.sha256 => {}
+            Ok(_) => mismatches.push((entry.path.clone(), FileStatus::Modified)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => mismatches.push((entry.path.clone(), FileStatus::Missing)),
+            Err(source) => return Err(ManifestError::Io { path: full_path, source }),
+        }
+    }
+    progress.finish();
+
+    Ok(ManifestReport { mismatches })
+}
+
+/// Checks the manifest signature against `public_key`, then re-hashes every listed file and
+/// reports the ones that changed or disappeared.
+///
+/// Fails with [`ManifestError::InvalidSignature`] before hashing anything if the signature
+/// does not verify.
+pub fn verify_files(manifest: &SignedManifest, public_key: &sig::PublicKey) -> Result<ManifestReport, ManifestError> {
+    verify_under(manifest, public_key, Path::new(""))
+}
+
+/// Lists every regular file below `dir`, relative to `dir`. Symbolic links are skipped.
+fn walk_directory(dir: &Path) -> Result<Vec<PathBuf>, ManifestError> {
+    let mut files = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let full_path = dir.join(&relative);
+        let io_error = |source| ManifestError::Io { path: full_path.clone(), source };
+        for entry in fs::read_dir(&full_path).map_err(io_error)? {
+            let entry = entry.map_err(io_error)?;
+            let file_type = entry.file_type().map_err(io_error)?;
+            let path = relative.join(entry.file_name());
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Signs every regular file below `dir`, with paths recorded relative to `dir`.
+pub fn sign_directory(dir: &Path, secret_key: &sig::SecretKey, algorithm: Algorithm) -> Result<SignedManifest, ManifestError> {
+    sign_under(dir, walk_directory(dir)?, None, secret_key, algorithm)
+}
+
+/// Checks the manifest signature, then compares it with every regular file below `dir` and
+/// returns all differences, sorted by path. An empty list means the directory matches.
+///
+/// Fails with [`ManifestError::InvalidSignature`] before touching the directory if the
+/// signature does not verify.
+pub fn verify_directory(
+    manifest: &SignedManifest,
+    public_key: &sig::PublicKey,
+    dir: &Path,
+) -> Result<Vec<ManifestDiff>, ManifestError> {
+    if !signature_valid(manifest, public_key)? {
+        return Err(ManifestError::InvalidSignature);
+    }
+
+    let mut expected: BTreeMap<&Path, [u8; 32]> =
+        manifest.entries.iter().map(|entry| (entry.path.as_path(), entry.sha256)).collect();
+    let on_disk = walk_directory(dir)?;
+    let progress = Progress::new(on_disk.len(), "Verifying");
+    let mut diffs = Vec::new();
+    for path in on_disk {
+        let Some(expected_hash) = expected.remove(path.as_path()) else {
+            diffs.push(ManifestDiff::Added(path));
+            continue;
+        };
+        progress.start_file(&path);
+        let full_path = dir.join(&path);
+        let actual_hash = hash_file(&full_path).map_err(|source| ManifestError::Io { path: full_path, source })?;
+        progress.finish_file();
+        if actual_hash != expected_hash {
+            diffs.push(ManifestDiff::Modified { path, expected_hash, actual_hash });
+        }
+    }
+    progress.finish();
+
+    diffs.extend(expected.into_keys().map(|path| ManifestDiff::Removed(path.to_path_buf())));
+    diffs.sort_by(|a, b| a.path().cmp(b.path()));
+    Ok(diffs)
+}
+
+/// A signed manifest encrypted to a recipient, so the file list itself stays confidential.
+#[derive(Debug, Clone)]
+pub struct SealedManifest {
+    pub envelope: Envelope,
+}
+
+fn manifest_to_json(manifest: &SignedManifest) -> Result<Vec<u8>, ManifestError> {
+    let mut document = json!({
+        "algorithm": algorithm_name(manifest.algorithm)?,
+        "files": files_json(&manifest.entries),
+        "signature": hex::encode(&manifest.signature),
+    });
+    if let Some(recipient_key_id) = manifest.recipient_key_id {
+        document["recipient"] = json!(hex::encode(recipient_key_id));
+    }
+    Ok(document.to_string().into_bytes())
+}
+
+fn manifest_from_json(bytes: &[u8]) -> Option<SignedManifest> {
+    let document: Value = serde_json::from_slice(bytes).ok()?;
+    let algorithm = AlgorithmId::from_name(document["algorithm"].as_str()?)?.to_sig()?;
+    let entries = document["files"]
+        .as_array()?
+        .iter()
+        .map(|file| {
+            let sha256 = hex::decode(file["sha256"].as_str()?).ok()?.try_into().ok()?;
+            Some(ManifestEntry { path: PathBuf::from(file["path"].as_str()?), sha256 })
+        })
+        .collect::<Option<_>>()?;
+    let recipient_key_id = match &document["recipient"] {
+        Value::Null => None,
+        recipient => Some(hex::decode(recipient.as_str()?).ok()?.try_into().ok()?),
+    };
+    let signature = hex::decode(document["signature"].as_str()?).ok()?;
+    Some(SignedManifest { algorithm, recipient_key_id, entries, signature })
+}
+
+/// Envelope purpose of sealed manifests, so no other envelope for the same recipient key is
+/// accepted as one.
+const SEALED_MANIFEST_PURPOSE: &str = "quantova-signed-manifest";
+
+/// Signs every file below `dir` with the signer's key, then encrypts the signed manifest to
+/// the recipient's KEM public key (see [`envelope::seal`]). The recipient's key ID is signed
+/// along with the files.
+pub fn seal_directory(
+    dir: &Path,
+    recipient: &kem::PublicKey,
+    kem_algorithm: kem::Algorithm,
+    cipher: Aead,
+    signer: &sig::SecretKey,
+    sig_algorithm: Algorithm,
+) -> Result<SealedManifest, ManifestError> {
+    let manifest = sign_under(dir, walk_directory(dir)?, Some(key_id(recipient.as_ref())), signer, sig_algorithm)?;
+    let envelope = envelope::seal(&manifest_to_json(&manifest)?, recipient, kem_algorithm, cipher, SEALED_MANIFEST_PURPOSE)?;
+    Ok(SealedManifest { envelope })
+}
+
+/// Decrypts a sealed manifest, verifies its signature against the signer's public key, and
+/// compares it with `dir` (see [`verify_directory`]). A manifest the signer addressed to a
+/// key other than `recipient_public_key` fails with [`ManifestError::RecipientMismatch`].
+pub fn unseal_directory(
+    sealed: &SealedManifest,
+    recipient_public_key: &kem::PublicKey,
+    recipient_secret_key: &kem::SecretKey,
+    signer: &sig::PublicKey,
+    dir: &Path,
+) -> Result<Vec<ManifestDiff>, ManifestError> {
+    let plaintext = envelope::open_expecting(&sealed.envelope, recipient_secret_key, SEALED_MANIFEST_PURPOSE)?;
+    let manifest = manifest_from_json(&plaintext).ok_or(ManifestError::Malformed)?;
+    if manifest.recipient_key_id != Some(key_id(recipient_public_key.as_ref())) {
+        return Err(ManifestError::RecipientMismatch);
+    }
+    verify_directory(&manifest, signer, dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oqs::kem::Kem;
+
+    const SIG: Algorithm = Algorithm::Dilithium2;
+    const KEM: kem::Algorithm = kem::Algorithm::MlKem768;
+
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quantova-manifest-test-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("ledger.txt"), "block 1\n").unwrap();
+        fs::write(dir.join("nested").join("peers.txt"), "node-a\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn signed_payload_names_the_algorithm_and_uses_slash_paths() {
+        let entries = [ManifestEntry { path: Path::new("nested").join("peers.txt"), sha256: [7; 32] }];
+        let payload = String::from_utf8(manifest_payload(SIG, None, &entries).unwrap()).unwrap();
+        assert!(payload.contains("\"algorithm\":\"Dilithium2\""), "{}", payload);
+        assert!(payload.contains("\"path\":\"nested/peers.txt\""), "{}", payload);
+        assert_eq!(portable_path(Path::new("/srv/./data/../ledger.txt")), "/srv/data/../ledger.txt");
+    }
+
+    #[test]
+    fn a_forwarded_sealed_manifest_is_refused() {
+        let dir = scratch_dir("forwarded");
+        let sig = Sig::new(SIG).unwrap();
+        let (signer_public_key, signer_secret_key) = sig.keypair().unwrap();
+        let kem = Kem::new(KEM).unwrap();
+        let (alice_public_key, alice_secret_key) = kem.keypair().unwrap();
+        let (bob_public_key, bob_secret_key) = kem.keypair().unwrap();
+
+        let sealed = seal_directory(&dir, &alice_public_key, KEM, Aead::Aes256Gcm, &signer_secret_key, SIG).unwrap();
+        let diffs = unseal_directory(&sealed, &alice_public_key, &alice_secret_key, &signer_public_key, &dir).unwrap();
+        assert!(diffs.is_empty());
+
+        // Alice decrypts the signed manifest and re-encrypts it to Bob unchanged
+        let plaintext = envelope::open(&sealed.envelope, &alice_secret_key).unwrap();
+        let envelope = envelope::seal(&plaintext, &bob_public_key, KEM, Aead::Aes256Gcm, SEALED_MANIFEST_PURPOSE).unwrap();
+        let forwarded = SealedManifest { envelope };
+        assert!(matches!(
+            unseal_directory(&forwarded, &bob_public_key, &bob_secret_key, &signer_public_key, &dir),
+            Err(ManifestError::RecipientMismatch)
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn directory_drift_reports_each_kind_of_difference() {
+        let dir = scratch_dir("drift");
+        let sig = Sig::new(SIG).unwrap();
+        let (public_key, secret_key) = sig.keypair().unwrap();
+        let manifest = sign_directory(&dir, &secret_key, SIG).unwrap();
+        assert!(verify_directory(&manifest, &public_key, &dir).unwrap().is_empty());
+
+        let expected_hash = manifest.entries.iter().find(|entry| entry.path == Path::new("ledger.txt")).unwrap().sha256;
+        fs::write(dir.join("ledger.txt"), "block 1\nblock 2\n").unwrap();
+        fs::write(dir.join("nested").join("intruder.txt"), "not signed\n").unwrap();
+        fs::remove_file(dir.join("nested").join("peers.txt")).unwrap();
+        let actual_hash = hash_file(&dir.join("ledger.txt")).unwrap();
+        assert_eq!(
+            verify_directory(&manifest, &public_key, &dir).unwrap(),
+            [
+                ManifestDiff::Modified { path: PathBuf::from("ledger.txt"), expected_hash, actual_hash },
+                ManifestDiff::Added(Path::new("nested").join("intruder.txt")),
+                ManifestDiff::Removed(Path::new("nested").join("peers.txt")),
+            ]
+        );
+
+        let mut forged = manifest.clone();
+        forged.entries[0].sha256 = actual_hash;
+        assert!(matches!(verify_directory(&forged, &public_key, &dir), Err(ManifestError::InvalidSignature)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn verify_files_reports_modified_and_missing_files() {
+        let dir = scratch_dir("verify-files");
+        let sig = Sig::new(SIG).unwrap();
+        let (public_key, secret_key) = sig.keypair().unwrap();
+        let files = [dir.join("ledger.txt"), dir.join("nested").join("peers.txt")];
+        let signed = sign_files(&files, &secret_key, SIG).unwrap();
+        assert!(verify_files(&signed, &public_key).unwrap().is_ok());
+
+        fs::write(&files[0], "block 1\nblock 2\n").unwrap();
+        fs::remove_file(&files[1]).unwrap();
+        let report = verify_files(&signed, &public_key).unwrap();
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(report.mismatches, [(files[0].clone(), FileStatus::Modified), (files[1].clone(), FileStatus::Missing)]);
+    }
+
+    #[test]
+    fn verify_files_fails_on_a_bad_signature() {
+        let dir = scratch_dir("verify-files-signature");
+        let sig = Sig::new(SIG).unwrap();
+        let (public_key, secret_key) = sig.keypair().unwrap();
+        let (other_public_key, _) = sig.keypair().unwrap();
+        let files = [dir.join("ledger.txt")];
+        let mut signed = sign_files(&files, &secret_key, SIG).unwrap();
+
+        let wrong_key = verify_files(&signed, &other_public_key);
+        signed.signature[0] ^= 1;
+        let tampered = verify_files(&signed, &public_key);
+        let _ = fs::remove_dir_all(&dir);
+        assert!(matches!(wrong_key, Err(ManifestError::InvalidSignature)));
+        assert!(matches!(tampered, Err(ManifestError::InvalidSignature)));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::algorithm::AlgorithmId;
+use oqs::sig::Sig;
+use std::fmt;
+
+/// Why a single signature in a [`MultiSig`] did not verify.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryFailure {
+    /// Not a signature algorithm, or not enabled in this liboqs build.
+    UnsupportedAlgorithm,
+    MalformedPublicKey,
+    BadSignature,
+}
+
+impl fmt::Display for EntryFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryFailure::UnsupportedAlgorithm => write!(f, "unsupported signature algorithm"),
+            EntryFailure::MalformedPublicKey => write!(f, "malformed public key"),
+            EntryFailure::BadSignature => write!(f, "signature does not verify"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MultiSigError {
+    Empty,
+    /// The entry at `index` failed; returned by [`MultiSig::verify_all`].
+    EntryFailed { index: usize, failure: EntryFailure },
+    /// `required` is zero or larger than the number of signatures.
+    InvalidThreshold { required: usize, signatures: usize },
+    /// Fewer than `required` signatures verified; `failed` lists the indices of the others.
+    BelowThreshold { valid: usize, required: usize, failed: Vec<usize> },
+}
+
+impl fmt::Display for MultiSigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultiSigError::Empty => write!(f, "no signatures to verify"),
+            MultiSigError::EntryFailed { index, failure } => write!(f, "signature {}: {}", index, failure),
+            MultiSigError::InvalidThreshold { required, signatures } => {
+                write!(f, "cannot require {} of {} signatures", required, signatures)
+            }
+            MultiSigError::BelowThreshold { valid, required, failed } => {
+                write!(f, "only {} of the required {} signatures are valid (failed: {:?})", valid, required, failed)
+            }
+        }
+    }
+}
+
+/// One signer's contribution: the algorithm, their public key and their signature.
+#[derive(Debug, Clone)]
+pub struct MultiSigEntry {
+    pub algorithm: AlgorithmId,
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl MultiSigEntry {
+    fn verify(&self, message: &[u8]) -> Result<(), EntryFailure> {
+        let sig = self
+            .algorithm
+            .to_sig()
+            .and_then(|algorithm| Sig::new(algorithm).ok())
+            .ok_or(EntryFailure::UnsupportedAlgorithm)?;
+        let public_key = sig.public_key_from_bytes(&self.public_key).ok_or(EntryFailure::MalformedPublicKey)?;
+        let signature = sig.signature_from_bytes(&self.signature).ok_or(EntryFailure::BadSignature)?;
+        sig.verify(message, signature, public_key).map_err(|_| EntryFailure::BadSignature)
+    }
+}
+
+/// Independent signatures over the same message, e.g. for multi-party attestation.
+///
+/// Unlike the threshold demo, every signer holds their own complete key pair and the
+/// signers may use different algorithms.
+#[derive(Debug, Clone, Default)]
+pub struct MultiSig {
+    entries: Vec<MultiSigEntry>,
+}
+
+impl MultiSig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, algorithm: AlgorithmId, public_key: Vec<u8>, signature: Vec<u8>) {
+        self.entries.push(MultiSigEntry { algorithm, public_key, signature });
+    }
+
+    /// Requires every signature to verify over `message`, reporting the first that does not.
+    pub fn verify_all(&self, message: &[u8]) -> Result<(), MultiSigError> {
+        if self.entries.is_empty() {
+            return Err(MultiSigError::Empty);
+        }
+        for (index, entry) in self.entries.iter().enumerate() {
+            entry.verify(message).map_err(|failure| MultiSigError::EntryFailed { index, failure })?;
+        }
+        Ok(())
+    }
+
+    /// Succeeds if at least `required` signatures verify over `message`, returning how many
+    /// did. Every signature is checked, so the error lists all failures.
+    pub fn verify_threshold_of(&self, message: &[u8], required: usize) -> Result<usize, MultiSigError> {
+        if required == 0 || required > self.entries.len() {
+            return Err(MultiSigError::InvalidThreshold { required, signatures: self.entries.len() });
+        }
+        let failed: Vec<usize> = (0..self.entries.len())
+            .filter(|&index| self.entries[index].verify(message).is_err())
+            .collect();
+        let valid = self.entries.len() - failed.len();
+        if valid < required {
+            return Err(MultiSigError::BelowThreshold { valid, required, failed });
+        }
+        Ok(valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = b"attested message";
+
+    /// Three attesters over `MESSAGE`; the entries at `bad` sign a different message instead.
+    fn attestations(bad: &[usize]) -> MultiSig {
+        let mut multisig = MultiSig::new();
+        let algorithms = [AlgorithmId::Dilithium2, AlgorithmId::Falcon512, AlgorithmId::MlDsa65];
+        for (index, algorithm) in algorithms.into_iter().enumerate() {
+            let sig = Sig::new(algorithm.to_sig().unwrap()).unwrap();
+            let (public_key, secret_key) = sig.keypair().unwrap();
+            let message: &[u8] = if bad.contains(&index) { b"something else" } else { MESSAGE };
+            let signature = sig.sign(message, &secret_key).unwrap();
+            multisig.push(algorithm, public_key.into_vec(), signature.into_vec());
+        }
+        multisig
+    }
+
+    #[test]
+    fn verify_all_reports_the_first_failing_entry() {
+        assert!(attestations(&[]).verify_all(MESSAGE).is_ok());
+        assert!(matches!(
+            attestations(&[1, 2]).verify_all(MESSAGE),
+            Err(MultiSigError::EntryFailed { index: 1, failure: EntryFailure::BadSignature })
+        ));
+    }
+
+    #[test]
+    fn below_threshold_lists_every_failed_entry() {
+        assert_eq!(attestations(&[1]).verify_threshold_of(MESSAGE, 2).unwrap(), 2);
+        match attestations(&[0, 2]).verify_threshold_of(MESSAGE, 2) {
+            Err(MultiSigError::BelowThreshold { valid: 1, required: 2, failed }) => assert_eq!(failed, [0, 2]),
+            other => panic!("expected BelowThreshold, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn thresholds_outside_one_to_n_are_rejected() {
+        let multisig = attestations(&[]);
+        assert!(matches!(
+            multisig.verify_threshold_of(MESSAGE, 0),
+            Err(MultiSigError::InvalidThreshold { required: 0, signatures: 3 })
+        ));
+        assert!(matches!(
+            multisig.verify_threshold_of(MESSAGE, 4),
+            Err(MultiSigError::InvalidThreshold { required: 4, signatures: 3 })
+        ));
+        assert_eq!(multisig.verify_threshold_of(MESSAGE, 3).unwrap(), 3);
+    }
+
+    #[test]
+    fn an_empty_signer_set_verifies_nothing() {
+        let empty = MultiSig::new();
+        assert!(matches!(empty.verify_all(MESSAGE), Err(MultiSigError::Empty)));
+        assert!(matches!(
+            empty.verify_threshold_of(MESSAGE, 1),
+            Err(MultiSigError::InvalidThreshold { required: 1, signatures: 0 })
+        ));
+    }
+}
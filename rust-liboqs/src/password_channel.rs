@@ -0,0 +1,238 @@
+// A password-authenticated channel built on a post-quantum KEM.
+//
+// Both parties know a shared password. The initiator encapsulates a shared secret to the
+// responder's KEM public key, and each side derives the session key with HKDF-SHA256 from
+// the KEM shared secret, mixing the password into the HKDF `info`. An attacker who can
+// break the KEM still needs the password, and one who knows the password still needs to
+// break the KEM. Each side then sends an HMAC key confirmation tag, so a password mismatch
+// is detected before the channel is used.
+//
+// This is an illustrative pattern, NOT a formally proven PAKE. In particular, an attacker
+// who breaks the KEM can test password guesses offline against a confirmation tag. Use a
+// vetted PAKE such as CPace or OPAQUE where that matters.
+
+use crate::keys;
+use oqs::kem::{self, Kem};
+use ring::hkdf::{self, HKDF_SHA256};
+use ring::hmac;
+use std::fmt;
+use zeroize::Zeroizing;
+
+/// HKDF info prefix binding derived keys to this construction.
+const CHANNEL_INFO: &[u8] = b"Quantova password channel v1";
+const SESSION_KEY_LEN: usize = 32;
+const CONFIRMATION_KEY_LEN: usize = 32;
+
+#[derive(Debug)]
+pub enum ChannelError {
+    Backend(oqs::Error),
+    /// The KEM ciphertext does not have the size the algorithm expects.
+    MalformedCiphertext,
+    /// The peer's key confirmation tag did not verify: the passwords differ or the
+    /// handshake was modified.
+    ConfirmationFailed,
+}
+
+impl fmt::Display for ChannelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelError::Backend(e) => write!(f, "KEM backend error: {}", e),
+            ChannelError::MalformedCiphertext => write!(f, "malformed KEM ciphertext"),
+            ChannelError::ConfirmationFailed => write!(f, "key confirmation failed: password mismatch or modified handshake"),
+        }
+    }
+}
+
+impl From<oqs::Error> for ChannelError {
+    fn from(e: oqs::Error) -> Self {
+        ChannelError::Backend(e)
+    }
+}
+
+/// Which side of the handshake a confirmation tag comes from, so one side's tag cannot be
+/// reflected back as the other's.
+#[derive(Debug, Clone, Copy)]
+enum Role {
+    Initiator,
+    Responder,
+}
+
+impl Role {
+    fn label(self) -> &'static [u8] {
+        match self {
+            Role::Initiator => b"initiator confirms",
+            Role::Responder => b"responder confirms",
+        }
+    }
+}
+
+/// The initiator's first (and only) message.
+#[derive(Debug, Clone)]
+pub struct Initiation {
+    pub kem: kem::Algorithm,
+    pub encapsulated_key: Vec<u8>,
+    /// Initiator's key confirmation tag over the encapsulated key.
+    pub confirmation: Vec<u8>,
+}
+
+struct OutputLen(usize);
+
+impl hkdf::KeyType for OutputLen {
+    fn len(&self) -> usize {
+        self.0
+    }
+}
+
+/// Keys derived by one side of the handshake.
+pub struct ChannelKeys {
+    session_key: Zeroizing<Vec<u8>>,
+    confirmation_key: hmac::Key,
+    encapsulated_key: Vec<u8>,
+}
+
+impl ChannelKeys {
+    fn derive(shared_secret: &kem::SharedSecret, password: &[u8], encapsulated_key: &[u8]) -> Self {
+        let prk = hkdf::Salt::new(HKDF_SHA256, &[]).extract(shared_secret.as_ref());
+        let password_len = (password.len() as u32).to_be_bytes();
+        let info = [CHANNEL_INFO, &password_len, password];
+        let mut okm = Zeroizing::new(vec![0u8; SESSION_KEY_LEN + CONFIRMATION_KEY_LEN]);
+        prk.expand(&info, OutputLen(okm.len()))
+            .and_then(|okm_material| okm_material.fill(&mut okm))
+            .expect("64 bytes is a valid HKDF-SHA256 output length");
+        Self {
+            session_key: Zeroizing::new(okm[..SESSION_KEY_LEN].to_vec()),
+            confirmation_key: hmac::Key::new(hmac::HMAC_SHA256, &okm[SESSION_KEY_LEN..]),
+            encapsulated_key: encapsulated_key.to_vec(),
+        }
+    }
+
+    /// The shared session key. Keep the borrow short and never log it.
+    pub fn session_key(&self) -> &[u8] {
+        &self.session_key
+    }
+
+    /// Short fingerprint of the session key, safe to print.
+    pub fn fingerprint(&self) -> String {
+        keys::fingerprint(&self.session_key)
+    }
+
+    fn confirmation(&self, role: Role) -> Vec<u8> {
+        let tag = hmac::sign(&self.confirmation_key, &[role.label(), &self.encapsulated_key].concat());
+        tag.as_ref().to_vec()
+    }
+
+    fn check_confirmation(&self, role: Role, tag: &[u8]) -> Result<(), ChannelError> {
+        hmac::verify(&self.confirmation_key, &[role.label(), &self.encapsulated_key].concat(), tag)
+            .map_err(|_| ChannelError::ConfirmationFailed)
+    }
+
+    /// Checks the responder's confirmation tag on the initiator's side.
+    pub fn confirm_responder(&self, tag: &[u8]) -> Result<(), ChannelError> {
+        self.check_confirmation(Role::Responder, tag)
+    }
+}
+
+/// Starts a handshake with the responder's KEM public key and returns the message to send
+/// along with the initiator's keys.
+pub fn initiate(
+    password: &[u8],
+    responder: &kem::PublicKey,
+    algorithm: kem::Algorithm,
+) -> Result<(Initiation, ChannelKeys), ChannelError> {
+    let kem = Kem::new(algorithm)?;
+    let (encapsulated_key, shared_secret) = kem.encapsulate(responder)?;
+    let keys = ChannelKeys::derive(&shared_secret, password, encapsulated_key.as_ref());
+    let initiation = Initiation {
+        kem: algorithm,
+        encapsulated_key: encapsulated_key.into_vec(),
+        confirmation: keys.confirmation(Role::Initiator),
+    };
+    Ok((initiation, keys))
+}
+
+/// Decapsulates the initiation and derives the responder's keys without checking the
+/// initiator's confirmation. Use [`respond`] unless the keys are needed for inspection.
+pub fn derive_responder_keys(
+    password: &[u8],
+    initiation: &Initiation,
+    responder: &kem::SecretKey,
+) -> Result<ChannelKeys, ChannelError> {
+    let kem = Kem::new(initiation.kem)?;
+    let encapsulated_key = kem
+        .ciphertext_from_bytes(&initiation.encapsulated_key)
+        .ok_or(ChannelError::MalformedCiphertext)?;
+    let shared_secret = kem.decapsulate(responder, encapsulated_key)?;
+    Ok(ChannelKeys::derive(&shared_secret, password, &initiation.encapsulated_key))
+}
+
+/// Completes the responder's side: derives the keys, checks the initiator's confirmation and
+/// returns the responder's own confirmation tag to send back.
+pub fn respond(
+    password: &[u8],
+    initiation: &Initiation,
+    responder: &kem::SecretKey,
+) -> Result<(ChannelKeys, Vec<u8>), ChannelError> {
+    let keys = derive_responder_keys(password, initiation, responder)?;
+    keys.check_confirmation(Role::Initiator, &initiation.confirmation)?;
+    let confirmation = keys.confirmation(Role::Responder);
+    Ok((keys, confirmation))
+}
+
+/// Runs the handshake with matching and mismatched passwords over `algorithm`.
+pub fn password_channel_demo(algorithm: kem::Algorithm) -> Result<(), ChannelError> {
+    let kem = Kem::new(algorithm)?;
+    let (responder_public_key, responder_secret_key) = kem.keypair()?;
+
+    let (initiation, initiator_keys) = initiate(b"correct horse battery staple", &responder_public_key, algorithm)?;
+    let (responder_keys, responder_confirmation) =
+        respond(b"correct horse battery staple", &initiation, &responder_secret_key)?;
+    initiator_keys.confirm_responder(&responder_confirmation)?;
+    println!(
+        "   - Matching passwords: session keys {} / {}, {}",
+        initiator_keys.fingerprint(),
+        responder_keys.fingerprint(),
+        if initiator_keys.session_key() == responder_keys.session_key() { "✅ confirmed" } else { "❌ differ" }
+    );
+
+    let (initiation, initiator_keys) = initiate(b"correct horse battery staple", &responder_public_key, algorithm)?;
+    let wrong_keys = derive_responder_keys(b"correct horse battery stapler", &initiation, &responder_secret_key)?;
+    let outcome = match respond(b"correct horse battery stapler", &initiation, &responder_secret_key) {
+        Ok(_) => "❌ confirmation accepted".to_string(),
+        Err(e) => format!("✅ {}", e),
+    };
+    println!(
+        "   - Mismatched passwords: session keys {} / {}, {}",
+        initiator_keys.fingerprint(),
+        wrong_keys.fingerprint(),
+        outcome
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALGORITHM: kem::Algorithm = kem::Algorithm::MlKem768;
+
+    #[test]
+    fn matching_passwords_agree_on_a_confirmed_key() {
+        let (public_key, secret_key) = Kem::new(ALGORITHM).unwrap().keypair().unwrap();
+        let (initiation, initiator_keys) = initiate(b"correct horse battery staple", &public_key, ALGORITHM).unwrap();
+        let (responder_keys, confirmation) = respond(b"correct horse battery staple", &initiation, &secret_key).unwrap();
+        initiator_keys.confirm_responder(&confirmation).unwrap();
+        assert_eq!(initiator_keys.session_key(), responder_keys.session_key());
+    }
+
+    #[test]
+    fn a_wrong_password_fails_confirmation() {
+        let (public_key, secret_key) = Kem::new(ALGORITHM).unwrap().keypair().unwrap();
+        let (initiation, initiator_keys) = initiate(b"correct horse battery staple", &public_key, ALGORITHM).unwrap();
+        let wrong_keys = derive_responder_keys(b"correct horse battery stapler", &initiation, &secret_key).unwrap();
+        assert_ne!(initiator_keys.session_key(), wrong_keys.session_key());
+        assert!(matches!(
+            respond(b"correct horse battery stapler", &initiation, &secret_key),
+            Err(ChannelError::ConfirmationFailed)
+        ));
+    }
+}
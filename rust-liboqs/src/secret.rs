@@ -0,0 +1,34 @@
+use crate::keys::fingerprint;
+use oqs::sig::SecretKey;
+use std::fmt;
+use zeroize::Zeroize;
+
+/// Owns a signature secret key, redacts it from `Debug` output and zeroes its bytes on drop.
+pub struct ProtectedSecretKey {
+    inner: Option<SecretKey>,
+}
+
+impl ProtectedSecretKey {
+    pub fn new(secret_key: SecretKey) -> Self {
+        Self { inner: Some(secret_key) }
+    }
+
+    /// Borrows the wrapped key for signing. Keep the borrow short and never log it.
+    pub fn expose(&self) -> &SecretKey {
+        self.inner.as_ref().expect("Secret key is only taken on drop.")
+    }
+}
+
+impl fmt::Debug for ProtectedSecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretKey(<redacted>, fingerprint {})", fingerprint(self.expose().as_ref()))
+    }
+}
+
+impl Drop for ProtectedSecretKey {
+    fn drop(&mut self) {
+        if let Some(secret_key) = self.inner.take() {
+            secret_key.into_vec().zeroize();
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::algorithm::AlgorithmId;
+use oqs::kem::Kem;
+use oqs::sig::Sig;
+use std::fmt;
+
+const SELF_TEST_MESSAGE: &[u8] = b"Quantova power-on self-test";
+
+/// Why an algorithm failed its self-test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelfTestFailure {
+    Backend(String),
+    /// A valid signature did not verify, or encapsulation and decapsulation disagreed.
+    RoundTrip,
+    /// A signature still verified after the message or signature was altered.
+    TamperedSignatureAccepted,
+    /// A modified ciphertext decapsulated to the original shared secret.
+    TamperedCiphertextAccepted,
+}
+
+impl fmt::Display for SelfTestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelfTestFailure::Backend(e) => write!(f, "backend error: {}", e),
+            SelfTestFailure::RoundTrip => write!(f, "round trip failed"),
+            SelfTestFailure::TamperedSignatureAccepted => write!(f, "tampered signature accepted"),
+            SelfTestFailure::TamperedCiphertextAccepted => write!(f, "tampered ciphertext accepted"),
+        }
+    }
+}
+
+impl From<oqs::Error> for SelfTestFailure {
+    fn from(e: oqs::Error) -> Self {
+        SelfTestFailure::Backend(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelfTestOutcome {
+    Passed,
+    /// Not enabled in this liboqs build.
+    Skipped,
+    Failed(SelfTestFailure),
+}
+
+/// Per-algorithm outcome of [`run_self_test`].
+#[derive(Debug, Clone)]
+pub struct SelfTestReport {
+    pub results: Vec<(AlgorithmId, SelfTestOutcome)>,
+}
+
+impl SelfTestReport {
+    pub fn count(&self, outcome: fn(&SelfTestOutcome) -> bool) -> usize {
+        self.results.iter().filter(|(_, result)| outcome(result)).count()
+    }
+}
+
+#[derive(Debug)]
+pub enum SelfTestError {
+    /// At least one algorithm failed; the report lists all outcomes.
+    Failed(SelfTestReport),
+    /// No algorithm the toolkit knows is enabled in the liboqs build.
+    NothingEnabled,
+}
+
+impl fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelfTestError::Failed(report) => {
+                write!(f, "self-test failed:")?;
+                for (id, outcome) in &report.results {
+                    if let SelfTestOutcome::Failed(failure) = outcome {
+                        write!(f, " {} ({})", id.liboqs_name(), failure)?;
+                    }
+                }
+                Ok(())
+            }
+            SelfTestError::NothingEnabled => write!(f, "self-test failed: no supported algorithm is enabled in liboqs"),
+        }
+    }
+}
+
+/// Sign/verify round trip, then flips a bit in the message and in the signature and expects
+/// both to be rejected.
+fn test_signature(algorithm: oqs::sig::Algorithm) -> Result<(), SelfTestFailure> {
+    let sig = Sig::new(algorithm)?;
+    let (public_key, secret_key) = sig.keypair()?;
+    let signature = sig.sign(SELF_TEST_MESSAGE, &secret_key)?;
+    if sig.verify(SELF_TEST_MESSAGE, &signature, &public_key).is_err() {
+        return Err(SelfTestFailure::RoundTrip);
+    }
+
+    let mut message = SELF_TEST_MESSAGE.to_vec();
+    message[0] ^= 1;
+    if sig.verify(&message, &signature, &public_key).is_ok() {
+        return Err(SelfTestFailure::TamperedSignatureAccepted);
+    }
+    let mut signature_bytes = signature.into_vec();
+    signature_bytes[0] ^= 1;
+    let tampered = sig.signature_from_bytes(&signature_bytes);
+    if tampered.is_some_and(|tampered| sig.verify(SELF_TEST_MESSAGE, tampered, &public_key).is_ok()) {
+        return Err(SelfTestFailure::TamperedSignatureAccepted);
+    }
+    Ok(())
+}
+
+/// Encapsulate/decapsulate round trip, then flips a bit in the ciphertext. ML-KEM uses
+/// implicit rejection, so the tampered ciphertext must yield a different shared secret
+/// rather than an error.
+fn test_kem(algorithm: oqs::kem::Algorithm) -> Result<(), SelfTestFailure> {
+    let kem = Kem::new(algorithm)?;
+    let (public_key, secret_key) = kem.keypair()?;
+    let (ciphertext, shared_secret) = kem.encapsulate(&public_key)?;
+    if kem.decapsulate(&secret_key, &ciphertext)?.as_ref() != shared_secret.as_ref() {
+        return Err(SelfTestFailure::RoundTrip);
+    }
+
+    let mut ciphertext_bytes = ciphertext.into_vec();
+    ciphertext_bytes[0] ^= 1;
+    let tampered = kem.ciphertext_from_bytes(&ciphertext_bytes).ok_or(SelfTestFailure::RoundTrip)?;
+    if kem.decapsulate(&secret_key, tampered)?.as_ref() == shared_secret.as_ref() {
+        return Err(SelfTestFailure::TamperedCiphertextAccepted);
+    }
+    Ok(())
+}
+
+/// Power-on self-test: runs an internal-consistency round trip and tamper check for every
+/// algorithm in [`AlgorithmId::ALL`] that is enabled in liboqs, SPHINCS+ included. SPHINCS+
+/// signing is slow, so the toolkit runs this once when the interactive menu starts rather
+/// than on every command. Returns the per-algorithm report, or an error carrying it if any
+/// algorithm failed.
+pub fn run_self_test() -> Result<SelfTestReport, SelfTestError> {
+    let results: Vec<_> = AlgorithmId::ALL
+        .into_iter()
+        .map(|id| {
+            let result = match (id.to_sig(), id.to_kem()) {
+                (Some(algorithm), _) if algorithm.is_enabled() => test_signature(algorithm),
+                (_, Some(algorithm)) if algorithm.is_enabled() => test_kem(algorithm),
+                _ => return (id, SelfTestOutcome::Skipped),
+            };
+            (id, result.map_or_else(SelfTestOutcome::Failed, |()| SelfTestOutcome::Passed))
+        })
+        .collect();
+
+    let report = SelfTestReport { results };
+    if report.count(|outcome| matches!(outcome, SelfTestOutcome::Failed(_))) > 0 {
+        return Err(SelfTestError::Failed(report));
+    }
+    if report.count(|outcome| *outcome == SelfTestOutcome::Passed) == 0 {
+        return Err(SelfTestError::NothingEnabled);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_enabled_algorithm_passes() {
+        let report = run_self_test().unwrap();
+        assert_eq!(report.results.len(), AlgorithmId::ALL.len());
+        for (id, outcome) in &report.results {
+            let enabled = id.to_sig().map_or_else(|| id.to_kem().is_some_and(|kem| kem.is_enabled()), |sig| sig.is_enabled());
+            let expected = if enabled { SelfTestOutcome::Passed } else { SelfTestOutcome::Skipped };
+            assert_eq!(*outcome, expected, "{}", id.liboqs_name());
+        }
+        assert!(report.results.iter().any(|(id, _)| *id == AlgorithmId::SphincsSha2128s));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::keys::key_fingerprint;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Enforces strictly increasing sequence numbers per signer.
+///
+/// The highest accepted sequence number for each public-key fingerprint is written to disk
+/// after every accepted message, so a restart cannot be used to replay old sequence numbers.
+pub struct SequenceGuard {
+    path: PathBuf,
+    highest: HashMap<String, u64>,
+}
+
+impl SequenceGuard {
+    /// Loads the guard state from `path`. A missing file starts with an empty map.
+    ///
+    /// Each line of the file holds a fingerprint and its highest accepted sequence number.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut highest = HashMap::new();
+        for (number, line) in contents.lines().enumerate() {
+            let parsed = line
+                .split_once(' ')
+                .and_then(|(fingerprint, seq)| Some((fingerprint.to_string(), seq.parse::<u64>().ok()?)));
+            let (fingerprint, seq) = parsed.ok_or_else(|| {
+                io::Error::new(ErrorKind::InvalidData, format!("malformed sequence entry on line {}", number + 1))
+            })?;
+            highest.insert(fingerprint, seq);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            highest,
+        })
+    }
+
+    /// Returns the highest sequence number accepted so far for `public_key`.
+    pub fn last_accepted(&self, public_key: &[u8]) -> Option<u64> {
+        self.highest.get(&key_fingerprint(public_key)).copied()
+    }
+
+    /// Accepts `seq` only if it is greater than every sequence number previously accepted
+    /// for `public_key`. If the new state cannot be persisted the sequence is rejected, so a
+    /// later restart never forgets an accepted message.
+    pub fn accept(&mut self, public_key: &[u8], seq: u64) -> bool {
+        let fingerprint = key_fingerprint(public_key);
+        let previous = self.highest.get(&fingerprint).copied();
+        if previous.is_some_and(|last| seq <= last) {
+            return false;
+        }
+
+        self.highest.insert(fingerprint.clone(), seq);
+        if self.persist().is_err() {
+            match previous {
+                Some(last) => self.highest.insert(fingerprint, last),
+                None => self.highest.remove(&fingerprint),
+            };
+            return false;
+        }
+        true
+    }
+
+    /// Writes the map to a temporary file and renames it over the state file, so a crash
+    /// mid-write never leaves a truncated map behind.
+    fn persist(&self) -> io::Result<()> {
+        let mut contents = String::new();
+        for (fingerprint, seq) in &self.highest {
+            contents.push_str(&format!("{} {}\n", fingerprint, seq));
+        }
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quantova-sequence-test-{}-{}", std::process::id(), test));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("sequence_guard.txt")
+    }
+
+    #[test]
+    fn accepts_increasing_sequence_numbers_and_rejects_replays() {
+        let path = state_path("replay");
+        let mut guard = SequenceGuard::load(&path).unwrap();
+        assert!(guard.accept(b"alice", 1));
+        assert!(guard.accept(b"alice", 2));
+        assert!(!guard.accept(b"alice", 2));
+        assert!(!guard.accept(b"alice", 1));
+        assert!(guard.accept(b"bob", 1));
+        assert_eq!(guard.last_accepted(b"alice"), Some(2));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn state_survives_a_reload() {
+        let path = state_path("reload");
+        let mut guard = SequenceGuard::load(&path).unwrap();
+        assert!(guard.accept(b"alice", 7));
+        let mut reloaded = SequenceGuard::load(&path).unwrap();
+        assert_eq!(reloaded.last_accepted(b"alice"), Some(7));
+        assert!(!reloaded.accept(b"alice", 7));
+        assert_eq!(reloaded.last_accepted(b"bob"), None);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn malformed_state_files_are_rejected() {
+        let path = state_path("malformed");
+        fs::write(&path, "not-a-sequence-entry\n").unwrap();
+        assert_eq!(SequenceGuard::load(&path).err().map(|e| e.kind()), Some(ErrorKind::InvalidData));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+// Shamir secret sharing over GF(256), byte by byte. Each secret byte is the constant term of
+// a random polynomial of degree threshold - 1; share i holds x = i + 1 followed by every
+// polynomial evaluated at x. Any `threshold` shares interpolate back to the secret, fewer
+// reveal nothing about it.
+
+use crate::gf256;
+use rand::random;
+use std::collections::HashMap;
+use std::fmt;
+use zeroize::Zeroizing;
+
+/// Share x coordinates are single nonzero bytes, which caps the number of shares.
+pub const MAX_TOTAL_SHARES: usize = 255;
+
+/// Shares keyed by their 0-based index; share `i` starts with its x coordinate `i + 1`.
+pub type Shares = HashMap<usize, Zeroizing<Vec<u8>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShamirError {
+    /// The parameters do not satisfy 1 <= threshold <= total <= 255.
+    InvalidParameters { threshold: usize, total: usize },
+    /// Fewer shares than the threshold were supplied; Shamir sharing reveals nothing then.
+    InsufficientShares { required: usize, provided: usize },
+    /// A share is empty, has x = 0, repeats another share's x, or differs in length.
+    MalformedShare(usize),
+}
+
+impl fmt::Display for ShamirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShamirError::InvalidParameters { threshold, total } => write!(
+                f,
+                "a {}-of-{} sharing is invalid: need 1 <= threshold <= total <= {}",
+                threshold, total, MAX_TOTAL_SHARES
+            ),
+            ShamirError::InsufficientShares { required, provided } => {
+                write!(f, "{} shares are required to reconstruct the key, got {}", required, provided)
+            }
+            ShamirError::MalformedShare(index) => write!(f, "share {} is malformed", index),
+        }
+    }
+}
+
+/// Checks 1 <= threshold <= total <= [`MAX_TOTAL_SHARES`].
+pub fn validate_params(threshold: usize, total: usize) -> Result<(), ShamirError> {
+    if threshold == 0 || threshold > total || total > MAX_TOTAL_SHARES {
+        return Err(ShamirError::InvalidParameters { threshold, total });
+    }
+    Ok(())
+}
+
+/// Splits `secret` into `total` shares, any `threshold` of which reconstruct it.
+pub fn split(secret: &[u8], threshold: usize, total: usize) -> Result<Shares, ShamirError> {
+    validate_params(threshold, total)?;
+    let mut shares: Vec<Zeroizing<Vec<u8>>> = (1..=total)
+        .map(|x| {
+            let mut share = Zeroizing::new(Vec::with_capacity(1 + secret.len()));
+            share.push(x as u8);
+            share
+        })
+        .collect();
+    let mut coefficients = Zeroizing::new(vec![0u8; threshold]);
+    for &byte in secret {
+        coefficients[0] = byte;
+        for coefficient in &mut coefficients[1..] {
+            *coefficient = random();
+        }
+        for share in &mut shares {
+            let x = share[0];
+            share.push(gf256::eval_poly(coefficients.as_slice(), x));
+        }
+    }
+    Ok(shares.into_iter().enumerate().collect())
+}
+
+/// Combines shares made by [`split`] with Lagrange interpolation at x = 0. Shamir sharing
+/// cannot tell a wrong reconstruction from a right one: a modified share yields a different
+/// secret, not an error, so callers should check the result against something they trust.
+pub fn reconstruct(shares: &Shares, threshold: usize) -> Result<Zeroizing<Vec<u8>>, ShamirError> {
+    if shares.is_empty() || shares.len() < threshold {
+        return Err(ShamirError::InsufficientShares { required: threshold, provided: shares.len() });
+    }
+    let mut points: Vec<(usize, &[u8])> = shares.iter().map(|(&index, share)| (index, share.as_slice())).collect();
+    points.sort_by_key(|&(index, _)| index);
+    let share_len = points[0].1.len();
+    let mut xs = Vec::with_capacity(points.len());
+    for &(index, share) in &points {
+        if share.len() != share_len || share.len() < 2 || share[0] == 0 || xs.contains(&share[0]) {
+            return Err(ShamirError::MalformedShare(index));
+        }
+        xs.push(share[0]);
+    }
+
+    let mut column = Zeroizing::new(Vec::with_capacity(points.len()));
+    let secret = (1..share_len)
+        .map(|byte| {
+            column.clear();
+            column.extend(points.iter().map(|(_, share)| (share[0], share[byte])));
+            gf256::lagrange_interpolate(&column, 0)
+        })
+        .collect();
+    Ok(Zeroizing::new(secret))
+}
+
+/// Rotates shares without changing the secret: adds a fresh sharing of zero (a random
+/// polynomial of degree threshold - 1 with constant term 0) to every share. New shares
+/// interpolate to the same secret, but mixing old and new shares yields garbage.
+pub fn refresh(shares: &Shares, threshold: usize) -> Shares {
+    let mut refreshed = shares.clone();
+    let share_len = shares.values().map(|share| share.len()).max().unwrap_or(0);
+    let mut coefficients = Zeroizing::new(vec![0u8; threshold.max(1)]);
+    for byte in 1..share_len {
+        for coefficient in &mut coefficients[1..] {
+            *coefficient = random();
+        }
+        for share in refreshed.values_mut().filter(|share| byte < share.len()) {
+            let x = share[0];
+            share[byte] = gf256::add(share[byte], gf256::eval_poly(coefficients.as_slice(), x));
+        }
+    }
+    refreshed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::sample::subsequence;
+
+    fn secret() -> Vec<u8> {
+        (0..32).map(|_| random()).collect()
+    }
+
+    fn subset(shares: &Shares, indices: &[usize]) -> Shares {
+        indices.iter().map(|i| (*i, shares[i].clone())).collect()
+    }
+
+    #[test]
+    fn every_threshold_subset_reconstructs() {
+        let secret = secret();
+        let shares = split(&secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        for mask in (0u32..1 << 5).filter(|mask| mask.count_ones() == 3) {
+            let indices: Vec<usize> = (0..5).filter(|i| mask & (1 << i) != 0).collect();
+            assert_eq!(reconstruct(&subset(&shares, &indices), 3).unwrap().as_slice(), secret.as_slice());
+        }
+        assert_eq!(reconstruct(&shares, 3).unwrap().as_slice(), secret.as_slice());
+    }
+
+    #[test]
+    fn fewer_than_threshold_shares_are_refused() {
+        let shares = split(&secret(), 3, 5).unwrap();
+        assert_eq!(
+            reconstruct(&subset(&shares, &[0, 4]), 3),
+            Err(ShamirError::InsufficientShares { required: 3, provided: 2 })
+        );
+        assert_eq!(
+            reconstruct(&Shares::new(), 1),
+            Err(ShamirError::InsufficientShares { required: 1, provided: 0 })
+        );
+    }
+
+    #[test]
+    fn a_wrong_share_gives_a_wrong_secret() {
+        let secret = secret();
+        let mut shares = subset(&split(&secret, 3, 5).unwrap(), &[0, 1, 2]);
+        shares.get_mut(&1).unwrap()[7] ^= 0x01;
+        let reconstructed = reconstruct(&shares, 3).unwrap();
+        assert_eq!(reconstructed.len(), secret.len());
+        assert_ne!(reconstructed.as_slice(), secret.as_slice());
+    }
+
+    #[test]
+    fn invalid_parameters_and_malformed_shares_are_rejected() {
+        for (threshold, total) in [(0, 3), (4, 3), (2, 256)] {
+            assert_eq!(split(b"key", threshold, total), Err(ShamirError::InvalidParameters { threshold, total }));
+        }
+        let mut shares = split(&secret(), 2, 3).unwrap();
+        let duplicate = shares[&0].clone();
+        shares.insert(1, duplicate);
+        assert_eq!(reconstruct(&subset(&shares, &[0, 1]), 2), Err(ShamirError::MalformedShare(1)));
+        shares.get_mut(&2).unwrap().pop();
+        assert_eq!(reconstruct(&subset(&shares, &[0, 2]), 2), Err(ShamirError::MalformedShare(2)));
+    }
+
+    #[test]
+    fn refreshed_shares_keep_the_secret() {
+        let secret = secret();
+        let shares = split(&secret, 3, 5).unwrap();
+        let refreshed = refresh(&shares, 3);
+        assert_ne!(refreshed[&0], shares[&0]);
+        assert_eq!(reconstruct(&subset(&refreshed, &[1, 3, 4]), 3).unwrap().as_slice(), secret.as_slice());
+    }
+
+    /// A secret, a valid (threshold, total) pair, and a `threshold`-subset of the share
+    /// indices in random order.
+    fn sharing() -> impl Strategy<Value = (Vec<u8>, usize, usize, Vec<usize>)> {
+        (prop::collection::vec(any::<u8>(), 16..64), 1..=16usize)
+            .prop_flat_map(|(secret, total)| (Just(secret), 1..=total, Just(total)))
+            .prop_flat_map(|(secret, threshold, total)| {
+                let subset = subsequence((0..total).collect::<Vec<_>>(), threshold).prop_shuffle();
+                (Just(secret), Just(threshold), Just(total), subset)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn any_threshold_subset_in_any_order_reconstructs((secret, threshold, total, indices) in sharing()) {
+            let shares = split(&secret, threshold, total).unwrap();
+            let reconstructed = reconstruct(&subset(&shares, &indices), threshold).unwrap();
+            prop_assert_eq!(reconstructed.as_slice(), secret.as_slice());
+        }
+
+        #[test]
+        fn any_smaller_subset_fails((secret, threshold, total, mut indices) in sharing()) {
+            let shares = split(&secret, threshold, total).unwrap();
+            indices.pop();
+            let short = subset(&shares, &indices);
+            prop_assert_eq!(
+                reconstruct(&short, threshold),
+                Err(ShamirError::InsufficientShares { required: threshold, provided: threshold - 1 })
+            );
+            // Interpolating the short subset anyway lands on an unrelated value
+            if threshold > 1 {
+                let guess = reconstruct(&short, threshold - 1).unwrap();
+                prop_assert_ne!(guess.as_slice(), secret.as_slice());
+            }
+        }
+    }
+}
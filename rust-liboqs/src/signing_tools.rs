@@ -0,0 +1,184 @@
+// Demos of the signing utilities that sit around the signature schemes rather than
+// authenticating anyone: the verification cache, replay-proof sequence numbers, signed JSON,
+// key files, file manifests, signed event logs, multi-party attestation and bulk key
+// generation.
+
+use crate::authentication::public_keys_equal;
+use crate::algorithm::AlgorithmId;
+use crate::cancellation::{BatchError, CancellationToken};
+use crate::error::QuantovaError;
+use crate::json_sign;
+use crate::key_file::{self, KeyEncoding};
+use crate::keygen;
+use crate::keys::key_id;
+use crate::log_signer::{self, LogSigner};
+use crate::manifest;
+use crate::multisig::MultiSig;
+use crate::sequence_guard::SequenceGuard;
+use crate::verify_cache::{self, VerifyCache};
+use oqs::sig::{self, Algorithm, Sig};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+/// Signature scheme every utility demo signs with.
+const DEMO_ALGORITHM: Algorithm = Algorithm::Dilithium2;
+
+pub fn signing_tools() -> Result<(), QuantovaError> {
+    let sig = Sig::new(DEMO_ALGORITHM)?;
+    let (public_key, secret_key) = sig.keypair()?;
+    // Everything the demo writes goes under one scratch directory, removed at the end
+    let demo_dir = std::env::temp_dir().join(format!("quantova-tools-demo-{}", std::process::id()));
+    fs::create_dir_all(&demo_dir)?;
+
+    let result = verify_cache_demo(&sig, &public_key, &secret_key)
+        .and_then(|()| sequence_guard_demo(&demo_dir, &public_key))
+        .and_then(|()| json_sign_demo(&sig, &public_key, &secret_key))
+        .and_then(|()| key_file_demo(&demo_dir, &public_key, &secret_key))
+        .and_then(|()| event_log_demo(&demo_dir, &public_key, &secret_key))
+        .and_then(|()| attestation_demo())
+        .and_then(|()| bulk_keygen_demo());
+
+    let _ = fs::remove_dir_all(&demo_dir);
+    result
+}
+
+fn verify_cache_demo(sig: &Sig, public_key: &sig::PublicKey, secret_key: &sig::SecretKey) -> Result<(), QuantovaError> {
+    let message = b"Quantova cached verification";
+    let signature = sig.sign(message, secret_key)?;
+    let mut cache = VerifyCache::new(128);
+    for _ in 0..2 {
+        verify_cache::cached_verify(&mut cache, sig, message, &signature, public_key);
+    }
+    println!("Repeated verification served from cache ({} entry).", cache.len());
+    Ok(())
+}
+
+fn sequence_guard_demo(demo_dir: &Path, public_key: &sig::PublicKey) -> Result<(), QuantovaError> {
+    let sequence_path = demo_dir.join("sequence_guard.txt");
+    let mut guard = SequenceGuard::load(&sequence_path)?;
+    let outcomes = [1, 2, 2].map(|sequence| (sequence, guard.accept(public_key.as_ref(), sequence)));
+    for (sequence, accepted) in outcomes {
+        println!("Sequence number {}: {}", sequence, if accepted { "accepted" } else { "rejected" });
+    }
+    let last = SequenceGuard::load(&sequence_path)?.last_accepted(public_key.as_ref());
+    println!("After a reload the last accepted sequence number is {:?}.", last);
+    Ok(())
+}
+
+fn json_sign_demo(sig: &Sig, public_key: &sig::PublicKey, secret_key: &sig::SecretKey) -> Result<(), QuantovaError> {
+    let document = serde_json::json!({ "action": "login", "user": "alice", "nonce": 42 });
+    let reordered = serde_json::json!({ "nonce": 42, "user": "alice", "action": "login" });
+    let json_signature = json_sign::sign_json(&document, sig, secret_key)?;
+    println!("Canonical JSON: {}", json_sign::canonicalize(&document));
+    if json_sign::verify_json(&reordered, &json_signature, sig, public_key) {
+        println!("Signed JSON verified after reordering its keys.");
+    } else {
+        println!("Signed JSON verification failed!");
+    }
+    Ok(())
+}
+
+/// Writes the public key as binary and hex files, decodes the hex one back and covers both
+/// with a signed manifest.
+fn key_file_demo(demo_dir: &Path, public_key: &sig::PublicKey, secret_key: &sig::SecretKey) -> Result<(), QuantovaError> {
+    let public_key_bin = demo_dir.join("public_key.bin");
+    let public_key_hex = demo_dir.join("public_key.hex");
+    fs::write(&public_key_bin, public_key.as_ref())?;
+    fs::write(&public_key_hex, hex::encode(public_key.as_ref()))?;
+
+    match key_file::decode_key_file(&public_key_hex, KeyEncoding::Hex, public_key.len()) {
+        Ok(bytes) if public_keys_equal(&bytes, public_key.as_ref()) => println!("Hex public key file decoded and matches."),
+        Ok(_) => println!("Decoded hex public key does not match!"),
+        Err(e) => eprintln!("Failed to decode public key file: {}", e),
+    }
+
+    let files = [public_key_bin, public_key_hex];
+    let mut signed = manifest::sign_files(&files, secret_key, DEMO_ALGORITHM)?;
+    let report = manifest::verify_files(&signed, public_key)?;
+    if report.is_ok() {
+        println!("Signed manifest covering {} files verified.", files.len());
+    } else {
+        println!("Files changed since signing: {:?}", report.mismatches);
+    }
+    signed.signature[0] ^= 1;
+    if let Err(e) = manifest::verify_files(&signed, public_key) {
+        println!("Manifest with a modified signature rejected: {}", e);
+    }
+    Ok(())
+}
+
+/// Signs an append-only event log chunk by chunk, reopening it part way through to show that
+/// signing resumes from the stored chain.
+fn event_log_demo(demo_dir: &Path, public_key: &sig::PublicKey, secret_key: &sig::SecretKey) -> Result<(), QuantovaError> {
+    let log_path = &demo_dir.join("auth_events.log");
+    log_signer::remove_log(log_path)?;
+    let mut signer = LogSigner::open(log_path, DEMO_ALGORITHM, secret_key)?;
+    signer.append(b"login alice\n")?;
+    signer.append(b"rotate-key alice\n")?;
+    let mut resumed = LogSigner::open(log_path, DEMO_ALGORITHM, secret_key)?;
+    resumed.append(b"logout alice\n")?;
+    let chunks = log_signer::verify_log(log_path, DEMO_ALGORITHM, public_key)?;
+    println!("Signed event log verified ({} chunks).", chunks);
+    Ok(())
+}
+
+/// Collects independent signatures over one message. Unlike threshold shares, each attester
+/// holds a complete key of their own and may use a different algorithm.
+fn attestation_demo() -> Result<(), QuantovaError> {
+    let message = b"Hello, Quantum World!";
+    let mut attestations = MultiSig::new();
+    for algorithm in [AlgorithmId::Dilithium2, AlgorithmId::Falcon512, AlgorithmId::MlDsa65] {
+        let sig = Sig::new(algorithm.to_sig().expect("Attesters use signature algorithms."))?;
+        let (public_key, secret_key) = keygen::generate_sig_keypair(&sig)?;
+        let signature = sig.sign(message, &secret_key)?;
+        attestations.push(algorithm, public_key.into_vec(), signature.into_vec());
+    }
+    attestations.verify_all(message)?;
+    println!("All 3 attestations verified.");
+    let altered = b"Hello, Quantum World?";
+    if let Err(e) = attestations.verify_all(altered) {
+        println!("Altered message rejected: {}", e);
+    }
+    let valid = attestations.verify_threshold_of(message, 2)?;
+    println!("{} of 3 attestations valid, 2 required.", valid);
+    Ok(())
+}
+
+/// Provisions a batch of identities at once, then starts a large batch and cancels it from
+/// another thread, as a shutdown handler would.
+fn bulk_keygen_demo() -> Result<(), QuantovaError> {
+    match keygen::generate_keypairs(8, DEMO_ALGORITHM, &CancellationToken::new()) {
+        Ok(keypairs) => {
+            let distinct: HashSet<&[u8]> = keypairs.iter().map(|(public_key, _)| public_key.as_ref()).collect();
+            let key_ids: HashSet<_> = keypairs.iter().map(|(public_key, _)| key_id(public_key.as_ref())).collect();
+            println!(
+                "Provisioned {} identities, {} distinct public keys, {} distinct key IDs.",
+                keypairs.len(),
+                distinct.len(),
+                key_ids.len()
+            );
+        }
+        Err(e) => eprintln!("Bulk provisioning failed: {}", e),
+    }
+
+    let cancel = CancellationToken::new();
+    let requested = 10_000;
+    let outcome = thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(Duration::from_millis(5));
+            cancel.cancel();
+        });
+        keygen::generate_keypairs(requested, DEMO_ALGORITHM, &cancel)
+    });
+    match outcome {
+        Err(BatchError::Cancelled { completed }) => {
+            println!("Provisioning cancelled after {} of {} identities.", completed.len(), requested)
+        }
+        Ok(keypairs) => println!("Provisioning finished all {} identities before the cancel.", keypairs.len()),
+        Err(e) => eprintln!("Bulk provisioning failed: {}", e),
+    }
+    Ok(())
+}
@@ -1,87 +1,671 @@
-use oqs::sig::{Algorithm, Sig, Signature, PublicKey, SecretKey};
+use crate::algorithm::AlgorithmId;
+use crate::authentication;
+use crate::config::ThresholdConfig;
+use crate::keygen::{self, KeygenError};
+use crate::keys;
+use crate::secret::ProtectedSecretKey;
+use crate::shamir::{self, ShamirError, MAX_TOTAL_SHARES};
+use crate::vss::{self, VssError};
+use oqs::sig::{Algorithm, Sig, Signature, PublicKey};
+use ring::digest::{self, SHA256};
 use std::collections::HashMap;
-use rand::random;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use zeroize::{Zeroize, Zeroizing};
 
-const THRESHOLD: usize = 3; // Minimum number of shares required
-const TOTAL_SHARES: usize = 5; // Total number of shares
+const SHARE_FILE_VERSION: u8 = 1;
+/// Version byte, share index byte and big-endian u32 share length.
+const SHARE_FILE_HEADER_LEN: usize = 6;
+const SHARE_FILE_CHECKSUM_LEN: usize = 32;
+
+#[derive(Debug)]
+pub enum ThresholdError {
+    /// The parameters do not satisfy 1 <= threshold <= total <= 255.
+    InvalidParameters { threshold: usize, total: usize },
+    Keygen(KeygenError),
+    /// Fewer shares than the threshold were supplied; Shamir sharing reveals nothing then.
+    InsufficientShares { required: usize, provided: usize },
+    /// A share is empty, has x = 0, repeats another share's x, or differs in length.
+    MalformedShare(usize),
+    /// The algorithm's secret key length differs from one call to the next, so shares of a
+    /// key could not be checked against it.
+    VariableSecretKeyLength { expected: usize, actual: usize },
+    /// Reconstructed bytes are not a valid secret key for the scheme.
+    InvalidReconstructedKey { expected: usize, actual: usize },
+    /// The reconstructed key does not produce signatures that verify under the public key.
+    ReconstructedKeyMismatch,
+    Backend(oqs::Error),
+    Vss(VssError),
+    Io(io::Error),
+    /// The quorum's signature does not verify under the original public key.
+    InvalidSignature,
+}
+
+impl fmt::Display for ThresholdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdError::InvalidParameters { threshold, total } => write!(
+                f,
+                "a {}-of-{} sharing is invalid: need 1 <= threshold <= total <= {}",
+                threshold, total, MAX_TOTAL_SHARES
+            ),
+            ThresholdError::Keygen(e) => write!(f, "{}", e),
+            ThresholdError::InsufficientShares { required, provided } => {
+                write!(f, "{} shares are required to reconstruct the key, got {}", required, provided)
+            }
+            ThresholdError::MalformedShare(index) => write!(f, "share {} is malformed", index),
+            ThresholdError::VariableSecretKeyLength { expected, actual } => {
+                write!(f, "secret key length changed from {} to {} bytes between calls", expected, actual)
+            }
+            ThresholdError::InvalidReconstructedKey { expected, actual } => {
+                write!(f, "reconstructed secret key is {} bytes, expected {}", actual, expected)
+            }
+            ThresholdError::ReconstructedKeyMismatch => {
+                write!(f, "reconstructed secret key does not match the public key")
+            }
+            ThresholdError::Backend(e) => write!(f, "signature backend error: {}", e),
+            ThresholdError::Vss(e) => write!(f, "{}", e),
+            ThresholdError::Io(e) => write!(f, "share file error: {}", e),
+            ThresholdError::InvalidSignature => write!(f, "threshold signature does not verify"),
+        }
+    }
+}
+
+impl From<KeygenError> for ThresholdError {
+    fn from(e: KeygenError) -> Self {
+        ThresholdError::Keygen(e)
+    }
+}
+
+impl From<ShamirError> for ThresholdError {
+    fn from(e: ShamirError) -> Self {
+        match e {
+            ShamirError::InvalidParameters { threshold, total } => ThresholdError::InvalidParameters { threshold, total },
+            ShamirError::InsufficientShares { required, provided } => {
+                ThresholdError::InsufficientShares { required, provided }
+            }
+            ShamirError::MalformedShare(index) => ThresholdError::MalformedShare(index),
+        }
+    }
+}
+
+impl From<io::Error> for ThresholdError {
+    fn from(e: io::Error) -> Self {
+        ThresholdError::Io(e)
+    }
+}
+
+impl From<VssError> for ThresholdError {
+    fn from(e: VssError) -> Self {
+        ThresholdError::Vss(e)
+    }
+}
+
+impl From<oqs::Error> for ThresholdError {
+    fn from(e: oqs::Error) -> Self {
+        ThresholdError::Backend(e)
+    }
+}
+
+/// Why a share file was rejected by [`load_shares`].
+#[derive(Debug)]
+pub enum ShareFileError {
+    /// The file is shorter (or longer) than its header says; typically a partial copy.
+    Truncated { expected: usize, actual: usize },
+    UnsupportedVersion(u8),
+    ChecksumMismatch,
+    /// Another file already held a share with this index.
+    DuplicateIndex(usize),
+    /// The file could not be read at all.
+    Io(io::Error),
+}
+
+impl fmt::Display for ShareFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareFileError::Truncated { expected, actual } => {
+                write!(f, "file is {} bytes but its header describes {}", actual, expected)
+            }
+            ShareFileError::UnsupportedVersion(version) => write!(f, "unsupported share file version {}", version),
+            ShareFileError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            ShareFileError::DuplicateIndex(index) => write!(f, "share {} was already loaded from another file", index),
+            ShareFileError::Io(e) => write!(f, "could not read file: {}", e),
+        }
+    }
+}
+
+/// Shares read by [`load_shares`], plus every share file that was rejected and why.
+struct LoadedShares {
+    shares: HashMap<usize, Zeroizing<Vec<u8>>>,
+    rejected: Vec<(PathBuf, ShareFileError)>,
+}
+
+/// Lays out a share file as `version || index || u32 len || share || SHA-256(all before)`.
+fn encode_share_file(index: usize, share: &[u8]) -> Zeroizing<Vec<u8>> {
+    let mut file = Zeroizing::new(Vec::with_capacity(SHARE_FILE_HEADER_LEN + share.len() + SHARE_FILE_CHECKSUM_LEN));
+    file.push(SHARE_FILE_VERSION);
+    file.push(index as u8);
+    file.extend_from_slice(&(share.len() as u32).to_be_bytes());
+    file.extend_from_slice(share);
+    let checksum = digest::digest(&SHA256, &file);
+    file.extend_from_slice(checksum.as_ref());
+    file
+}
+
+fn decode_share_file(file: &[u8]) -> Result<(usize, Zeroizing<Vec<u8>>), ShareFileError> {
+    if file.len() < SHARE_FILE_HEADER_LEN {
+        return Err(ShareFileError::Truncated { expected: SHARE_FILE_HEADER_LEN, actual: file.len() });
+    }
+    if file[0] != SHARE_FILE_VERSION {
+        return Err(ShareFileError::UnsupportedVersion(file[0]));
+    }
+    let share_len = u32::from_be_bytes([file[2], file[3], file[4], file[5]]) as usize;
+    let expected = SHARE_FILE_HEADER_LEN + share_len + SHARE_FILE_CHECKSUM_LEN;
+    if file.len() != expected {
+        return Err(ShareFileError::Truncated { expected, actual: file.len() });
+    }
+    let (payload, checksum) = file.split_at(SHARE_FILE_HEADER_LEN + share_len);
+    if digest::digest(&SHA256, payload).as_ref() != checksum {
+        return Err(ShareFileError::ChecksumMismatch);
+    }
+    Ok((file[1] as usize, Zeroizing::new(payload[SHARE_FILE_HEADER_LEN..].to_vec())))
+}
+
+/// Reads every `share_*.bin` file in `dir`. Files that cannot be read or fail their checks
+/// are listed in [`LoadedShares::rejected`] instead of being dropped silently; only I/O
+/// errors on the directory itself abort the load.
+fn load_shares(dir: &Path) -> io::Result<LoadedShares> {
+    let mut loaded = LoadedShares { shares: HashMap::new(), rejected: Vec::new() };
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    paths.retain(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("share_") && name.ends_with(".bin"))
+    });
+    paths.sort();
+    for path in paths {
+        let file = match fs::read(&path) {
+            Ok(file) => Zeroizing::new(file),
+            Err(e) => {
+                loaded.rejected.push((path, ShareFileError::Io(e)));
+                continue;
+            }
+        };
+        match decode_share_file(&file) {
+            Ok((index, _)) if loaded.shares.contains_key(&index) => {
+                loaded.rejected.push((path, ShareFileError::DuplicateIndex(index)));
+            }
+            Ok((index, share)) => {
+                loaded.shares.insert(index, share);
+            }
+            Err(e) => loaded.rejected.push((path, e)),
+        }
+    }
+    Ok(loaded)
+}
 
 struct QuantumSafeThreshold {
+    algorithm: Algorithm,
     public_key: PublicKey,
-    secret_key: SecretKey,
+    secret_key: ProtectedSecretKey,
+    threshold: usize,
+    total: usize,
 }
 
 impl QuantumSafeThreshold {
-    fn new() -> Self {
-        let sig = Sig::new(Algorithm::Dilithium2).unwrap();
-        let (public_key, secret_key) = sig.keypair().unwrap();
+    // Generate a Dilithium2 key pair to be shared as `threshold`-of-`total`
+    fn with_params(threshold: usize, total: usize) -> Result<Self, ThresholdError> {
+        Self::with_algorithm(Algorithm::Dilithium2, threshold, total)
+    }
+
+    // Generate a key pair for `algorithm` to be shared as `threshold`-of-`total`. The same
+    // algorithm is used to restore, sign and verify, so every share must come from this key
+    fn with_algorithm(algorithm: Algorithm, threshold: usize, total: usize) -> Result<Self, ThresholdError> {
+        shamir::validate_params(threshold, total)?;
+        let sig = Sig::new(algorithm)?;
+        let (public_key, secret_key) = keygen::generate_sig_keypair(&sig)?;
+        // Shares are split byte by byte, so the key must have the length the algorithm reports
+        if secret_key.as_ref().len() != sig.length_secret_key() {
+            return Err(ThresholdError::VariableSecretKeyLength {
+                expected: sig.length_secret_key(),
+                actual: secret_key.as_ref().len(),
+            });
+        }
+        let secret_key = ProtectedSecretKey::new(secret_key);
         println!("\n Quantum-safe key pair generated.\nPublic Key: {:?}\nSecret Key: {:?}\n", public_key, secret_key);
-        Self { public_key, secret_key }
+        Ok(Self { algorithm, public_key, secret_key, threshold, total })
     }
 
-    // Split the private key into shares (dummy implementation)
-    fn split_private_key(&self) -> HashMap<usize, Vec<u8>> {
-        let mut shares = HashMap::new();
-        for i in 0..TOTAL_SHARES {
-            let random_bytes: Vec<u8> = (0..self.secret_key.as_ref().len()).map(|_| random()).collect();
-            shares.insert(i, random_bytes);
-            println!(" Key share {} generated: {:?}", i + 1, shares.get(&i).unwrap());
+    // Split the private key into `total` Shamir shares over GF(256) (see the shamir module)
+    fn split_private_key(&self) -> HashMap<usize, Zeroizing<Vec<u8>>> {
+        let shares = shamir::split(self.secret_key.expose().as_ref(), self.threshold, self.total)
+            .expect("Parameters were validated when the key was generated");
+        let mut indices: Vec<usize> = shares.keys().copied().collect();
+        indices.sort_unstable();
+        for i in indices {
+            println!(" Key share {} generated (fingerprint {})", i + 1, keys::fingerprint(&shares[&i]));
         }
         shares
     }
 
-    // Generate a partial signature using a key share
-    fn partial_sign(&self, message: &[u8]) -> Signature {
-        let sig = Sig::new(Algorithm::Dilithium2).unwrap();
-        let signature = sig.sign(message, &self.secret_key).unwrap();
-        println!("\n Partial signature created: {:?}", signature);
-        signature
+    // Combine shares produced by split_private_key with Lagrange interpolation at x = 0.
+    // Shamir sharing cannot tell a wrong reconstruction from a right one, so callers should
+    // check the result against the public key with restore_secret_key
+    fn reconstruct_private_key(&self, shares: &HashMap<usize, Zeroizing<Vec<u8>>>) -> Result<Zeroizing<Vec<u8>>, ThresholdError> {
+        Ok(shamir::reconstruct(shares, self.threshold)?)
     }
 
-    // Aggregate partial signatures
-    fn aggregate_signatures(&self, partial_sigs: Vec<Signature>) -> Signature {
-        println!("Aggregating partial signatures...");
-        partial_sigs[0].clone()
+    // Split the private key and write one `share_{n}.bin` file per shareholder, numbered
+    // from 1 like the printed key shares. Share files are readable by the owner only
+    fn save_shares(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        for (index, share) in self.split_private_key() {
+            authentication::write_private_file(&dir.join(format!("share_{}.bin", index + 1)), &encode_share_file(index, &share))?;
+        }
+        Ok(())
     }
 
-    // Verify the final aggregated signature
-    fn verify_signature(&self, message: &[u8], signature: &Signature) -> bool {
-        let sig = Sig::new(Algorithm::Dilithium2).unwrap();
-        let result = sig.verify(message, signature, &self.public_key).is_ok();
-        if result {
-            println!("✅ Signature verification successful!");
-        } else {
-            println!("❌ Signature verification failed!");
+    // Rotate shares without changing the key (see shamir::refresh). Leaked old shares become
+    // useless once every holder has refreshed
+    fn refresh_shares(&self, shares: &HashMap<usize, Zeroizing<Vec<u8>>>) -> HashMap<usize, Zeroizing<Vec<u8>>> {
+        shamir::refresh(shares, self.threshold)
+    }
+
+    // Deal the private key as verifiable shares: besides the shares, publish commitments
+    // that let every shareholder check its own share (see the vss module)
+    fn split_verifiable(&self) -> (HashMap<usize, Zeroizing<Vec<u8>>>, vss::Commitments) {
+        vss::deal(self.secret_key.expose().as_ref(), self.threshold, self.total)
+    }
+
+    // Turn reconstructed secret key bytes back into a usable key, signing a test message to
+    // confirm it matches the public key before declaring reconstruction successful
+    fn restore_secret_key(&self, reconstructed: &[u8]) -> Result<ProtectedSecretKey, ThresholdError> {
+        let sig = Sig::new(self.algorithm)?;
+        let expected = self.secret_key.expose().as_ref().len();
+        if sig.length_secret_key() != expected {
+            return Err(ThresholdError::VariableSecretKeyLength { expected, actual: sig.length_secret_key() });
         }
-        result
+        let secret_key = sig
+            .secret_key_from_bytes(reconstructed)
+            .filter(|_| reconstructed.len() == expected)
+            .ok_or(ThresholdError::InvalidReconstructedKey { expected, actual: reconstructed.len() })?
+            .to_owned();
+        if !authentication::validate_keypair(&sig, &self.public_key, &secret_key) {
+            return Err(ThresholdError::ReconstructedKeyMismatch);
+        }
+        Ok(ProtectedSecretKey::new(secret_key))
+    }
+
+    // Dilithium has no signature aggregation, so the quorum's shares are combined on the key
+    // side instead: reconstruct the secret key, check it against the public key, and produce
+    // a single ordinary detached signature with it. The reconstructed bytes and the restored
+    // key are zeroized as soon as the signature exists; the returned Signature holds only
+    // signature bytes
+    fn sign_with_quorum(
+        &self,
+        message: &[u8],
+        shares: &HashMap<usize, Zeroizing<Vec<u8>>>,
+    ) -> Result<Signature, ThresholdError> {
+        let mut reconstructed = self.reconstruct_private_key(shares)?;
+        self.sign_with_reconstructed(message, &mut reconstructed)
+    }
+
+    // Sign with reconstructed secret key bytes, wiping them in place whether or not the key
+    // checks out; the restored key is dropped (and zeroized) before returning
+    fn sign_with_reconstructed(&self, message: &[u8], reconstructed: &mut [u8]) -> Result<Signature, ThresholdError> {
+        let signature = self.restore_secret_key(reconstructed).and_then(|secret_key| {
+            let sig = Sig::new(self.algorithm)?;
+            Ok(sig.sign(message, secret_key.expose())?)
+        });
+        reconstructed.zeroize();
+        signature
+    }
+
+    // Verify the final aggregated signature against the original public key
+    fn verify_signature(&self, message: &[u8], signature: &Signature) -> Result<(), ThresholdError> {
+        let sig = Sig::new(self.algorithm)?;
+        sig.verify(message, signature, &self.public_key)
+            .map_err(|_| ThresholdError::InvalidSignature)
+    }
+}
+
+/// Splits the key, signs with the first `threshold` shares and verifies the result.
+fn sign_with_first_quorum(threshold: &QuantumSafeThreshold, message: &[u8]) -> Result<(), ThresholdError> {
+    let quorum: HashMap<usize, Zeroizing<Vec<u8>>> =
+        threshold.split_private_key().into_iter().filter(|(i, _)| *i < threshold.threshold).collect();
+    let signature = threshold.sign_with_quorum(message, &quorum)?;
+    threshold.verify_signature(message, &signature)
+}
+
+/// Saves shares to `dir`, damages two files, loads them back and reconstructs from the rest.
+fn persist_shares_demo(threshold: &QuantumSafeThreshold, dir: &Path) -> Result<(), ThresholdError> {
+    threshold.save_shares(dir)?;
+    // Only damage shares the quorum can spare, so a t-of-t sharing still reconstructs
+    let spare = threshold.total - threshold.threshold;
+    if spare >= 1 {
+        let truncated = dir.join("share_2.bin");
+        let bytes = fs::read(&truncated)?;
+        fs::write(&truncated, &bytes[..bytes.len() / 2])?;
+    }
+    if spare >= 2 {
+        let corrupted = dir.join(format!("share_{}.bin", threshold.total));
+        let mut bytes = fs::read(&corrupted)?;
+        bytes[SHARE_FILE_HEADER_LEN] ^= 0x01;
+        fs::write(&corrupted, &bytes)?;
+    }
+
+    let loaded = load_shares(dir)?;
+    fs::remove_dir_all(dir)?;
+    for (path, e) in &loaded.rejected {
+        println!(" Rejected {}: {}", path.display(), e);
     }
+    println!(" Loaded {} intact share files", loaded.shares.len());
+    let restored = threshold.reconstruct_private_key(&loaded.shares).and_then(|key| threshold.restore_secret_key(&key))?;
+    println!("✅ Key reconstructed from share files: {:?}", restored);
+    Ok(())
 }
 
-pub fn threshold() {
+/// Runs the threshold demo with the `t`-of-`n` sharing from the loaded config.
+pub fn threshold(config: &ThresholdConfig) -> Result<(), ThresholdError> {
     let message = b"Hello, Quantum World!";
     println!("\n Original Message: {}\n", String::from_utf8_lossy(message));
-    let threshold = QuantumSafeThreshold::new();
+    let threshold = QuantumSafeThreshold::with_params(config.t, config.n)?;
 
     // Step 1: Split Private Key into Shares
     println!("\n  Splitting private key into shares...");
     let shares = threshold.split_private_key();
     println!(" Total shares generated: {}\n", shares.len());
 
-    // Step 2: Generate Partial Signatures
-    let mut partial_sigs = Vec::new();
-    println!(" Generating partial signatures...");
-    for (i, _) in shares.iter().take(THRESHOLD) {
-        let partial_sig = threshold.partial_sign(message);
-        println!("Partial Signature {}: {:?}", i + 1, partial_sig);
-        partial_sigs.push(partial_sig);
-    }
+    // Step 2: Gather a Quorum
+    // Only `threshold` of the shareholders need to take part.
+    let quorum: HashMap<usize, Zeroizing<Vec<u8>>> =
+        shares.iter().filter(|(i, _)| **i >= threshold.total - threshold.threshold).map(|(i, share)| (*i, share.clone())).collect();
+    let mut holders: Vec<usize> = quorum.keys().map(|i| i + 1).collect();
+    holders.sort_unstable();
+    println!(" Shareholders {:?} take part ({} of {} required).", holders, quorum.len(), threshold.threshold);
 
-    // Step 3: Aggregate Partial Signatures
-    println!("\n Aggregating partial signatures...");
-    let aggregated_signature = threshold.aggregate_signatures(partial_sigs);
+    // Step 3: Sign with the Quorum
+    println!("\n Reconstructing the key from the quorum and signing...");
+    let aggregated_signature = threshold.sign_with_quorum(message, &quorum)?;
     println!(" Aggregated Signature: {:?}\n", aggregated_signature);
 
     // Step 4: Verify Aggregated Signature
     println!(" Verifying aggregated signature...");
-    threshold.verify_signature(message, &aggregated_signature);
+    threshold.verify_signature(message, &aggregated_signature)?;
+    println!("✅ Signature verification successful!");
+
+    // Step 5: Check Reconstruction Boundaries
+    // Any `threshold` shares interpolate back to the key; fewer are refused outright, and a
+    // truncated buffer shows the rejection path of the restore check.
+    println!("\n Checking reconstruction from fewer than {} shares...", threshold.threshold);
+    let too_few: HashMap<usize, Zeroizing<Vec<u8>>> = quorum.into_iter().take(threshold.threshold - 1).collect();
+    if let Err(e) = threshold.sign_with_quorum(message, &too_few) {
+        println!(" Signing with {} shares refused: {}", too_few.len(), e);
+    }
+    let secret_key = threshold.secret_key.expose().as_ref();
+    if let Err(e) = threshold.restore_secret_key(&secret_key[1..]) {
+        println!(" Truncated reconstruction rejected: {}", e);
+    }
+
+    // Step 6: Refresh Shares Proactively
+    // After a refresh the key is unchanged, but an old share combined with new ones no
+    // longer reconstructs it.
+    println!("\n Refreshing shares...");
+    let refreshed = threshold.refresh_shares(&shares);
+    let post_refresh: HashMap<usize, Zeroizing<Vec<u8>>> =
+        refreshed.iter().filter(|(i, _)| **i < threshold.threshold).map(|(i, share)| (*i, share.clone())).collect();
+    threshold.restore_secret_key(&threshold.reconstruct_private_key(&post_refresh)?)?;
+    println!("✅ Refreshed quorum reconstructs the same key");
+    let mut mixed = post_refresh;
+    mixed.insert(0, shares[&0].clone());
+    if let Err(e) = threshold.reconstruct_private_key(&mixed).and_then(|key| threshold.restore_secret_key(&key)) {
+        println!(" Old share 1 mixed with refreshed shares rejected: {}", e);
+    }
+
+    // Step 7: Verifiable Dealing
+    // Each shareholder checks its share against the dealer's commitments before accepting
+    // it; a share altered in transit (or by a dishonest dealer) is caught.
+    println!("\n Dealing verifiable shares...");
+    let (verifiable_shares, commitments) = threshold.split_verifiable();
+    if let Some((&index, _)) = verifiable_shares.iter().find(|(i, share)| !vss::verify_share(**i, share, &commitments)) {
+        return Err(VssError::InvalidShare(index).into());
+    }
+    println!(
+        "✅ {} shares checked against {} commitments",
+        verifiable_shares.len(),
+        commitments.rows.iter().map(Vec::len).sum::<usize>()
+    );
+    let mut tampered = verifiable_shares.clone();
+    if let Some(share) = tampered.get_mut(&0) {
+        share[1] ^= 0x01;
+    }
+    if let Err(e) = vss::reconstruct(&tampered, &commitments) {
+        println!(" Tampered share 1 rejected: {}", e);
+    }
+    let verifiable_quorum: HashMap<usize, Zeroizing<Vec<u8>>> =
+        verifiable_shares.into_iter().take(threshold.threshold).collect();
+    threshold.restore_secret_key(&vss::reconstruct(&verifiable_quorum, &commitments)?)?;
+    println!("✅ Key reconstructed from {} verified shares", verifiable_quorum.len());
+
+    // Step 8: Persist Shares for Custody
+    // One file per shareholder; a partially copied file and a corrupted one are reported,
+    // and the remaining shares still reconstruct the key.
+    println!("\n Saving shares to individual files...");
+    persist_shares_demo(&threshold, &std::env::temp_dir().join(format!("quantova-threshold-shares-{}", std::process::id())))?;
+
+    // Step 9: Other Quorum Sizes
+    // The same split / reconstruct / sign path works for any valid threshold and share count.
+    println!("\n Running other quorum sizes...");
+    for (t, n) in [(2, 3), (7, 10)] {
+        sign_with_first_quorum(&QuantumSafeThreshold::with_params(t, n)?, message)?;
+        println!("✅ {}-of-{} signature verified", t, n);
+    }
+    for (t, n) in [(0, 3), (4, 3)] {
+        if let Err(e) = QuantumSafeThreshold::with_params(t, n) {
+            println!(" Rejected: {}", e);
+        }
+    }
+
+    // Step 10: Other Signature Algorithms
+    // The whole split / reconstruct / sign / verify cycle is independent of the scheme.
+    println!("\n Running the cycle with other signature algorithms...");
+    for algorithm in [AlgorithmId::Dilithium3, AlgorithmId::Falcon512] {
+        let sig_algorithm = algorithm.to_sig().expect("Threshold keys use signature algorithms.");
+        let threshold = QuantumSafeThreshold::with_algorithm(sig_algorithm, config.t, config.n)?;
+        sign_with_first_quorum(&threshold, message)?;
+        println!("✅ {} {}-of-{} signature verified", algorithm.liboqs_name(), threshold.threshold, threshold.total);
+    }
+
+    Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_THRESHOLD: usize = 3;
+    const DEFAULT_TOTAL_SHARES: usize = 5;
+
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("quantova-threshold-test-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn quorum(shares: &HashMap<usize, Zeroizing<Vec<u8>>>, indices: &[usize]) -> HashMap<usize, Zeroizing<Vec<u8>>> {
+        indices.iter().map(|i| (*i, shares[i].clone())).collect()
+    }
+
+    #[test]
+    fn split_reconstruct_round_trip() {
+        let threshold = QuantumSafeThreshold::with_params(DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+        let shares = threshold.split_private_key();
+        let reconstructed = threshold.reconstruct_private_key(&quorum(&shares, &[4, 0, 2])).unwrap();
+        assert_eq!(reconstructed.as_slice(), threshold.secret_key.expose().as_ref());
+        assert!(threshold.restore_secret_key(&reconstructed).is_ok());
+    }
+
+    #[test]
+    fn threshold_minus_one_shares_fail() {
+        let threshold = QuantumSafeThreshold::with_params(DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+        let shares = threshold.split_private_key();
+        assert!(matches!(
+            threshold.reconstruct_private_key(&quorum(&shares, &[1, 3])),
+            Err(ThresholdError::InsufficientShares { required: 3, provided: 2 })
+        ));
+    }
+
+    #[test]
+    fn a_wrong_share_gives_a_wrong_key() {
+        let threshold = QuantumSafeThreshold::with_params(DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+        let mut shares = quorum(&threshold.split_private_key(), &[0, 1, 2]);
+        shares.get_mut(&2).unwrap()[1] ^= 0x80;
+        let reconstructed = threshold.reconstruct_private_key(&shares).unwrap();
+        assert_ne!(reconstructed.as_slice(), threshold.secret_key.expose().as_ref());
+        assert!(matches!(threshold.restore_secret_key(&reconstructed), Err(ThresholdError::ReconstructedKeyMismatch)));
+    }
+
+    #[test]
+    fn saved_shares_are_private_and_unreadable_files_are_reported() {
+        let threshold = QuantumSafeThreshold::with_params(DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+        let dir = scratch_dir("load");
+        threshold.save_shares(&dir).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.join("share_1.bin")).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        // A directory with a share file's name cannot be read as one
+        fs::create_dir(dir.join("share_9.bin")).unwrap();
+
+        let loaded = load_shares(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded.shares.len(), DEFAULT_TOTAL_SHARES);
+        assert_eq!(loaded.rejected.len(), 1);
+        assert!(loaded.rejected[0].0.ends_with("share_9.bin"));
+        assert!(matches!(loaded.rejected[0].1, ShareFileError::Io(_)));
+        let reconstructed = threshold.reconstruct_private_key(&loaded.shares).unwrap();
+        assert!(threshold.restore_secret_key(&reconstructed).is_ok());
+    }
+
+    #[test]
+    fn exactly_threshold_shares_sign_and_one_fewer_cannot() {
+        let threshold = QuantumSafeThreshold::with_params(DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+        let shares = threshold.split_private_key();
+        let message = b"quorum signed";
+        let signature = threshold.sign_with_quorum(message, &quorum(&shares, &[1, 2, 4])).unwrap();
+        threshold.verify_signature(message, &signature).unwrap();
+        assert!(matches!(
+            threshold.verify_signature(b"quorum signed?", &signature),
+            Err(ThresholdError::InvalidSignature)
+        ));
+        assert!(matches!(
+            threshold.sign_with_quorum(message, &quorum(&shares, &[1, 2])),
+            Err(ThresholdError::InsufficientShares { required: DEFAULT_THRESHOLD, provided: 2 })
+        ));
+    }
+
+    #[test]
+    fn two_of_three_and_seven_of_ten_sign() {
+        for (t, n) in [(2, 3), (7, 10)] {
+            let threshold = QuantumSafeThreshold::with_params(t, n).unwrap();
+            assert_eq!(threshold.split_private_key().len(), n);
+            sign_with_first_quorum(&threshold, b"configurable quorum").unwrap();
+        }
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        for (t, n) in [(0, 3), (4, 3), (2, MAX_TOTAL_SHARES + 1)] {
+            assert!(matches!(
+                QuantumSafeThreshold::with_params(t, n),
+                Err(ThresholdError::InvalidParameters { threshold, total }) if threshold == t && total == n
+            ));
+        }
+    }
+
+    #[test]
+    fn refreshed_quorum_reconstructs_but_mixed_shares_do_not() {
+        let threshold = QuantumSafeThreshold::with_params(DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+        let shares = threshold.split_private_key();
+        let refreshed = threshold.refresh_shares(&shares);
+        let post_refresh = quorum(&refreshed, &[0, 1, 2]);
+        let reconstructed = threshold.reconstruct_private_key(&post_refresh).unwrap();
+        assert_eq!(reconstructed.as_slice(), threshold.secret_key.expose().as_ref());
+
+        let mut mixed = post_refresh;
+        mixed.insert(0, shares[&0].clone());
+        let reconstructed = threshold.reconstruct_private_key(&mixed).unwrap();
+        assert!(matches!(threshold.restore_secret_key(&reconstructed), Err(ThresholdError::ReconstructedKeyMismatch)));
+    }
+
+    #[test]
+    fn quorum_signing_wipes_the_reconstructed_key() {
+        let threshold = QuantumSafeThreshold::with_params(DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+        let shares = threshold.split_private_key();
+        let message = b"wipe after signing";
+        let mut reconstructed = threshold.reconstruct_private_key(&quorum(&shares, &[0, 2, 3])).unwrap();
+        let signature = threshold.sign_with_reconstructed(message, &mut reconstructed).unwrap();
+        assert!(reconstructed.iter().all(|&b| b == 0));
+        threshold.verify_signature(message, &signature).unwrap();
+
+        // The detached signature carries signature bytes only, no copy of the key
+        let sig = Sig::new(threshold.algorithm).unwrap();
+        assert!(signature.as_ref().len() <= sig.length_signature());
+        let key_prefix = &threshold.secret_key.expose().as_ref()[..32];
+        assert!(!signature.as_ref().windows(key_prefix.len()).any(|window| window == key_prefix));
+
+        // A key that fails the public key check is wiped too
+        let mut wrong = threshold.secret_key.expose().as_ref().to_vec();
+        wrong[0] ^= 0x01;
+        assert!(threshold.sign_with_reconstructed(message, &mut wrong).is_err());
+        assert!(wrong.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn full_cycle_with_other_algorithms() {
+        for algorithm in [Algorithm::Dilithium3, Algorithm::Falcon512] {
+            let threshold = QuantumSafeThreshold::with_algorithm(algorithm, DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+            assert_eq!(threshold.algorithm, algorithm);
+            let shares = threshold.split_private_key();
+            let reconstructed = threshold.reconstruct_private_key(&quorum(&shares, &[4, 3, 1])).unwrap();
+            assert_eq!(reconstructed.len(), Sig::new(algorithm).unwrap().length_secret_key());
+            let message = b"any scheme";
+            let signature = threshold.sign_with_quorum(message, &quorum(&shares, &[0, 1, 2])).unwrap();
+            threshold.verify_signature(message, &signature).unwrap();
+        }
+    }
+
+    #[test]
+    fn damaged_share_files_are_reported() {
+        let threshold = QuantumSafeThreshold::with_params(DEFAULT_THRESHOLD, DEFAULT_TOTAL_SHARES).unwrap();
+        let dir = scratch_dir("damaged");
+        threshold.save_shares(&dir).unwrap();
+        let truncated = dir.join("share_2.bin");
+        let bytes = fs::read(&truncated).unwrap();
+        fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+        let corrupted = dir.join("share_4.bin");
+        let mut bytes = fs::read(&corrupted).unwrap();
+        bytes[SHARE_FILE_HEADER_LEN] ^= 0x01;
+        fs::write(&corrupted, &bytes).unwrap();
+
+        let loaded = load_shares(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded.shares.len(), DEFAULT_TOTAL_SHARES - 2);
+        assert!(matches!(loaded.rejected[0].1, ShareFileError::Truncated { .. }));
+        assert!(matches!(loaded.rejected[1].1, ShareFileError::ChecksumMismatch));
+        let reconstructed = threshold.reconstruct_private_key(&loaded.shares).unwrap();
+        assert!(threshold.restore_secret_key(&reconstructed).is_ok());
+    }
+
+    #[test]
+    fn demo_runs_when_every_share_is_needed() {
+        // A t-of-t sharing has no share to spare for the tampering steps
+        threshold(&ThresholdConfig { t: 2, n: 2 }).unwrap();
+    }
+}
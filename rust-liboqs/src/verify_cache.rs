@@ -0,0 +1,126 @@
+use oqs::sig::{self, Sig};
+use ring::digest::{self, SHA256};
+use std::collections::{BTreeMap, HashMap};
+
+type CacheKey = [u8; 32];
+
+/// Least-recently-used cache of signature verification results.
+///
+/// Entries are keyed by a SHA-256 digest over the algorithm, public key, message and
+/// signature, so the same signature checked against a different key or message never
+/// reuses a cached result.
+pub struct VerifyCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<CacheKey, (bool, u64)>,
+    recency: BTreeMap<u64, CacheKey>,
+}
+
+impl VerifyCache {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "VerifyCache capacity must be non-zero.");
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn get(&mut self, key: &CacheKey) -> Option<bool> {
+        let tick = self.next_tick();
+        let (valid, last_used) = self.entries.get_mut(key)?;
+        self.recency.remove(last_used);
+        self.recency.insert(tick, *key);
+        *last_used = tick;
+        Some(*valid)
+    }
+
+    fn insert(&mut self, key: CacheKey, valid: bool) {
+        if self.entries.len() >= self.capacity
+            && let Some((_, oldest)) = self.recency.pop_first()
+        {
+            self.entries.remove(&oldest);
+        }
+        let tick = self.next_tick();
+        self.recency.insert(tick, key);
+        self.entries.insert(key, (valid, tick));
+    }
+}
+
+fn cache_key(sig: &Sig, message: &[u8], signature: &[u8], public_key: &[u8]) -> CacheKey {
+    let mut ctx = digest::Context::new(&SHA256);
+    let algorithm = format!("{:?}", sig.algorithm());
+    for part in [algorithm.as_bytes(), public_key, message, signature] {
+        ctx.update(&(part.len() as u64).to_be_bytes());
+        ctx.update(part);
+    }
+    let mut key = [0u8; 32];
+    key.copy_from_slice(ctx.finish().as_ref());
+    key
+}
+
+/// Verifies `signature` over `message`, returning a cached result when this exact
+/// (algorithm, public key, message, signature) combination has been checked before.
+pub fn cached_verify(
+    cache: &mut VerifyCache,
+    sig: &Sig,
+    message: &[u8],
+    signature: &sig::Signature,
+    public_key: &sig::PublicKey,
+) -> bool {
+    let key = cache_key(sig, message, signature.as_ref(), public_key.as_ref());
+    if let Some(valid) = cache.get(&key) {
+        return valid;
+    }
+    let valid = sig.verify(message, signature, public_key).is_ok();
+    cache.insert(key, valid);
+    valid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oqs::sig::Algorithm;
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let mut cache = VerifyCache::new(2);
+        let (a, b, c) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        cache.insert(a, true);
+        cache.insert(b, true);
+        // Touching `a` leaves `b` as the oldest entry
+        assert_eq!(cache.get(&a), Some(true));
+        cache.insert(c, false);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&b), None);
+        assert_eq!(cache.get(&a), Some(true));
+        assert_eq!(cache.get(&c), Some(false));
+    }
+
+    #[test]
+    fn a_cached_result_is_not_reused_for_another_key_or_message() {
+        let sig = Sig::new(Algorithm::Dilithium2).unwrap();
+        let (public_key, secret_key) = sig.keypair().unwrap();
+        let (other_public_key, _) = sig.keypair().unwrap();
+        let signature = sig.sign(b"message", &secret_key).unwrap();
+
+        let mut cache = VerifyCache::new(8);
+        assert!(cached_verify(&mut cache, &sig, b"message", &signature, &public_key));
+        assert!(!cached_verify(&mut cache, &sig, b"message", &signature, &other_public_key));
+        assert!(!cached_verify(&mut cache, &sig, b"other message", &signature, &public_key));
+        assert_eq!(cache.len(), 3);
+        // The original combination is still served from the cache
+        assert!(cached_verify(&mut cache, &sig, b"message", &signature, &public_key));
+        assert_eq!(cache.len(), 3);
+    }
+}
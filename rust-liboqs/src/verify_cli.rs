@@ -0,0 +1,107 @@
+use crate::algorithm::AlgorithmId;
+use crate::armor;
+use crate::config::Config;
+use oqs::sig::Sig;
+use std::fs;
+
+/// Exit code for a valid signature.
+pub const EXIT_VALID: i32 = 0;
+/// Exit code for an invalid signature.
+pub const EXIT_INVALID: i32 = 1;
+/// Exit code for bad arguments or unreadable inputs.
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "usage: verify --pub <pk.bin> --in <message> --sig <signature> [--alg <algorithm>]";
+
+struct VerifyArgs {
+    public_key: String,
+    message: String,
+    signature: String,
+    /// `--alg`, if given.
+    algorithm: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<VerifyArgs, String> {
+    let (mut public_key, mut message, mut signature, mut algorithm) = (None, None, None, None);
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let slot = match flag.as_str() {
+            "--pub" => &mut public_key,
+            "--in" => &mut message,
+            "--sig" => &mut signature,
+            "--alg" => &mut algorithm,
+            other => return Err(format!("unknown argument {}", other)),
+        };
+        *slot = Some(iter.next().ok_or_else(|| format!("{} needs a value", flag))?.clone());
+    }
+    Ok(VerifyArgs {
+        public_key: public_key.ok_or("missing --pub")?,
+        message: message.ok_or("missing --in")?,
+        signature: signature.ok_or("missing --sig")?,
+        algorithm,
+    })
+}
+
+/// Checks the signature and returns the name of the algorithm used with the verdict.
+///
+/// An armored signature file (see [`armor::armor_signature`]) is dearmored first, and its
+/// Algorithm header is used when `--alg` is not given. Otherwise the algorithm comes from
+/// `--alg` or, failing that, `default_algorithm`.
+fn verify(args: &VerifyArgs, default_algorithm: &str) -> Result<(String, bool), String> {
+    let read = |path: &str| fs::read(path).map_err(|e| format!("cannot read {}: {}", path, e));
+    let public_key_bytes = read(&args.public_key)?;
+    let message = read(&args.message)?;
+    let mut signature_bytes = read(&args.signature)?;
+
+    let mut name = args.algorithm.clone().unwrap_or_else(|| default_algorithm.to_string());
+    if let Ok(text) = std::str::from_utf8(&signature_bytes)
+        && text.trim_start().starts_with(armor::BEGIN_SIGNATURE)
+    {
+        let (armored, bytes) =
+            armor::dearmor_signature(text).map_err(|e| format!("cannot read {}: {}", args.signature, e))?;
+        match &args.algorithm {
+            Some(requested) if AlgorithmId::from_name(requested) != Some(armored) => {
+                return Err(format!("--alg {} does not match the armored {} signature", requested, armored.liboqs_name()));
+            }
+            Some(_) => {}
+            None => name = armored.liboqs_name().to_string(),
+        }
+        signature_bytes = bytes;
+    }
+
+    let algorithm = AlgorithmId::from_name(&name)
+        .and_then(AlgorithmId::to_sig)
+        .ok_or_else(|| format!("unsupported signature algorithm {}", name))?;
+    let sig = Sig::new(algorithm).map_err(|e| format!("{} is not available: {}", name, e))?;
+
+    let public_key = sig
+        .public_key_from_bytes(&public_key_bytes)
+        .ok_or_else(|| format!("{} is not a {} public key", args.public_key, name))?;
+    // A signature of the wrong size can never verify, so it is reported as invalid.
+    let Some(signature) = sig.signature_from_bytes(&signature_bytes) else {
+        return Ok((name, false));
+    };
+    let valid = sig.verify(&message, signature, public_key).is_ok();
+    Ok((name, valid))
+}
+
+/// Runs `verify --pub pk.bin --in message.dat --sig message.sig --alg dilithium3`, printing a
+/// one-line verdict and returning the process exit code. Without `--alg`, the algorithm named
+/// by an armored signature, or else the signature algorithm from the config file, is used.
+pub fn run(args: &[String], config: &Config) -> i32 {
+    let result = parse_args(args).and_then(|args| verify(&args, &config.signature).map(|verdict| (args, verdict)));
+    match result {
+        Ok((args, (algorithm, true))) => {
+            println!("VALID: {} signature over {}", algorithm, args.message);
+            EXIT_VALID
+        }
+        Ok((args, (algorithm, false))) => {
+            println!("INVALID: {} signature over {}", algorithm, args.message);
+            EXIT_INVALID
+        }
+        Err(e) => {
+            eprintln!("error: {}\n{}", e, USAGE);
+            EXIT_USAGE
+        }
+    }
+}
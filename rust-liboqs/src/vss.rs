@@ -0,0 +1,238 @@
+// Verifiable secret sharing for the threshold module.
+//
+// Plain Shamir sharing gives a shareholder no way to tell whether the dealer handed out
+// consistent shares. Here the dealer also publishes commitments to every polynomial
+// coefficient, and each shareholder checks its share against them before trusting it.
+//
+// Commitments need a prime-order group, which GF(256) is not, so this sharing works over
+// Ristretto255 instead: the secret is cut into 31-byte chunks, each chunk is a scalar, and
+// each chunk gets its own polynomial of degree threshold - 1 over the scalar field.
+//
+// The commitments are Pedersen commitments, C_k = a_k*G + b_k*H, with a second blinding
+// polynomial b, rather than Feldman's C_k = a_k*G. Feldman commitments include a_0*G, so
+// anyone who can solve discrete logarithms on the curve, including a future quantum
+// computer, could read the secret straight from them. Pedersen commitments reveal nothing
+// about the secret even to an unbounded adversary. They are only binding while discrete
+// logarithms stay hard, so a quantum dealer could cheat, but it could not leak the key
+// through the public data.
+
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::VartimeMultiscalarMul;
+use rand::random;
+use ring::digest::{self, SHA512};
+use std::collections::HashMap;
+use std::fmt;
+use zeroize::Zeroizing;
+
+/// Secret bytes per scalar; 31 bytes always fit below the group order.
+const CHUNK_LEN: usize = 31;
+const SCALAR_LEN: usize = 32;
+/// Domain separation for the second generator, whose discrete log to G must be unknown.
+const GENERATOR_H_LABEL: &[u8] = b"Quantova VSS generator H";
+
+#[derive(Debug)]
+pub enum VssError {
+    InsufficientShares { required: usize, provided: usize },
+    /// The share does not match the dealer's commitments.
+    InvalidShare(usize),
+}
+
+impl fmt::Display for VssError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VssError::InsufficientShares { required, provided } => {
+                write!(f, "{} verified shares are required, got {}", required, provided)
+            }
+            VssError::InvalidShare(index) => write!(f, "share {} does not match the dealer's commitments", index),
+        }
+    }
+}
+
+/// The dealer's public commitments: one row per secret chunk, one point per coefficient.
+#[derive(Debug, Clone)]
+pub struct Commitments {
+    pub secret_len: usize,
+    pub rows: Vec<Vec<CompressedRistretto>>,
+}
+
+impl Commitments {
+    /// Number of shares needed to reconstruct, which is the number of coefficients.
+    pub fn threshold(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
+    }
+}
+
+/// The second Pedersen generator, derived by hashing to the curve so nobody knows its
+/// discrete log with respect to the basepoint.
+fn generator_h() -> RistrettoPoint {
+    let hash = digest::digest(&SHA512, GENERATOR_H_LABEL);
+    let mut uniform = [0u8; 64];
+    uniform.copy_from_slice(hash.as_ref());
+    RistrettoPoint::from_uniform_bytes(&uniform)
+}
+
+fn random_scalar() -> Scalar {
+    let mut wide = Zeroizing::new([0u8; 64]);
+    wide[..32].copy_from_slice(&random::<[u8; 32]>());
+    wide[32..].copy_from_slice(&random::<[u8; 32]>());
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+/// Evaluates the polynomial with `coefficients` (constant term first) at `x`.
+fn eval(coefficients: &[Scalar], x: Scalar) -> Scalar {
+    coefficients.iter().rev().fold(Scalar::ZERO, |acc, c| acc * x + c)
+}
+
+/// Splits `secret` into `total` shares, any `threshold` of which reconstruct it, and returns
+/// the commitments to publish alongside them. Share `i` is `x || (f(x) || g(x)) per chunk`
+/// with x = i + 1, matching the indexing of the GF(256) shares.
+///
+/// The caller validates `1 <= threshold <= total <= 255`.
+pub fn deal(secret: &[u8], threshold: usize, total: usize) -> (HashMap<usize, Zeroizing<Vec<u8>>>, Commitments) {
+    let h = generator_h();
+    let chunks = secret.chunks(CHUNK_LEN);
+    let mut shares: Vec<Zeroizing<Vec<u8>>> = (1..=total)
+        .map(|x| {
+            let mut share = Zeroizing::new(Vec::with_capacity(1 + chunks.len() * 2 * SCALAR_LEN));
+            share.push(x as u8);
+            share
+        })
+        .collect();
+    let mut rows = Vec::with_capacity(chunks.len());
+
+    for chunk in chunks {
+        let mut constant = Zeroizing::new([0u8; SCALAR_LEN]);
+        constant[..chunk.len()].copy_from_slice(chunk);
+        let mut secret_poly = Zeroizing::new(vec![Scalar::from_bytes_mod_order(*constant)]);
+        secret_poly.extend((1..threshold).map(|_| random_scalar()));
+        let blinding_poly = Zeroizing::new((0..threshold).map(|_| random_scalar()).collect::<Vec<_>>());
+
+        rows.push(
+            secret_poly
+                .iter()
+                .zip(blinding_poly.iter())
+                .map(|(a, b)| (RistrettoPoint::mul_base(a) + b * h).compress())
+                .collect(),
+        );
+        for share in &mut shares {
+            let x = Scalar::from(share[0] as u64);
+            share.extend_from_slice(eval(&secret_poly, x).as_bytes());
+            share.extend_from_slice(eval(&blinding_poly, x).as_bytes());
+        }
+    }
+
+    let shares = shares.into_iter().enumerate().collect();
+    (shares, Commitments { secret_len: secret.len(), rows })
+}
+
+/// Checks that share `index` lies on the polynomials the dealer committed to:
+/// f(x)*G + g(x)*H must equal the sum of x^k * C_k for every chunk.
+pub fn verify_share(index: usize, share: &[u8], commitments: &Commitments) -> bool {
+    if index >= 255 || share.len() != 1 + commitments.rows.len() * 2 * SCALAR_LEN || share[0] as usize != index + 1 {
+        return false;
+    }
+    let h = generator_h();
+    let x = Scalar::from(share[0] as u64);
+    let powers: Vec<Scalar> = (0..commitments.threshold())
+        .scan(Scalar::ONE, |power, _| {
+            let current = *power;
+            *power *= x;
+            Some(current)
+        })
+        .collect();
+
+    commitments.rows.iter().zip(share[1..].chunks(2 * SCALAR_LEN)).all(|(row, pair)| {
+        let (Some(f), Some(g)) = (canonical_scalar(&pair[..SCALAR_LEN]), canonical_scalar(&pair[SCALAR_LEN..])) else {
+            return false;
+        };
+        let Some(points) = row.iter().map(CompressedRistretto::decompress).collect::<Option<Vec<_>>>() else {
+            return false;
+        };
+        RistrettoPoint::mul_base(&f) + g * h == RistrettoPoint::vartime_multiscalar_mul(&powers, &points)
+    })
+}
+
+fn canonical_scalar(bytes: &[u8]) -> Option<Scalar> {
+    let bytes: [u8; SCALAR_LEN] = bytes.try_into().ok()?;
+    Scalar::from_canonical_bytes(bytes).into()
+}
+
+/// Verifies every supplied share against `commitments`, then interpolates the secret from
+/// them. A single inconsistent share is reported rather than silently corrupting the result.
+pub fn reconstruct(
+    shares: &HashMap<usize, Zeroizing<Vec<u8>>>,
+    commitments: &Commitments,
+) -> Result<Zeroizing<Vec<u8>>, VssError> {
+    let required = commitments.threshold();
+    if shares.len() < required {
+        return Err(VssError::InsufficientShares { required, provided: shares.len() });
+    }
+    let mut points: Vec<(usize, &[u8])> = shares.iter().map(|(&index, share)| (index, share.as_slice())).collect();
+    points.sort_by_key(|&(index, _)| index);
+    if let Some(&(index, _)) = points.iter().find(|(index, share)| !verify_share(*index, share, commitments)) {
+        return Err(VssError::InvalidShare(index));
+    }
+
+    // Lagrange basis at zero: l_j = prod_{m != j} x_m / (x_m - x_j).
+    let xs: Vec<Scalar> = points.iter().map(|(_, share)| Scalar::from(share[0] as u64)).collect();
+    let basis: Vec<Scalar> = xs
+        .iter()
+        .enumerate()
+        .map(|(j, x_j)| {
+            xs.iter()
+                .enumerate()
+                .filter(|&(m, _)| m != j)
+                .fold(Scalar::ONE, |acc, (_, x_m)| acc * x_m * (x_m - x_j).invert())
+        })
+        .collect();
+
+    let mut secret = Zeroizing::new(Vec::with_capacity(commitments.rows.len() * CHUNK_LEN));
+    for chunk in 0..commitments.rows.len() {
+        let offset = 1 + chunk * 2 * SCALAR_LEN;
+        let value = points.iter().zip(&basis).fold(Scalar::ZERO, |acc, ((_, share), l)| {
+            let f = canonical_scalar(&share[offset..offset + SCALAR_LEN]).expect("Verified shares hold canonical scalars.");
+            acc + f * l
+        });
+        secret.extend_from_slice(&value.as_bytes()[..CHUNK_LEN]);
+    }
+    secret.truncate(commitments.secret_len);
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn honest_shares_verify_and_reconstruct() {
+        let secret: Vec<u8> = (0..100).map(|_| random()).collect();
+        let (shares, commitments) = deal(&secret, 3, 5);
+        assert_eq!(commitments.threshold(), 3);
+        for (index, share) in &shares {
+            assert!(verify_share(*index, share, &commitments));
+        }
+        let quorum: HashMap<usize, Zeroizing<Vec<u8>>> = shares.into_iter().filter(|(i, _)| *i >= 2).collect();
+        assert_eq!(reconstruct(&quorum, &commitments).unwrap().as_slice(), secret.as_slice());
+    }
+
+    #[test]
+    fn tampered_share_is_flagged() {
+        let (mut shares, commitments) = deal(b"verifiable secret", 2, 3);
+        shares.get_mut(&1).unwrap()[1] ^= 0x01;
+        assert!(!verify_share(1, &shares[&1], &commitments));
+        assert!(matches!(reconstruct(&shares, &commitments), Err(VssError::InvalidShare(1))));
+        // A share presented under another shareholder's index does not verify either
+        assert!(!verify_share(2, &shares[&0], &commitments));
+    }
+
+    #[test]
+    fn too_few_shares_are_refused() {
+        let (shares, commitments) = deal(b"verifiable secret", 3, 4);
+        let pair: HashMap<usize, Zeroizing<Vec<u8>>> = shares.into_iter().take(2).collect();
+        assert!(matches!(
+            reconstruct(&pair, &commitments),
+            Err(VssError::InsufficientShares { required: 3, provided: 2 })
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::authentication;
+use crate::error::QuantovaError;
+use crate::keygen;
+use oqs::kem::{self, Kem};
+use oqs::sig::{self, Sig};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use zeroize::Zeroizing;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UseCase {
+    Signing,
+    KeyExchange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Priority {
+    /// Smallest keys, signatures and ciphertexts on the wire.
+    Bandwidth,
+    /// Fastest operations.
+    Speed,
+    /// Most conservative security assumptions, at a cost in size and speed.
+    Conservative,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Sig(sig::Algorithm),
+    Kem(kem::Algorithm),
+}
+
+/// An algorithm picked by the wizard, with the tradeoffs shown to the user.
+struct Recommendation {
+    name: &'static str,
+    scheme: Scheme,
+    rationale: &'static str,
+}
+
+/// Picks an algorithm for the use case, NIST security level (1, 3 or 5) and priority.
+fn recommend(use_case: UseCase, level: u8, priority: Priority) -> Recommendation {
+    match (use_case, priority) {
+        (UseCase::Signing, Priority::Bandwidth) => {
+            let (name, algorithm) = if level == 1 {
+                ("Falcon-512", sig::Algorithm::Falcon512)
+            } else {
+                ("Falcon-1024", sig::Algorithm::Falcon1024)
+            };
+            Recommendation {
+                name,
+                scheme: Scheme::Sig(algorithm),
+                rationale: "Falcon has the most compact lattice signatures and fast verification. Signing \
+                            relies on floating-point Gaussian sampling, which is harder to implement in \
+                            constant time, and there is no level 3 parameter set, so level 3 is rounded up.",
+            }
+        }
+        (UseCase::Signing, Priority::Speed) => {
+            let (name, algorithm) = match level {
+                1 => ("Dilithium2", sig::Algorithm::Dilithium2),
+                3 => ("Dilithium3", sig::Algorithm::Dilithium3),
+                _ => ("Dilithium5", sig::Algorithm::Dilithium5),
+            };
+            Recommendation {
+                name,
+                scheme: Scheme::Sig(algorithm),
+                rationale: "Dilithium balances fast signing and verification with moderate sizes and is \
+                            simple to implement safely. Signatures are several times larger than Falcon's.",
+            }
+        }
+        (UseCase::Signing, Priority::Conservative) => {
+            let (name, algorithm) = match level {
+                1 => ("SPHINCS+-SHA2-128s", sig::Algorithm::SphincsSha2128sSimple),
+                3 => ("SPHINCS+-SHA2-192s", sig::Algorithm::SphincsSha2192sSimple),
+                _ => ("SPHINCS+-SHA2-256s", sig::Algorithm::SphincsSha2256sSimple),
+            };
+            Recommendation {
+                name,
+                scheme: Scheme::Sig(algorithm),
+                rationale: "SPHINCS+ rests only on the security of its hash function. Keys are tiny, but \
+                            signatures run to kilobytes and signing is slow.",
+            }
+        }
+        (UseCase::KeyExchange, Priority::Bandwidth | Priority::Speed) => {
+            let (name, algorithm) = match level {
+                1 => ("ML-KEM-512", kem::Algorithm::MlKem512),
+                3 => ("ML-KEM-768", kem::Algorithm::MlKem768),
+                _ => ("ML-KEM-1024", kem::Algorithm::MlKem1024),
+            };
+            Recommendation {
+                name,
+                scheme: Scheme::Kem(algorithm),
+                rationale: "ML-KEM is the NIST standard KEM: fast, with public keys and ciphertexts of \
+                            around a kilobyte. Its structured lattice is what makes it compact.",
+            }
+        }
+        (UseCase::KeyExchange, Priority::Conservative) => {
+            let (name, algorithm) = match level {
+                1 => ("FrodoKEM-640-AES", kem::Algorithm::FrodoKem640Aes),
+                3 => ("FrodoKEM-976-AES", kem::Algorithm::FrodoKem976Aes),
+                _ => ("FrodoKEM-1344-AES", kem::Algorithm::FrodoKem1344Aes),
+            };
+            Recommendation {
+                name,
+                scheme: Scheme::Kem(algorithm),
+                rationale: "FrodoKEM uses an unstructured lattice, avoiding the extra algebraic structure \
+                            ML-KEM relies on. Public keys and ciphertexts are 10-20 KB and operations are \
+                            slower.",
+            }
+        }
+    }
+}
+
+/// Prints `question` with numbered `options` until a valid choice is entered. Returns the
+/// 0-based index, or `None` at end of input.
+fn prompt(question: &str, options: &[&str]) -> Option<usize> {
+    loop {
+        println!("\n{}", question);
+        for (i, option) in options.iter().enumerate() {
+            println!("  {}. {}", i + 1, option);
+        }
+        print!("Select an option: ");
+        io::stdout().flush().unwrap();
+
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).ok()? == 0 {
+            return None;
+        }
+        match answer.trim().parse::<usize>() {
+            Ok(n) if (1..=options.len()).contains(&n) => return Some(n - 1),
+            _ => println!("❌ Invalid option. Please try again."),
+        }
+    }
+}
+
+/// Generates a key pair for the recommendation and saves it as `<algorithm>_public.key` and
+/// `<algorithm>_secret.key`, the secret key readable only by its owner on Unix. Returns the
+/// two paths.
+fn generate_and_save(recommendation: &Recommendation) -> Result<(String, String), QuantovaError> {
+    let (public_key, secret_key) = match recommendation.scheme {
+        Scheme::Sig(algorithm) => {
+            let (public_key, secret_key) = keygen::generate_sig_keypair(&Sig::new(algorithm)?)?;
+            (public_key.into_vec(), Zeroizing::new(secret_key.into_vec()))
+        }
+        Scheme::Kem(algorithm) => {
+            let (public_key, secret_key) = keygen::generate_kem_keypair(&Kem::new(algorithm)?)?;
+            (public_key.into_vec(), Zeroizing::new(secret_key.into_vec()))
+        }
+    };
+    println!(
+        "✅ Generated {} key pair: public key {} bytes, secret key {} bytes",
+        recommendation.name,
+        public_key.len(),
+        secret_key.len()
+    );
+
+    let stem = recommendation.name.to_ascii_lowercase();
+    let public_path = format!("{}_public.key", stem);
+    let secret_path = format!("{}_secret.key", stem);
+    fs::write(&public_path, &public_key)?;
+    authentication::write_private_file(Path::new(&secret_path), &secret_key)?;
+    Ok((public_path, secret_path))
+}
+
+/// Asks what the key is for, recommends an algorithm, explains why, and generates and saves
+/// a key pair.
+pub fn wizard() -> bool {
+    let Some(use_case) = prompt("What will the key be used for?", &["Signing", "Key exchange"]) else {
+        return false;
+    };
+    let use_case = [UseCase::Signing, UseCase::KeyExchange][use_case];
+
+    let Some(level) = prompt(
+        "Which security level do you need?",
+        &["Level 1 (comparable to AES-128)", "Level 3 (AES-192)", "Level 5 (AES-256)"],
+    ) else {
+        return false;
+    };
+    let level = [1, 3, 5][level];
+
+    let Some(priority) = prompt(
+        "What matters most?",
+        &["Small keys and messages", "Speed", "Conservative security assumptions"],
+    ) else {
+        return false;
+    };
+    let priority = [Priority::Bandwidth, Priority::Speed, Priority::Conservative][priority];
+
+    let recommendation = recommend(use_case, level, priority);
+    println!("\n Recommended: {}", recommendation.name);
+    println!(" {}", recommendation.rationale);
+
+    match generate_and_save(&recommendation).map_err(|e| e.with_algorithm(recommendation.name)) {
+        Ok((public_path, secret_path)) => {
+            println!(" Saved public key to {} and secret key to {}", public_path, secret_path);
+            println!(" Keep {} private.", secret_path);
+            true
+        }
+        Err(e) => {
+            println!("❌ {}", e);
+            false
+        }
+    }
+}